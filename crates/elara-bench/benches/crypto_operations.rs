@@ -2,6 +2,7 @@
 //!
 //! Comprehensive benchmarks for ELARA cryptographic operations including:
 //! - Encryption/decryption for various payload sizes
+//! - Crypto suite comparison (ChaCha20-Poly1305 vs AES-256-GCM)
 //! - Signature generation and verification
 //! - Key derivation
 //! - Identity generation
@@ -10,7 +11,7 @@ use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criteri
 use elara_bench::WIRE_PAYLOAD_SIZES;
 use elara_core::{NodeId, PacketClass, RepresentationProfile, SessionId};
 use elara_crypto::{Identity, SecureFrameProcessor};
-use elara_wire::Extensions;
+use elara_wire::{CryptoSuite, Extensions};
 
 /// Benchmark identity generation
 fn bench_identity_generate(c: &mut Criterion) {
@@ -122,6 +123,44 @@ fn bench_encrypt_decrypt_roundtrip(c: &mut Criterion) {
     group.finish();
 }
 
+/// Benchmark encryption under each supported crypto suite
+fn bench_suite_comparison(c: &mut Criterion) {
+    let mut group = c.benchmark_group("crypto/suite_encrypt");
+
+    for suite in [CryptoSuite::Suite0, CryptoSuite::Suite1] {
+        for &size in WIRE_PAYLOAD_SIZES {
+            group.throughput(Throughput::Bytes(size as u64));
+            group.bench_with_input(
+                BenchmarkId::new(format!("{:?}", suite), size),
+                &size,
+                |b, &size| {
+                    let session_id = SessionId::new(0x1234_5678_9ABC_DEF0);
+                    let node_id = NodeId::new(0xDEAD_BEEF_CAFE_BABE);
+                    let session_key = [0x42u8; 32];
+                    let mut processor =
+                        SecureFrameProcessor::with_suite(session_id, node_id, session_key, suite)
+                            .unwrap();
+                    let payload = vec![0u8; size];
+
+                    b.iter(|| {
+                        processor
+                            .encrypt_frame(
+                                black_box(PacketClass::Core),
+                                black_box(RepresentationProfile::Textual),
+                                black_box(0),
+                                black_box(Extensions::new()),
+                                black_box(&payload),
+                            )
+                            .unwrap()
+                    });
+                },
+            );
+        }
+    }
+
+    group.finish();
+}
+
 /// Benchmark signature generation (via identity)
 fn bench_signature_generation(c: &mut Criterion) {
     let identity = Identity::generate();
@@ -179,6 +218,7 @@ criterion_group!(
     bench_secure_frame_encrypt,
     bench_secure_frame_decrypt,
     bench_encrypt_decrypt_roundtrip,
+    bench_suite_comparison,
     bench_signature_generation,
     bench_signature_verification,
     bench_key_derivation,
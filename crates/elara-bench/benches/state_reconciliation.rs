@@ -123,7 +123,7 @@ fn bench_version_vector_equals(c: &mut Criterion) {
                 vv2.increment(NodeId::new(i as u64));
             }
 
-            b.iter(|| black_box(&vv1 == &vv2))
+            b.iter(|| black_box(vv1 == vv2))
         });
     }
 
@@ -20,6 +20,9 @@ pub enum ElaraError {
     #[error("Unknown event type: {0}")]
     UnknownEventType(u8),
 
+    #[error("Decompression failed: {0}")]
+    DecompressionFailed(String),
+
     // Crypto errors
     #[error("Decryption failed")]
     DecryptionFailed,
@@ -33,6 +36,15 @@ pub enum ElaraError {
     #[error("Ratchet out of sync")]
     RatchetOutOfSync,
 
+    #[error("Handshake failed: {0}")]
+    HandshakeFailed(String),
+
+    #[error("Unsupported crypto suite: {0}")]
+    UnsupportedCryptoSuite(u8),
+
+    #[error("Crypto suite mismatch: expected {expected}, got {actual}")]
+    CryptoSuiteMismatch { expected: u8, actual: u8 },
+
     // Authority errors
     #[error("Unauthorized: node {node} cannot mutate state {state}")]
     Unauthorized { node: NodeId, state: StateId },
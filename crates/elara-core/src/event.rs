@@ -4,7 +4,16 @@
 //! Each event carries source identity, target state, mutation delta,
 //! temporal intent, and authority proof.
 
-use crate::{EventId, NodeId, StateId, StateTime, TimeIntent, VersionVector};
+use crate::{
+    AuthorityScope, EventId, NodeId, PacketClass, SessionId, StateId, StateTime, TimeIntent,
+    VersionVector,
+};
+
+/// Domain separation for event authority signatures
+const EVENT_SIGNING_LABEL: &[u8] = b"ELARA_EVENT_v0";
+
+/// Domain separation for delegation link signatures
+const DELEGATION_SIGNING_LABEL: &[u8] = b"ELARA_DELEGATION_v0";
 
 /// Event type classification
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -100,6 +109,24 @@ impl EventType {
     pub fn is_core_protocol(self) -> bool {
         (self as u8) < 0x80
     }
+
+    /// Packet class events of this type travel in
+    pub fn packet_class(self) -> PacketClass {
+        match self {
+            // Group rekeys are also `SessionSync` but go out in their own
+            // Core key-exchange frames, never through this mapping
+            EventType::StateRequest
+            | EventType::StateResponse
+            | EventType::GapFill
+            | EventType::SessionSync => PacketClass::Repair,
+            EventType::VoiceFrame | EventType::VoiceMute => PacketClass::Perceptual,
+            EventType::TypingStart | EventType::TypingStop | EventType::PresenceUpdate => {
+                PacketClass::Perceptual
+            }
+            EventType::VisualKeyframe | EventType::VisualDelta => PacketClass::Perceptual,
+            _ => PacketClass::Core,
+        }
+    }
 }
 
 /// Mutation operation
@@ -255,14 +282,107 @@ impl AuthorityProof {
 }
 
 /// Link in a delegation chain
+///
+/// Signed by the delegator, granting `scope` of its own authority over one
+/// atom in one session to the delegate until `expiry` (state time). Each
+/// link may only narrow the scope of the one before it.
 #[derive(Clone, Debug)]
 pub struct DelegationLink {
     pub delegator: NodeId,
     pub delegate: NodeId,
-    pub scope: Vec<u8>, // Encoded AuthorityScope
+    pub session: SessionId,
+    pub target_state: StateId,
+    pub scope: AuthorityScope,
+    pub expiry: Option<StateTime>,
     pub signature: [u8; 64],
 }
 
+impl DelegationLink {
+    /// Create an unsigned link
+    pub fn new(
+        delegator: NodeId,
+        delegate: NodeId,
+        session: SessionId,
+        target_state: StateId,
+        scope: AuthorityScope,
+        expiry: Option<StateTime>,
+    ) -> Self {
+        DelegationLink {
+            delegator,
+            delegate,
+            session,
+            target_state,
+            scope,
+            expiry,
+            signature: [0u8; 64],
+        }
+    }
+
+    /// Canonical bytes covered by the delegator's signature
+    pub fn signing_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(DELEGATION_SIGNING_LABEL);
+        self.encode_body(&mut buf);
+        buf
+    }
+
+    /// Encode for the wire
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        self.encode_body(&mut buf);
+        buf.extend_from_slice(&self.signature);
+        buf
+    }
+
+    fn encode_body(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&self.delegator.to_bytes());
+        buf.extend_from_slice(&self.delegate.to_bytes());
+        buf.extend_from_slice(&self.session.to_bytes());
+        buf.extend_from_slice(&self.target_state.to_bytes());
+        match self.expiry {
+            Some(expiry) => {
+                buf.push(1);
+                buf.extend_from_slice(&expiry.as_micros().to_le_bytes());
+            }
+            None => buf.push(0),
+        }
+        buf.extend_from_slice(&self.scope.encode());
+    }
+
+    /// Decode from the wire, returning the link and bytes consumed
+    pub fn decode(buf: &[u8]) -> Option<(Self, usize)> {
+        let delegator = NodeId::from_bytes(buf.get(0..8)?.try_into().ok()?);
+        let delegate = NodeId::from_bytes(buf.get(8..16)?.try_into().ok()?);
+        let session = SessionId::from_bytes(buf.get(16..24)?.try_into().ok()?);
+        let target_state = StateId::from_bytes(buf.get(24..32)?.try_into().ok()?);
+        let (expiry, mut offset) = match *buf.get(32)? {
+            0 => (None, 33),
+            1 => {
+                let micros = i64::from_le_bytes(buf.get(33..41)?.try_into().ok()?);
+                (Some(StateTime::from_micros(micros)), 41)
+            }
+            _ => return None,
+        };
+        let (scope, used) = AuthorityScope::decode(buf.get(offset..)?)?;
+        offset += used;
+        let signature = buf.get(offset..offset + 64)?.try_into().ok()?;
+        offset += 64;
+
+        Some((
+            DelegationLink {
+                delegator,
+                delegate,
+                session,
+                target_state,
+                scope,
+                expiry,
+                signature,
+            },
+            offset,
+        ))
+    }
+}
+
 /// Entropy hint for divergence control
 #[derive(Clone, Copy, Debug, Default)]
 pub struct EntropyHint {
@@ -368,6 +488,34 @@ impl Event {
     pub fn absolute_time(&self, reference: StateTime) -> StateTime {
         self.time_intent.to_absolute(reference)
     }
+
+    /// Canonical bytes covered by the authority signature in `session`
+    ///
+    /// Binds the source, sequence number, target, version reference and
+    /// time intent along with the mutation, so none can be swapped under a
+    /// valid signature, and the session, so the event cannot be replayed
+    /// into another one. Events signed outside a session use
+    /// `SessionId::ZERO`.
+    pub fn signing_bytes(&self, session: SessionId) -> Vec<u8> {
+        let mut entries = self.version_ref.to_compact();
+        entries.sort_by_key(|(node, _)| node.0);
+
+        let mut buf = Vec::new();
+        buf.extend_from_slice(EVENT_SIGNING_LABEL);
+        buf.extend_from_slice(&session.to_bytes());
+        buf.push(self.event_type.to_byte());
+        buf.extend_from_slice(&self.source.to_bytes());
+        buf.extend_from_slice(&self.id.seq.to_le_bytes());
+        buf.extend_from_slice(&self.target_state.to_bytes());
+        buf.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+        for (node, count) in entries {
+            buf.extend_from_slice(&node.to_bytes());
+            buf.extend_from_slice(&count.to_le_bytes());
+        }
+        buf.extend_from_slice(&self.time_intent.ts_offset().to_le_bytes());
+        buf.extend_from_slice(&self.mutation.encode());
+        buf
+    }
 }
 
 /// Validated event - passed all checks
@@ -404,12 +552,14 @@ pub enum EventResult {
 }
 
 /// Reason for event rejection
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum RejectReason {
     /// Source not authorized
     Unauthorized,
     /// Invalid signature
     InvalidSignature,
+    /// No public key known for the source
+    UnknownSigner,
     /// Authority was revoked
     AuthorityRevoked,
     /// Causality violation
@@ -418,8 +568,12 @@ pub enum RejectReason {
     MissingDependency(EventId),
     /// Out of bounds
     OutOfBounds,
+    /// Mutation not defined under the target's delta law
+    InvalidMutation,
     /// Rate limit exceeded
     RateLimitExceeded,
+    /// Mutation would grow the atom past its `max_size`
+    StateBoundsExceeded,
     /// Entropy exceeded
     EntropyExceeded,
     /// Too late (beyond correction horizon)
@@ -488,4 +642,82 @@ mod tests {
         assert_eq!(event.target_state, target);
         assert_eq!(event.id.seq, 1);
     }
+
+    #[test]
+    fn test_signing_bytes_bind_event_fields() {
+        let event = Event::new(
+            NodeId::new(1),
+            1,
+            EventType::TextAppend,
+            StateId::new(100),
+            MutationOp::Append(b"Hello".to_vec()),
+        );
+        let base = event.signing_bytes(SessionId::new(1));
+
+        let mut version = VersionVector::new();
+        version.increment(NodeId::new(2));
+        let variants = [
+            Event {
+                source: NodeId::new(2),
+                ..event.clone()
+            },
+            Event {
+                id: EventId::new(NodeId::new(1), 9),
+                ..event.clone()
+            },
+            Event {
+                target_state: StateId::new(101),
+                ..event.clone()
+            },
+            event.clone().with_version(version),
+            event.clone().with_time_intent(TimeIntent::new(5)),
+        ];
+        for variant in variants {
+            assert_ne!(variant.signing_bytes(SessionId::new(1)), base);
+        }
+        assert_ne!(event.signing_bytes(SessionId::new(2)), base);
+    }
+
+    #[test]
+    fn test_delegation_link_roundtrip() {
+        let mut link = DelegationLink::new(
+            NodeId::new(1),
+            NodeId::new(2),
+            SessionId::new(3),
+            StateId::new(4),
+            AuthorityScope::Append,
+            Some(StateTime::from_millis(5000)),
+        );
+        link.signature = [7u8; 64];
+
+        let encoded = link.encode();
+        let (decoded, used) = DelegationLink::decode(&encoded).unwrap();
+        assert_eq!(used, encoded.len());
+        assert_eq!(decoded.delegate, link.delegate);
+        assert_eq!(decoded.session, link.session);
+        assert_eq!(decoded.target_state, link.target_state);
+        assert_eq!(decoded.scope, link.scope);
+        assert_eq!(decoded.expiry, link.expiry);
+        assert_eq!(decoded.signature, link.signature);
+        assert!(DelegationLink::decode(&encoded[..encoded.len() - 1]).is_none());
+
+        // The signature covers the scope
+        let widened = DelegationLink {
+            scope: AuthorityScope::Full,
+            ..link.clone()
+        };
+        assert_ne!(widened.signing_bytes(), link.signing_bytes());
+
+        // And the atom and session it applies to
+        let moved = DelegationLink {
+            target_state: StateId::new(5),
+            ..link.clone()
+        };
+        assert_ne!(moved.signing_bytes(), link.signing_bytes());
+        let replayed = DelegationLink {
+            session: SessionId::new(6),
+            ..link.clone()
+        };
+        assert_ne!(replayed.signing_bytes(), link.signing_bytes());
+    }
 }
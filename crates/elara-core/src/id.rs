@@ -6,7 +6,7 @@
 use std::fmt;
 
 /// Node identity - cryptographic fingerprint (truncated hash of public key)
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub struct NodeId(pub u64);
 
 impl NodeId {
@@ -3,7 +3,7 @@
 //! State atoms (ω) are the fundamental units of reality in ELARA.
 //! Each atom has identity, type, authority, versioning, and merge behavior.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use crate::{NodeId, StateId, StateTime, StateType};
 
@@ -213,6 +213,52 @@ pub enum AuthorityScope {
 }
 
 impl AuthorityScope {
+    /// Encode for delegation links
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        match self {
+            AuthorityScope::Full => buf.push(0x00),
+            AuthorityScope::Append => buf.push(0x01),
+            AuthorityScope::ReadOnly => buf.push(0x02),
+            AuthorityScope::Custom(operations) => {
+                buf.push(0x03);
+                // Sorted so the encoding (and any signature over it) is stable
+                let mut operations: Vec<&String> = operations.iter().collect();
+                operations.sort();
+                buf.extend_from_slice(&(operations.len() as u16).to_le_bytes());
+                for operation in operations {
+                    buf.extend_from_slice(&(operation.len() as u16).to_le_bytes());
+                    buf.extend_from_slice(operation.as_bytes());
+                }
+            }
+        }
+        buf
+    }
+
+    /// Decode, returning the scope and bytes consumed
+    pub fn decode(buf: &[u8]) -> Option<(Self, usize)> {
+        match *buf.first()? {
+            0x00 => Some((AuthorityScope::Full, 1)),
+            0x01 => Some((AuthorityScope::Append, 1)),
+            0x02 => Some((AuthorityScope::ReadOnly, 1)),
+            0x03 => {
+                let count = u16::from_le_bytes(buf.get(1..3)?.try_into().ok()?) as usize;
+                let mut offset = 3;
+                let mut operations = HashSet::with_capacity(count);
+                for _ in 0..count {
+                    let len =
+                        u16::from_le_bytes(buf.get(offset..offset + 2)?.try_into().ok()?) as usize;
+                    offset += 2;
+                    let bytes = buf.get(offset..offset + len)?;
+                    operations.insert(String::from_utf8(bytes.to_vec()).ok()?);
+                    offset += len;
+                }
+                Some((AuthorityScope::Custom(operations), offset))
+            }
+            _ => None,
+        }
+    }
+
     /// Check if this scope allows a given operation
     pub fn allows(&self, operation: &AuthorityScope) -> bool {
         match (self, operation) {
@@ -263,6 +309,56 @@ pub enum InterpolationType {
     Catmull,
 }
 
+/// Per-law merge bookkeeping
+///
+/// Convergent delta laws keep more than the rendered `value` so that
+/// replicas reach the same result whatever order deltas arrive in.
+#[derive(Clone, Debug, Default)]
+pub enum MergeState {
+    /// The value is all there is (last-writer-wins)
+    #[default]
+    Plain,
+    /// Append-only entries ordered by `(seq, source)`
+    Log(BTreeMap<(u64, NodeId), Vec<u8>>),
+    /// One counter slot per source
+    Counter(BTreeMap<NodeId, CounterSlot>),
+    /// Concurrent register values, ordered by source
+    Siblings(Vec<Sibling>),
+    /// Latest weighted sample per source
+    Blend(BTreeMap<NodeId, BlendSample>),
+}
+
+/// A single source's contribution to a counter
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CounterSlot {
+    /// Running total for this source
+    pub total: i64,
+    /// Highest event sequence folded into the slot
+    pub seq: u64,
+}
+
+/// One of several concurrent values of a multi-value register
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Sibling {
+    /// Node that wrote the value
+    pub source: NodeId,
+    /// Causal version of the write
+    pub version: VersionVector,
+    /// The written value
+    pub value: Vec<u8>,
+}
+
+/// A source's latest sample for a continuous blend
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlendSample {
+    /// Event sequence of the sample
+    pub seq: u64,
+    /// Sample components
+    pub components: Vec<f32>,
+    /// Blend weight in `[0, 1]`
+    pub weight: f32,
+}
+
 /// State bounds - constraints on state values
 #[derive(Clone, Debug)]
 pub struct StateBounds {
@@ -356,6 +452,8 @@ pub struct StateAtom {
     pub last_modified: StateTime,
     /// The actual value (opaque bytes for now)
     pub value: Vec<u8>,
+    /// Merge bookkeeping the value is rendered from
+    pub merge: MergeState,
 }
 
 impl StateAtom {
@@ -370,6 +468,17 @@ impl StateAtom {
             entropy: EntropyModel::new(),
             last_modified: StateTime::ZERO,
             value: Vec::new(),
+            merge: MergeState::Plain,
+        }
+    }
+
+    /// Concurrent values of a multi-value register
+    ///
+    /// Empty for atoms under any other delta law.
+    pub fn siblings(&self) -> &[Sibling] {
+        match &self.merge {
+            MergeState::Siblings(siblings) => siblings,
+            _ => &[],
         }
     }
 
@@ -451,4 +560,26 @@ mod tests {
         auth.revoke(delegate);
         assert!(!auth.has_authority(delegate, &AuthorityScope::Append));
     }
+
+    #[test]
+    fn test_authority_scope_roundtrip() {
+        let custom = AuthorityScope::Custom(
+            ["mute".to_string(), "pin".to_string()]
+                .into_iter()
+                .collect(),
+        );
+        for scope in [
+            AuthorityScope::Full,
+            AuthorityScope::Append,
+            AuthorityScope::ReadOnly,
+            custom,
+        ] {
+            let encoded = scope.encode();
+            assert_eq!(
+                AuthorityScope::decode(&encoded),
+                Some((scope, encoded.len()))
+            );
+        }
+        assert!(AuthorityScope::decode(&[0x03, 0x01, 0x00, 0x05, 0x00]).is_none());
+    }
 }
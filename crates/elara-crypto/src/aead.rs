@@ -1,31 +1,64 @@
-//! AEAD encryption using ChaCha20-Poly1305
+//! AEAD encryption (ChaCha20-Poly1305 or AES-256-GCM, selected by crypto suite)
 
+use aes_gcm::Aes256Gcm;
 use chacha20poly1305::{
-    aead::{Aead, KeyInit},
+    aead::{Aead, KeyInit, Payload},
     ChaCha20Poly1305, Nonce,
 };
 
 use elara_core::{ElaraError, ElaraResult, NodeId, PacketClass};
+use elara_wire::CryptoSuite;
 
-/// Key size for ChaCha20-Poly1305
+/// Key size for ChaCha20-Poly1305 and AES-256-GCM
 pub const KEY_SIZE: usize = 32;
 
-/// Nonce size for ChaCha20-Poly1305
+/// Nonce size for ChaCha20-Poly1305 and AES-256-GCM
 pub const NONCE_SIZE: usize = 12;
 
-/// Tag size for ChaCha20-Poly1305
+/// Tag size for ChaCha20-Poly1305 and AES-256-GCM
 pub const TAG_SIZE: usize = 16;
 
+/// Concrete AEAD behind a cipher
+enum CipherImpl {
+    ChaCha20Poly1305(ChaCha20Poly1305),
+    Aes256Gcm(Box<Aes256Gcm>),
+}
+
 /// AEAD cipher wrapper
 pub struct AeadCipher {
-    cipher: ChaCha20Poly1305,
+    cipher: CipherImpl,
 }
 
 impl AeadCipher {
-    /// Create a new cipher from key bytes
+    /// Create a new ChaCha20-Poly1305 cipher from key bytes (Suite0)
     pub fn new(key: &[u8; KEY_SIZE]) -> Self {
         let cipher = ChaCha20Poly1305::new_from_slice(key).expect("Invalid key size");
-        AeadCipher { cipher }
+        AeadCipher {
+            cipher: CipherImpl::ChaCha20Poly1305(cipher),
+        }
+    }
+
+    /// Create the cipher used by a crypto suite
+    ///
+    /// Suite2 only changes the key agreement, so it shares Suite0's AEAD.
+    pub fn for_suite(suite: CryptoSuite, key: &[u8; KEY_SIZE]) -> ElaraResult<Self> {
+        match suite {
+            CryptoSuite::Suite0 | CryptoSuite::Suite2 => Ok(Self::new(key)),
+            CryptoSuite::Suite1 => {
+                let cipher = Aes256Gcm::new_from_slice(key).expect("Invalid key size");
+                Ok(AeadCipher {
+                    cipher: CipherImpl::Aes256Gcm(Box::new(cipher)),
+                })
+            }
+        }
+    }
+
+    /// Crypto suite whose AEAD this cipher implements
+    pub fn suite(&self) -> CryptoSuite {
+        match self.cipher {
+            CipherImpl::ChaCha20Poly1305(_) => CryptoSuite::Suite0,
+            CipherImpl::Aes256Gcm(_) => CryptoSuite::Suite1,
+        }
     }
 
     /// Encrypt plaintext with associated data
@@ -37,16 +70,16 @@ impl AeadCipher {
         plaintext: &[u8],
     ) -> ElaraResult<Vec<u8>> {
         let nonce = Nonce::from_slice(nonce);
-
-        self.cipher
-            .encrypt(
-                nonce,
-                chacha20poly1305::aead::Payload {
-                    msg: plaintext,
-                    aad,
-                },
-            )
-            .map_err(|_| ElaraError::DecryptionFailed)
+        let payload = Payload {
+            msg: plaintext,
+            aad,
+        };
+
+        match &self.cipher {
+            CipherImpl::ChaCha20Poly1305(cipher) => cipher.encrypt(nonce, payload),
+            CipherImpl::Aes256Gcm(cipher) => cipher.encrypt(nonce, payload),
+        }
+        .map_err(|_| ElaraError::DecryptionFailed)
     }
 
     /// Decrypt ciphertext with associated data
@@ -58,16 +91,16 @@ impl AeadCipher {
         ciphertext: &[u8],
     ) -> ElaraResult<Vec<u8>> {
         let nonce = Nonce::from_slice(nonce);
-
-        self.cipher
-            .decrypt(
-                nonce,
-                chacha20poly1305::aead::Payload {
-                    msg: ciphertext,
-                    aad,
-                },
-            )
-            .map_err(|_| ElaraError::DecryptionFailed)
+        let payload = Payload {
+            msg: ciphertext,
+            aad,
+        };
+
+        match &self.cipher {
+            CipherImpl::ChaCha20Poly1305(cipher) => cipher.decrypt(nonce, payload),
+            CipherImpl::Aes256Gcm(cipher) => cipher.decrypt(nonce, payload),
+        }
+        .map_err(|_| ElaraError::DecryptionFailed)
     }
 }
 
@@ -131,6 +164,23 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_suite1_aes_gcm() {
+        let key = [0x42u8; KEY_SIZE];
+        let gcm = AeadCipher::for_suite(CryptoSuite::Suite1, &key).unwrap();
+        let chacha = AeadCipher::for_suite(CryptoSuite::Suite0, &key).unwrap();
+        assert_eq!(gcm.suite(), CryptoSuite::Suite1);
+
+        let nonce = derive_nonce(NodeId::new(7), 1, PacketClass::Core);
+        let ciphertext = gcm.encrypt(&nonce, b"aad", b"secret").unwrap();
+        assert_eq!(gcm.decrypt(&nonce, b"aad", &ciphertext).unwrap(), b"secret");
+
+        // Same key, different AEAD
+        assert!(chacha.decrypt(&nonce, b"aad", &ciphertext).is_err());
+        let hybrid = AeadCipher::for_suite(CryptoSuite::Suite2, &key).unwrap();
+        assert_eq!(hybrid.suite(), CryptoSuite::Suite0);
+    }
+
     #[test]
     fn test_nonce_uniqueness() {
         let n1 = derive_nonce(NodeId::new(1), 1, PacketClass::Core);
@@ -0,0 +1,366 @@
+//! Group session key schedule
+//!
+//! Rotates the session root whenever membership changes. One node (the
+//! session host, i.e. the handshake responder) acts as distributor: on every
+//! `SessionJoin`/`SessionLeave` it draws a fresh epoch root and wraps it for
+//! each remaining member under the pairwise root that member established
+//! through the handshake. A removed peer never receives the new root, so it
+//! loses access to traffic once the previous epoch's grace period ends.
+//!
+//! ```text
+//! Distributor                                 Member m
+//! ───────────                                 ────────
+//! root_e = HKDF(fresh, session | e)
+//! wrap_m = AEAD(KDF(pairwise_m), root_e)
+//!                      ──── GroupRekey ────>
+//!                                             root_e = unwrap(pairwise_m)
+//! ```
+
+use std::collections::HashMap;
+
+use hkdf::Hkdf;
+use rand::{rngs::OsRng, RngCore};
+use sha2::Sha256;
+
+use elara_core::{ElaraError, ElaraResult, NodeId, PacketClass, SessionId};
+
+use crate::{derive_nonce, AeadCipher, KEY_SIZE, TAG_SIZE};
+
+/// Group rekey wire format version
+pub const GROUP_REKEY_VERSION: u8 = 0;
+
+/// Size of an encoded rekey message
+/// version(1) + session(8) + epoch(2) + recipient(8) + wrapped_root(32 + 16)
+pub const GROUP_REKEY_SIZE: usize = 19 + KEY_SIZE + TAG_SIZE;
+
+/// Domain separation label for epoch roots
+const EPOCH_ROOT_LABEL: &[u8] = b"ELARA_GROUP_EPOCH_v0";
+
+/// Domain separation label for the pairwise wrapping key
+const WRAP_KEY_LABEL: &[u8] = b"ELARA_GROUP_WRAP_v0";
+
+/// Epoch root wrapped for a single member
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GroupRekey {
+    /// Session being rekeyed
+    pub session_id: SessionId,
+    /// New key epoch (advertised in the `KeyEpoch` extension)
+    pub epoch: u16,
+    /// Member the root is wrapped for
+    pub recipient: NodeId,
+    /// Epoch root encrypted under the pairwise wrapping key
+    pub wrapped_root: Vec<u8>,
+}
+
+impl GroupRekey {
+    /// Encode to bytes
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(GROUP_REKEY_SIZE);
+        buf.push(GROUP_REKEY_VERSION);
+        buf.extend_from_slice(&self.session_id.to_bytes());
+        buf.extend_from_slice(&self.epoch.to_le_bytes());
+        buf.extend_from_slice(&self.recipient.to_bytes());
+        buf.extend_from_slice(&self.wrapped_root);
+        buf
+    }
+
+    /// Decode from bytes
+    pub fn decode(buf: &[u8]) -> ElaraResult<Self> {
+        if buf.len() != GROUP_REKEY_SIZE {
+            return Err(ElaraError::InvalidWireFormat(format!(
+                "Group rekey must be {} bytes, got {}",
+                GROUP_REKEY_SIZE,
+                buf.len()
+            )));
+        }
+        if buf[0] != GROUP_REKEY_VERSION {
+            return Err(ElaraError::InvalidWireFormat(format!(
+                "Unsupported group rekey version: {}",
+                buf[0]
+            )));
+        }
+
+        Ok(GroupRekey {
+            session_id: SessionId::from_bytes(buf[1..9].try_into().unwrap()),
+            epoch: u16::from_le_bytes(buf[9..11].try_into().unwrap()),
+            recipient: NodeId::from_bytes(buf[11..19].try_into().unwrap()),
+            wrapped_root: buf[19..].to_vec(),
+        })
+    }
+
+    /// Associated data binding the wrapped root to its header fields
+    fn aad(session_id: SessionId, epoch: u16, recipient: NodeId) -> Vec<u8> {
+        let mut aad = Vec::with_capacity(18);
+        aad.extend_from_slice(&session_id.to_bytes());
+        aad.extend_from_slice(&epoch.to_le_bytes());
+        aad.extend_from_slice(&recipient.to_bytes());
+        aad
+    }
+}
+
+/// Epoch-based group key schedule for one session
+///
+/// `members` holds the pairwise roots this node shares with its peers. On
+/// the distributor these are all group members; on an ordinary member it is
+/// only the distributor, whose rekeys are the only ones accepted.
+pub struct GroupKeySchedule {
+    session_id: SessionId,
+    local_node_id: NodeId,
+    epoch: u16,
+    root: [u8; KEY_SIZE],
+    members: HashMap<NodeId, [u8; KEY_SIZE]>,
+}
+
+impl GroupKeySchedule {
+    /// Create a schedule starting at epoch 0 with the given root
+    pub fn new(session_id: SessionId, local_node_id: NodeId, root: [u8; KEY_SIZE]) -> Self {
+        GroupKeySchedule {
+            session_id,
+            local_node_id,
+            epoch: 0,
+            root,
+            members: HashMap::new(),
+        }
+    }
+
+    /// Register a peer with the pairwise root from its handshake
+    pub fn add_member(&mut self, node_id: NodeId, pairwise_root: [u8; KEY_SIZE]) {
+        self.members.insert(node_id, pairwise_root);
+    }
+
+    /// Forget a peer; returns false if it was not a member
+    pub fn remove_member(&mut self, node_id: NodeId) -> bool {
+        self.members.remove(&node_id).is_some()
+    }
+
+    /// Check if a peer is a member
+    pub fn is_member(&self, node_id: NodeId) -> bool {
+        self.members.contains_key(&node_id)
+    }
+
+    /// Number of peers holding a pairwise root with this node
+    pub fn member_count(&self) -> usize {
+        self.members.len()
+    }
+
+    /// Session this schedule belongs to
+    pub fn session_id(&self) -> SessionId {
+        self.session_id
+    }
+
+    /// Current key epoch
+    pub fn epoch(&self) -> u16 {
+        self.epoch
+    }
+
+    /// Current epoch root
+    pub fn root(&self) -> &[u8; KEY_SIZE] {
+        &self.root
+    }
+
+    /// Advance to a fresh epoch and wrap its root for every member
+    pub fn rekey(&mut self) -> ElaraResult<Vec<GroupRekey>> {
+        let epoch = self
+            .epoch
+            .checked_add(1)
+            .ok_or(ElaraError::RatchetOutOfSync)?;
+
+        let mut fresh = [0u8; KEY_SIZE];
+        OsRng.fill_bytes(&mut fresh);
+        let root = Self::derive_epoch_root(&fresh, self.session_id, epoch);
+
+        let mut rekeys = Vec::with_capacity(self.members.len());
+        for (&recipient, pairwise) in &self.members {
+            let cipher = AeadCipher::new(&Self::wrap_key(pairwise));
+            let nonce = derive_nonce(recipient, epoch, PacketClass::Core);
+            let aad = GroupRekey::aad(self.session_id, epoch, recipient);
+            rekeys.push(GroupRekey {
+                session_id: self.session_id,
+                epoch,
+                recipient,
+                wrapped_root: cipher.encrypt(&nonce, &aad, &root)?,
+            });
+        }
+
+        tracing::info!(
+            session_id = self.session_id.0,
+            node_id = self.local_node_id.0,
+            epoch = epoch,
+            members = self.members.len(),
+            "Group session rekeyed"
+        );
+
+        self.epoch = epoch;
+        self.root = root;
+        Ok(rekeys)
+    }
+
+    /// Accept a rekey from the distributor and return the new root
+    pub fn accept(&mut self, from: NodeId, rekey: &GroupRekey) -> ElaraResult<[u8; KEY_SIZE]> {
+        if rekey.session_id != self.session_id {
+            return Err(ElaraError::SessionMismatch);
+        }
+        if rekey.recipient != self.local_node_id {
+            return Err(ElaraError::InvalidWireFormat(
+                "Group rekey addressed to another node".into(),
+            ));
+        }
+        let Some(pairwise) = self.members.get(&from) else {
+            return Err(ElaraError::NodeNotInSession);
+        };
+        if rekey.epoch <= self.epoch {
+            tracing::warn!(
+                session_id = self.session_id.0,
+                source = from.0,
+                epoch = rekey.epoch,
+                current_epoch = self.epoch,
+                "Rejecting stale group rekey"
+            );
+            return Err(ElaraError::RatchetOutOfSync);
+        }
+
+        let cipher = AeadCipher::new(&Self::wrap_key(pairwise));
+        let nonce = derive_nonce(rekey.recipient, rekey.epoch, PacketClass::Core);
+        let aad = GroupRekey::aad(rekey.session_id, rekey.epoch, rekey.recipient);
+        let plaintext = cipher.decrypt(&nonce, &aad, &rekey.wrapped_root)?;
+        let root: [u8; KEY_SIZE] = plaintext
+            .as_slice()
+            .try_into()
+            .map_err(|_| ElaraError::DecryptionFailed)?;
+
+        tracing::info!(
+            session_id = self.session_id.0,
+            node_id = self.local_node_id.0,
+            source = from.0,
+            epoch = rekey.epoch,
+            "Accepted group rekey"
+        );
+
+        self.epoch = rekey.epoch;
+        self.root = root;
+        Ok(root)
+    }
+
+    fn derive_epoch_root(
+        fresh: &[u8; KEY_SIZE],
+        session_id: SessionId,
+        epoch: u16,
+    ) -> [u8; KEY_SIZE] {
+        let mut info = Vec::with_capacity(EPOCH_ROOT_LABEL.len() + 10);
+        info.extend_from_slice(EPOCH_ROOT_LABEL);
+        info.extend_from_slice(&session_id.to_bytes());
+        info.extend_from_slice(&epoch.to_le_bytes());
+
+        let hkdf = Hkdf::<Sha256>::new(None, fresh);
+        let mut root = [0u8; KEY_SIZE];
+        hkdf.expand(&info, &mut root).expect("HKDF expand failed");
+        root
+    }
+
+    fn wrap_key(pairwise_root: &[u8; KEY_SIZE]) -> [u8; KEY_SIZE] {
+        let hkdf = Hkdf::<Sha256>::new(None, pairwise_root);
+        let mut key = [0u8; KEY_SIZE];
+        hkdf.expand(WRAP_KEY_LABEL, &mut key)
+            .expect("HKDF expand failed");
+        key
+    }
+}
+
+impl std::fmt::Debug for GroupKeySchedule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GroupKeySchedule")
+            .field("session_id", &self.session_id)
+            .field("local_node_id", &self.local_node_id)
+            .field("epoch", &self.epoch)
+            .field("members", &self.members.len())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedules() -> (GroupKeySchedule, GroupKeySchedule, GroupKeySchedule) {
+        let session = SessionId::new(9);
+        let host_id = NodeId::new(1);
+        let bob_id = NodeId::new(2);
+        let carol_id = NodeId::new(3);
+
+        let mut host = GroupKeySchedule::new(session, host_id, [0u8; KEY_SIZE]);
+        host.add_member(bob_id, [0x11; KEY_SIZE]);
+        host.add_member(carol_id, [0x22; KEY_SIZE]);
+
+        let mut bob = GroupKeySchedule::new(session, bob_id, [0x11; KEY_SIZE]);
+        bob.add_member(host_id, [0x11; KEY_SIZE]);
+        let mut carol = GroupKeySchedule::new(session, carol_id, [0x22; KEY_SIZE]);
+        carol.add_member(host_id, [0x22; KEY_SIZE]);
+
+        (host, bob, carol)
+    }
+
+    fn rekey_for(rekeys: &[GroupRekey], node_id: NodeId) -> Option<&GroupRekey> {
+        rekeys.iter().find(|r| r.recipient == node_id)
+    }
+
+    #[test]
+    fn test_members_share_epoch_root() {
+        let (mut host, mut bob, mut carol) = schedules();
+        let rekeys = host.rekey().unwrap();
+        assert_eq!(rekeys.len(), 2);
+        assert_eq!(host.epoch(), 1);
+
+        let bob_root = bob
+            .accept(NodeId::new(1), rekey_for(&rekeys, NodeId::new(2)).unwrap())
+            .unwrap();
+        let carol_root = carol
+            .accept(NodeId::new(1), rekey_for(&rekeys, NodeId::new(3)).unwrap())
+            .unwrap();
+
+        assert_eq!(&bob_root, host.root());
+        assert_eq!(&carol_root, host.root());
+        assert_eq!(bob.epoch(), 1);
+    }
+
+    #[test]
+    fn test_removed_member_excluded() {
+        let (mut host, _bob, mut carol) = schedules();
+        assert!(host.remove_member(NodeId::new(3)));
+        let rekeys = host.rekey().unwrap();
+        assert!(rekey_for(&rekeys, NodeId::new(3)).is_none());
+
+        // Carol cannot unwrap Bob's copy of the new root
+        let mut stolen = rekey_for(&rekeys, NodeId::new(2)).unwrap().clone();
+        stolen.recipient = NodeId::new(3);
+        assert!(carol.accept(NodeId::new(1), &stolen).is_err());
+    }
+
+    #[test]
+    fn test_stale_and_foreign_rekeys_rejected() {
+        let (mut host, mut bob, _carol) = schedules();
+        let first = host.rekey().unwrap();
+        let first = rekey_for(&first, NodeId::new(2)).unwrap().clone();
+        bob.accept(NodeId::new(1), &first).unwrap();
+
+        assert!(matches!(
+            bob.accept(NodeId::new(1), &first),
+            Err(ElaraError::RatchetOutOfSync)
+        ));
+
+        let second = host.rekey().unwrap();
+        let second = rekey_for(&second, NodeId::new(2)).unwrap();
+        assert!(matches!(
+            bob.accept(NodeId::new(3), second),
+            Err(ElaraError::NodeNotInSession)
+        ));
+    }
+
+    #[test]
+    fn test_rekey_roundtrip() {
+        let (mut host, _, _) = schedules();
+        let rekeys = host.rekey().unwrap();
+        let encoded = rekeys[0].encode();
+        assert_eq!(encoded.len(), GROUP_REKEY_SIZE);
+        assert_eq!(GroupRekey::decode(&encoded).unwrap(), rekeys[0]);
+    }
+}
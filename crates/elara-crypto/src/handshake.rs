@@ -0,0 +1,912 @@
+//! Authenticated session handshake (X25519 + Ed25519)
+//!
+//! SIGMA-style exchange that establishes the session root for the
+//! multi-ratchet without any pre-shared key:
+//!
+//! ```text
+//! Initiator                                   Responder
+//! ─────────                                   ─────────
+//! ephemeral e_i, sign(role, session, pk_i, e_i)
+//!                      ──────────────────────>
+//!                                             verify, ephemeral e_r
+//!                                             sign(role, session, pk_r, e_r, e_i)
+//!                      <──────────────────────
+//! verify, DH(e_i, e_r)                        DH(e_r, e_i)
+//! mac(root, session, e_i, e_r)
+//!                      ──────────────────────>
+//!                                             verify
+//! ```
+//!
+//! Both sides derive the same root with HKDF-SHA256 over the shared secret,
+//! salted with the ephemeral transcript and bound to the session and the
+//! canonically ordered node IDs.
+//!
+//! The initiator message is signed but carries nothing fresh from the
+//! responder, so anyone can replay it. The final confirmation proves the
+//! initiator holds the root derived from the responder's new ephemeral key;
+//! the responder must not use its root until that confirmation verifies.
+//!
+//! The initiator offers its crypto suites and the responder picks one; both
+//! choices are covered by the signatures, so the suite cannot be downgraded.
+//!
+//! Offering `Suite2` adds an ML-KEM-768 encapsulation key to the initiator
+//! message; a responder selecting it answers with the ciphertext. The root is
+//! then derived from both the X25519 and ML-KEM shared secrets, so it stays
+//! secret unless both are broken.
+
+use hkdf::Hkdf;
+use ml_kem::kem::{Decapsulate, Encapsulate};
+use ml_kem::{Ciphertext, Encoded, EncodedSizeUser, KemCore, MlKem768};
+use rand::rngs::OsRng;
+use sha2::{Digest, Sha256};
+use x25519_dalek::{EphemeralSecret, PublicKey};
+
+use elara_core::{ElaraError, ElaraResult, NodeId, SessionId};
+use elara_wire::CryptoSuite;
+
+use crate::{Identity, PublicIdentity, KEY_SIZE};
+
+/// Handshake wire format version
+pub const HANDSHAKE_VERSION: u8 = 0;
+
+/// Size of an encoded handshake message without a KEM payload
+/// version(1) + role(1) + suites(1) + session(8) + verifying_key(32) + ephemeral(32) + signature(64)
+pub const HANDSHAKE_MESSAGE_SIZE: usize = 139;
+
+/// Size of an ML-KEM-768 encapsulation key
+pub const KEM_PUBLIC_KEY_SIZE: usize = 1184;
+
+/// Size of an ML-KEM-768 ciphertext
+pub const KEM_CIPHERTEXT_SIZE: usize = 1088;
+
+/// Offset of the KEM payload (after the fixed fields)
+const KEM_PAYLOAD_OFFSET: usize = 75;
+
+/// Size of an encoded key confirmation
+/// version(1) + marker(1) + session(8) + initiator_ephemeral(32) + tag(32)
+pub const HANDSHAKE_CONFIRM_SIZE: usize = 74;
+
+/// Byte in the role position marking a key confirmation
+const CONFIRM_MARKER: u8 = 2;
+
+type KemDecapsulationKey = <MlKem768 as KemCore>::DecapsulationKey;
+type KemEncapsulationKey = <MlKem768 as KemCore>::EncapsulationKey;
+
+/// Domain separation label for handshake signatures
+const HANDSHAKE_LABEL: &[u8] = b"ELARA_HANDSHAKE_v0";
+
+/// HKDF info prefix for the session root
+const SESSION_ROOT_LABEL: &[u8] = b"elara-session-root-v0";
+
+/// HKDF info prefix for the key confirmation tag
+const CONFIRM_LABEL: &[u8] = b"elara-handshake-confirm-v0";
+
+/// Role of a node in the handshake
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum HandshakeRole {
+    /// Node that opens the exchange
+    Initiator = 0,
+    /// Node that answers an initiator
+    Responder = 1,
+}
+
+impl HandshakeRole {
+    pub fn from_byte(b: u8) -> Option<Self> {
+        match b {
+            0 => Some(HandshakeRole::Initiator),
+            1 => Some(HandshakeRole::Responder),
+            _ => None,
+        }
+    }
+
+    pub fn to_byte(self) -> u8 {
+        self as u8
+    }
+}
+
+/// Bitmask with one bit per crypto suite nibble
+fn suite_mask(suites: &[CryptoSuite]) -> u8 {
+    suites.iter().fold(0, |mask, s| mask | (1 << s.to_nibble()))
+}
+
+/// Signed handshake message carried in a `SessionJoin` event
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HandshakeMessage {
+    /// Sender role
+    pub role: HandshakeRole,
+    /// Suites offered (initiator) or the single selected suite (responder),
+    /// one bit per suite nibble
+    pub suites: u8,
+    /// Session being joined
+    pub session_id: SessionId,
+    /// Sender's Ed25519 verifying key
+    pub verifying_key: [u8; 32],
+    /// Sender's ephemeral X25519 public key
+    pub ephemeral_public: [u8; 32],
+    /// ML-KEM-768 encapsulation key (initiator offering `Suite2`) or
+    /// ciphertext (responder selecting `Suite2`), empty otherwise
+    pub kem_payload: Vec<u8>,
+    /// Ed25519 signature over the transcript
+    pub signature: [u8; 64],
+}
+
+impl HandshakeMessage {
+    /// Public identity of the sender (None if the key is malformed)
+    pub fn public_identity(&self) -> Option<PublicIdentity> {
+        PublicIdentity::from_bytes(&self.verifying_key)
+    }
+
+    /// Node ID of the sender (None if the key is malformed)
+    pub fn node_id(&self) -> Option<NodeId> {
+        self.public_identity().map(|p| p.node_id())
+    }
+
+    /// Check if the message offers a crypto suite
+    pub fn offers(&self, suite: CryptoSuite) -> bool {
+        self.suites & (1 << suite.to_nibble()) != 0
+    }
+
+    /// Suite selected by a responder (None unless exactly one is set)
+    pub fn selected_suite(&self) -> Option<CryptoSuite> {
+        if self.suites.count_ones() != 1 {
+            return None;
+        }
+        CryptoSuite::from_nibble(self.suites.trailing_zeros() as u8)
+    }
+
+    /// Encode to bytes
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(HANDSHAKE_MESSAGE_SIZE + self.kem_payload.len());
+        buf.push(HANDSHAKE_VERSION);
+        buf.push(self.role.to_byte());
+        buf.push(self.suites);
+        buf.extend_from_slice(&self.session_id.to_bytes());
+        buf.extend_from_slice(&self.verifying_key);
+        buf.extend_from_slice(&self.ephemeral_public);
+        buf.extend_from_slice(&self.kem_payload);
+        buf.extend_from_slice(&self.signature);
+        buf
+    }
+
+    /// Decode from bytes
+    pub fn decode(buf: &[u8]) -> ElaraResult<Self> {
+        if buf.len() < HANDSHAKE_MESSAGE_SIZE {
+            return Err(ElaraError::InvalidWireFormat(format!(
+                "Handshake message must be at least {} bytes, got {}",
+                HANDSHAKE_MESSAGE_SIZE,
+                buf.len()
+            )));
+        }
+        if buf[0] != HANDSHAKE_VERSION {
+            return Err(ElaraError::InvalidWireFormat(format!(
+                "Unsupported handshake version: {}",
+                buf[0]
+            )));
+        }
+        let role = HandshakeRole::from_byte(buf[1]).ok_or_else(|| {
+            ElaraError::InvalidWireFormat(format!("Unknown handshake role: {}", buf[1]))
+        })?;
+
+        let kem_end = buf.len() - 64;
+        let kem_payload = buf[KEM_PAYLOAD_OFFSET..kem_end].to_vec();
+        let expected_kem = match role {
+            HandshakeRole::Initiator => KEM_PUBLIC_KEY_SIZE,
+            HandshakeRole::Responder => KEM_CIPHERTEXT_SIZE,
+        };
+        if !kem_payload.is_empty() && kem_payload.len() != expected_kem {
+            return Err(ElaraError::InvalidWireFormat(format!(
+                "Handshake KEM payload must be {} bytes, got {}",
+                expected_kem,
+                kem_payload.len()
+            )));
+        }
+
+        let suites = buf[2];
+        let session_id = SessionId::from_bytes(buf[3..11].try_into().unwrap());
+        let verifying_key: [u8; 32] = buf[11..43].try_into().unwrap();
+        let ephemeral_public: [u8; 32] = buf[43..75].try_into().unwrap();
+        let signature: [u8; 64] = buf[kem_end..].try_into().unwrap();
+
+        Ok(HandshakeMessage {
+            role,
+            suites,
+            session_id,
+            verifying_key,
+            ephemeral_public,
+            kem_payload,
+            signature,
+        })
+    }
+
+    /// Bytes covered by the signature
+    ///
+    /// The responder also signs the initiator's ephemeral key, binding its
+    /// answer to this exchange so an old response cannot be replayed.
+    fn transcript(
+        role: HandshakeRole,
+        suites: u8,
+        session_id: SessionId,
+        verifying_key: &[u8; 32],
+        ephemeral_public: &[u8; 32],
+        kem_payload: &[u8],
+        peer_ephemeral: Option<&[u8; 32]>,
+    ) -> Vec<u8> {
+        let mut buf =
+            Vec::with_capacity(HANDSHAKE_LABEL.len() + 2 + 8 + 32 + 32 + kem_payload.len() + 32);
+        buf.extend_from_slice(HANDSHAKE_LABEL);
+        buf.push(role.to_byte());
+        buf.push(suites);
+        buf.extend_from_slice(&session_id.to_bytes());
+        buf.extend_from_slice(verifying_key);
+        buf.extend_from_slice(ephemeral_public);
+        buf.extend_from_slice(kem_payload);
+        if let Some(peer) = peer_ephemeral {
+            buf.extend_from_slice(peer);
+        }
+        buf
+    }
+
+    fn signed(
+        identity: &Identity,
+        role: HandshakeRole,
+        suites: u8,
+        session_id: SessionId,
+        ephemeral_public: [u8; 32],
+        kem_payload: Vec<u8>,
+        peer_ephemeral: Option<&[u8; 32]>,
+    ) -> Self {
+        let verifying_key = identity.verifying_key_bytes();
+        let transcript = Self::transcript(
+            role,
+            suites,
+            session_id,
+            &verifying_key,
+            &ephemeral_public,
+            &kem_payload,
+            peer_ephemeral,
+        );
+        HandshakeMessage {
+            role,
+            suites,
+            session_id,
+            verifying_key,
+            ephemeral_public,
+            kem_payload,
+            signature: identity.sign(&transcript),
+        }
+    }
+
+    /// Verify the signature and return the sender's public identity
+    fn verify(&self, peer_ephemeral: Option<&[u8; 32]>) -> ElaraResult<PublicIdentity> {
+        let public = self.public_identity().ok_or(ElaraError::InvalidSignature)?;
+        let transcript = Self::transcript(
+            self.role,
+            self.suites,
+            self.session_id,
+            &self.verifying_key,
+            &self.ephemeral_public,
+            &self.kem_payload,
+            peer_ephemeral,
+        );
+        if !public.verify(&transcript, &self.signature) {
+            tracing::warn!(
+                peer_id = public.node_id().0,
+                session_id = self.session_id.0,
+                role = ?self.role,
+                "Handshake signature verification failed"
+            );
+            return Err(ElaraError::InvalidSignature);
+        }
+        Ok(public)
+    }
+}
+
+/// Result of a completed handshake
+#[derive(Clone)]
+pub struct HandshakeOutcome {
+    /// Session the root belongs to
+    pub session_id: SessionId,
+    /// Authenticated peer identity
+    pub peer: PublicIdentity,
+    /// Negotiated crypto suite
+    pub suite: CryptoSuite,
+    /// Session root for `MultiRatchet`
+    pub session_root: [u8; KEY_SIZE],
+    /// Initiator's ephemeral key, identifying the exchange
+    initiator_ephemeral: [u8; 32],
+    /// Responder's ephemeral key
+    responder_ephemeral: [u8; 32],
+}
+
+impl HandshakeOutcome {
+    /// Key confirmation the initiator sends once it derived the root
+    pub fn confirmation(&self) -> HandshakeConfirm {
+        HandshakeConfirm {
+            session_id: self.session_id,
+            initiator_ephemeral: self.initiator_ephemeral,
+            tag: self.confirmation_tag(),
+        }
+    }
+
+    /// Check the initiator's key confirmation (responder side)
+    ///
+    /// Only a peer that completed this exchange knows the root, so a replayed
+    /// initiator message never yields a confirmation that verifies.
+    pub fn verify_confirmation(&self, confirm: &HandshakeConfirm) -> ElaraResult<()> {
+        if confirm.session_id != self.session_id {
+            return Err(ElaraError::SessionMismatch);
+        }
+        let expected = self.confirmation_tag();
+        let diff = expected
+            .iter()
+            .zip(confirm.tag.iter())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b));
+        if confirm.initiator_ephemeral != self.initiator_ephemeral || diff != 0 {
+            tracing::warn!(
+                peer_id = self.peer.node_id().0,
+                session_id = self.session_id.0,
+                "Handshake key confirmation failed"
+            );
+            return Err(ElaraError::HandshakeFailed(
+                "key confirmation mismatch".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Initiator ephemeral key of the exchange this outcome belongs to
+    pub fn initiator_ephemeral(&self) -> &[u8; 32] {
+        &self.initiator_ephemeral
+    }
+
+    fn confirmation_tag(&self) -> [u8; 32] {
+        let mut info = Vec::with_capacity(CONFIRM_LABEL.len() + 8 + 32 + 32);
+        info.extend_from_slice(CONFIRM_LABEL);
+        info.extend_from_slice(&self.session_id.to_bytes());
+        info.extend_from_slice(&self.initiator_ephemeral);
+        info.extend_from_slice(&self.responder_ephemeral);
+
+        let hkdf = Hkdf::<Sha256>::from_prk(&self.session_root).expect("root is a valid PRK");
+        let mut tag = [0u8; 32];
+        hkdf.expand(&info, &mut tag).expect("HKDF expand failed");
+        tag
+    }
+}
+
+/// Key confirmation carried in a `SessionJoin` event after the exchange
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HandshakeConfirm {
+    /// Session being joined
+    pub session_id: SessionId,
+    /// Initiator's ephemeral key, naming the exchange being confirmed
+    pub initiator_ephemeral: [u8; 32],
+    /// Tag keyed by the derived session root
+    pub tag: [u8; 32],
+}
+
+impl HandshakeConfirm {
+    /// Encode to bytes
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(HANDSHAKE_CONFIRM_SIZE);
+        buf.push(HANDSHAKE_VERSION);
+        buf.push(CONFIRM_MARKER);
+        buf.extend_from_slice(&self.session_id.to_bytes());
+        buf.extend_from_slice(&self.initiator_ephemeral);
+        buf.extend_from_slice(&self.tag);
+        buf
+    }
+
+    /// Decode from bytes
+    pub fn decode(buf: &[u8]) -> ElaraResult<Self> {
+        if buf.len() != HANDSHAKE_CONFIRM_SIZE {
+            return Err(ElaraError::InvalidWireFormat(format!(
+                "Handshake confirmation must be {} bytes, got {}",
+                HANDSHAKE_CONFIRM_SIZE,
+                buf.len()
+            )));
+        }
+        if buf[0] != HANDSHAKE_VERSION || buf[1] != CONFIRM_MARKER {
+            return Err(ElaraError::InvalidWireFormat(
+                "Not a handshake confirmation".into(),
+            ));
+        }
+        Ok(HandshakeConfirm {
+            session_id: SessionId::from_bytes(buf[2..10].try_into().unwrap()),
+            initiator_ephemeral: buf[10..42].try_into().unwrap(),
+            tag: buf[42..74].try_into().unwrap(),
+        })
+    }
+}
+
+impl std::fmt::Debug for HandshakeOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HandshakeOutcome")
+            .field("session_id", &self.session_id)
+            .field("peer", &self.peer)
+            .field("suite", &self.suite)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Initiator state machine, waiting for the responder's message
+///
+/// The ephemeral secret is consumed once a valid response arrives; forged or
+/// stale responses leave the handshake pending.
+pub struct Handshake {
+    session_id: SessionId,
+    local_node_id: NodeId,
+    offered_suites: u8,
+    ephemeral: Option<EphemeralSecret>,
+    ephemeral_public: [u8; 32],
+    /// ML-KEM decapsulation key, present when `Suite2` was offered
+    kem_secret: Option<KemDecapsulationKey>,
+}
+
+impl Handshake {
+    /// Start a handshake as initiator offering only `Suite0`
+    ///
+    /// Returns the pending state and the message to send to the responder.
+    pub fn initiate(identity: &Identity, session_id: SessionId) -> (Self, HandshakeMessage) {
+        Self::initiate_with_suites(identity, session_id, &[CryptoSuite::Suite0])
+    }
+
+    /// Start a handshake as initiator offering the given crypto suites
+    pub fn initiate_with_suites(
+        identity: &Identity,
+        session_id: SessionId,
+        suites: &[CryptoSuite],
+    ) -> (Self, HandshakeMessage) {
+        let offered_suites = suite_mask(suites);
+        let ephemeral = EphemeralSecret::random_from_rng(OsRng);
+        let ephemeral_public = PublicKey::from(&ephemeral).to_bytes();
+
+        let (kem_secret, kem_payload) = if suites.contains(&CryptoSuite::Suite2) {
+            let (dk, ek) = MlKem768::generate(&mut OsRng);
+            (Some(dk), ek.as_bytes().to_vec())
+        } else {
+            (None, Vec::new())
+        };
+
+        tracing::info!(
+            node_id = identity.node_id().0,
+            session_id = session_id.0,
+            "Initiating session handshake"
+        );
+
+        let message = HandshakeMessage::signed(
+            identity,
+            HandshakeRole::Initiator,
+            offered_suites,
+            session_id,
+            ephemeral_public,
+            kem_payload,
+            None,
+        );
+
+        (
+            Handshake {
+                session_id,
+                local_node_id: identity.node_id(),
+                offered_suites,
+                ephemeral: Some(ephemeral),
+                ephemeral_public,
+                kem_secret,
+            },
+            message,
+        )
+    }
+
+    /// Answer an initiator message as responder accepting only `Suite0`
+    ///
+    /// Returns the message to send back and the derived session root.
+    pub fn respond(
+        identity: &Identity,
+        init: &HandshakeMessage,
+    ) -> ElaraResult<(HandshakeMessage, HandshakeOutcome)> {
+        Self::respond_with_suites(identity, init, &[CryptoSuite::Suite0])
+    }
+
+    /// Answer an initiator message, picking the first suite in `preference`
+    /// that the initiator offers
+    ///
+    /// The outcome stays unconfirmed: keep it aside until the initiator's
+    /// `HandshakeConfirm` passes `verify_confirmation`.
+    pub fn respond_with_suites(
+        identity: &Identity,
+        init: &HandshakeMessage,
+        preference: &[CryptoSuite],
+    ) -> ElaraResult<(HandshakeMessage, HandshakeOutcome)> {
+        if init.role != HandshakeRole::Initiator {
+            return Err(ElaraError::HandshakeFailed(
+                "expected initiator message".into(),
+            ));
+        }
+        let peer = init.verify(None)?;
+        if peer.node_id() == identity.node_id() {
+            return Err(ElaraError::HandshakeFailed(
+                "refusing handshake with self".into(),
+            ));
+        }
+        let suite = preference
+            .iter()
+            .copied()
+            .find(|&s| init.offers(s))
+            .ok_or_else(|| ElaraError::HandshakeFailed("no common crypto suite".into()))?;
+
+        let ephemeral = EphemeralSecret::random_from_rng(OsRng);
+        let ephemeral_public = PublicKey::from(&ephemeral).to_bytes();
+        let shared = ephemeral.diffie_hellman(&PublicKey::from(init.ephemeral_public));
+
+        let mut ikm = shared.as_bytes().to_vec();
+        let mut kem_payload = Vec::new();
+        if suite == CryptoSuite::Suite2 {
+            let ek = kem_encapsulation_key(&init.kem_payload)?;
+            let (ciphertext, kem_shared) = ek
+                .encapsulate(&mut OsRng)
+                .map_err(|_| ElaraError::HandshakeFailed("ML-KEM encapsulation failed".into()))?;
+            ikm.extend_from_slice(&kem_shared);
+            kem_payload = ciphertext.to_vec();
+        }
+
+        let session_root = derive_session_root(
+            &ikm,
+            &init.ephemeral_public,
+            &ephemeral_public,
+            init.session_id,
+            peer.node_id(),
+            identity.node_id(),
+        );
+
+        let response = HandshakeMessage::signed(
+            identity,
+            HandshakeRole::Responder,
+            suite_mask(&[suite]),
+            init.session_id,
+            ephemeral_public,
+            kem_payload,
+            Some(&init.ephemeral_public),
+        );
+
+        tracing::info!(
+            node_id = identity.node_id().0,
+            peer_id = peer.node_id().0,
+            session_id = init.session_id.0,
+            "Handshake completed as responder"
+        );
+
+        Ok((
+            response,
+            HandshakeOutcome {
+                session_id: init.session_id,
+                peer,
+                suite,
+                session_root,
+                initiator_ephemeral: init.ephemeral_public,
+                responder_ephemeral: ephemeral_public,
+            },
+        ))
+    }
+
+    /// Finish the handshake with the responder's message
+    ///
+    /// Send `outcome.confirmation()` back so the responder accepts the root.
+    pub fn complete(&mut self, response: &HandshakeMessage) -> ElaraResult<HandshakeOutcome> {
+        if self.ephemeral.is_none() {
+            return Err(ElaraError::HandshakeFailed(
+                "handshake already completed".into(),
+            ));
+        }
+        if response.role != HandshakeRole::Responder {
+            return Err(ElaraError::HandshakeFailed(
+                "expected responder message".into(),
+            ));
+        }
+        if response.session_id != self.session_id {
+            return Err(ElaraError::SessionMismatch);
+        }
+        let peer = response.verify(Some(&self.ephemeral_public))?;
+        let suite = response
+            .selected_suite()
+            .filter(|&s| self.offered_suites & (1 << s.to_nibble()) != 0)
+            .ok_or_else(|| {
+                ElaraError::HandshakeFailed("responder chose an unoffered suite".into())
+            })?;
+
+        let mut kem_shared = None;
+        if suite == CryptoSuite::Suite2 {
+            let dk = self.kem_secret.as_ref().expect("offered Suite2");
+            let ciphertext = Ciphertext::<MlKem768>::try_from(response.kem_payload.as_slice())
+                .map_err(|_| ElaraError::HandshakeFailed("missing ML-KEM ciphertext".into()))?;
+            let shared = dk
+                .decapsulate(&ciphertext)
+                .map_err(|_| ElaraError::HandshakeFailed("ML-KEM decapsulation failed".into()))?;
+            kem_shared = Some(shared);
+        } else if !response.kem_payload.is_empty() {
+            return Err(ElaraError::HandshakeFailed("unexpected KEM payload".into()));
+        }
+
+        let ephemeral = self.ephemeral.take().expect("checked above");
+        let shared = ephemeral.diffie_hellman(&PublicKey::from(response.ephemeral_public));
+        let mut ikm = shared.as_bytes().to_vec();
+        if let Some(kem_shared) = kem_shared {
+            ikm.extend_from_slice(&kem_shared);
+        }
+        self.kem_secret = None;
+
+        let session_root = derive_session_root(
+            &ikm,
+            &self.ephemeral_public,
+            &response.ephemeral_public,
+            self.session_id,
+            self.local_node_id,
+            peer.node_id(),
+        );
+
+        tracing::info!(
+            node_id = self.local_node_id.0,
+            peer_id = peer.node_id().0,
+            session_id = self.session_id.0,
+            "Handshake completed as initiator"
+        );
+
+        Ok(HandshakeOutcome {
+            session_id: self.session_id,
+            peer,
+            suite,
+            session_root,
+            initiator_ephemeral: self.ephemeral_public,
+            responder_ephemeral: response.ephemeral_public,
+        })
+    }
+
+    /// Session this handshake is for
+    pub fn session_id(&self) -> SessionId {
+        self.session_id
+    }
+
+    /// Check if the handshake has completed
+    pub fn is_complete(&self) -> bool {
+        self.ephemeral.is_none()
+    }
+}
+
+impl std::fmt::Debug for Handshake {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Handshake")
+            .field("session_id", &self.session_id)
+            .field("local_node_id", &self.local_node_id)
+            .field("complete", &self.is_complete())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Parse the ML-KEM encapsulation key offered by an initiator
+fn kem_encapsulation_key(payload: &[u8]) -> ElaraResult<KemEncapsulationKey> {
+    let encoded = Encoded::<KemEncapsulationKey>::try_from(payload)
+        .map_err(|_| ElaraError::HandshakeFailed("Suite2 offered without an ML-KEM key".into()))?;
+    Ok(KemEncapsulationKey::from_bytes(&encoded))
+}
+
+/// Derive the session root from the shared secret
+///
+/// For `Suite2` the secret is the X25519 output followed by the ML-KEM one.
+fn derive_session_root(
+    shared_secret: &[u8],
+    initiator_ephemeral: &[u8; 32],
+    responder_ephemeral: &[u8; 32],
+    session_id: SessionId,
+    a: NodeId,
+    b: NodeId,
+) -> [u8; KEY_SIZE] {
+    // Transcript salt
+    let mut hasher = Sha256::new();
+    hasher.update(HANDSHAKE_LABEL);
+    hasher.update(initiator_ephemeral);
+    hasher.update(responder_ephemeral);
+    let salt = hasher.finalize();
+
+    // Canonical ordering
+    let (first, second) = if a.0 < b.0 { (a, b) } else { (b, a) };
+
+    let mut info = Vec::with_capacity(SESSION_ROOT_LABEL.len() + 24);
+    info.extend_from_slice(SESSION_ROOT_LABEL);
+    info.extend_from_slice(&session_id.to_bytes());
+    info.extend_from_slice(&first.to_bytes());
+    info.extend_from_slice(&second.to_bytes());
+
+    let hkdf = Hkdf::<Sha256>::new(Some(&salt), shared_secret);
+    let mut root = [0u8; KEY_SIZE];
+    hkdf.expand(&info, &mut root).expect("HKDF expand failed");
+    root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_handshake_derives_same_root() {
+        let alice = Identity::generate();
+        let bob = Identity::generate();
+        let session = SessionId::new(7);
+
+        let (mut pending, init) = Handshake::initiate(&alice, session);
+        let (response, bob_outcome) = Handshake::respond(&bob, &init).unwrap();
+        let alice_outcome = pending.complete(&response).unwrap();
+
+        assert!(pending.is_complete());
+        assert!(pending.complete(&response).is_err());
+        assert_eq!(alice_outcome.session_root, bob_outcome.session_root);
+        assert_eq!(alice_outcome.peer.node_id(), bob.node_id());
+        assert_eq!(bob_outcome.peer.node_id(), alice.node_id());
+    }
+
+    #[test]
+    fn test_message_roundtrip() {
+        let alice = Identity::generate();
+        let (_, init) = Handshake::initiate(&alice, SessionId::new(1));
+
+        let encoded = init.encode();
+        assert_eq!(encoded.len(), HANDSHAKE_MESSAGE_SIZE);
+        assert_eq!(HandshakeMessage::decode(&encoded).unwrap(), init);
+        assert_eq!(init.node_id(), Some(alice.node_id()));
+    }
+
+    #[test]
+    fn test_tampered_initiator_rejected() {
+        let alice = Identity::generate();
+        let bob = Identity::generate();
+        let mallory = Identity::generate();
+
+        let (_, mut init) = Handshake::initiate(&alice, SessionId::new(1));
+        init.ephemeral_public =
+            PublicKey::from(&EphemeralSecret::random_from_rng(OsRng)).to_bytes();
+        assert!(matches!(
+            Handshake::respond(&bob, &init),
+            Err(ElaraError::InvalidSignature)
+        ));
+
+        // Claiming another identity without its signing key
+        let (_, mut init) = Handshake::initiate(&alice, SessionId::new(1));
+        init.verifying_key = mallory.verifying_key_bytes();
+        assert!(Handshake::respond(&bob, &init).is_err());
+    }
+
+    #[test]
+    fn test_response_bound_to_exchange() {
+        let alice = Identity::generate();
+        let bob = Identity::generate();
+        let session = SessionId::new(3);
+
+        // Response to an earlier exchange cannot complete a new one
+        let (_, old_init) = Handshake::initiate(&alice, session);
+        let (old_response, _) = Handshake::respond(&bob, &old_init).unwrap();
+
+        let (mut pending, init) = Handshake::initiate(&alice, session);
+        assert!(matches!(
+            pending.complete(&old_response),
+            Err(ElaraError::InvalidSignature)
+        ));
+
+        // Rejected response leaves the handshake pending
+        assert!(!pending.is_complete());
+        let (response, bob_outcome) = Handshake::respond(&bob, &init).unwrap();
+        let alice_outcome = pending.complete(&response).unwrap();
+        assert_eq!(alice_outcome.session_root, bob_outcome.session_root);
+    }
+
+    #[test]
+    fn test_confirmation_proves_root() {
+        let alice = Identity::generate();
+        let bob = Identity::generate();
+        let session = SessionId::new(6);
+
+        let (mut pending, init) = Handshake::initiate(&alice, session);
+        let (response, bob_outcome) = Handshake::respond(&bob, &init).unwrap();
+        let confirm = pending.complete(&response).unwrap().confirmation();
+        let encoded = confirm.encode();
+        assert_eq!(encoded.len(), HANDSHAKE_CONFIRM_SIZE);
+        assert!(HandshakeMessage::decode(&encoded).is_err());
+        let confirm = HandshakeConfirm::decode(&encoded).unwrap();
+        assert!(bob_outcome.verify_confirmation(&confirm).is_ok());
+
+        // Replaying the initiator message gets a fresh responder ephemeral,
+        // and the old confirmation does not prove the new root
+        let (_, replayed) = Handshake::respond(&bob, &init).unwrap();
+        assert_ne!(replayed.session_root, bob_outcome.session_root);
+        assert!(matches!(
+            replayed.verify_confirmation(&confirm),
+            Err(ElaraError::HandshakeFailed(_))
+        ));
+
+        let mut forged = confirm.clone();
+        forged.tag[0] ^= 1;
+        assert!(bob_outcome.verify_confirmation(&forged).is_err());
+    }
+
+    #[test]
+    fn test_suite_negotiation() {
+        let alice = Identity::generate();
+        let bob = Identity::generate();
+        let offered = [CryptoSuite::Suite1, CryptoSuite::Suite0];
+
+        let (mut pending, init) =
+            Handshake::initiate_with_suites(&alice, SessionId::new(4), &offered);
+        assert!(init.offers(CryptoSuite::Suite1) && init.offers(CryptoSuite::Suite0));
+
+        let (response, bob_outcome) =
+            Handshake::respond_with_suites(&bob, &init, &offered).unwrap();
+        assert_eq!(response.selected_suite(), Some(CryptoSuite::Suite1));
+        let alice_outcome = pending.complete(&response).unwrap();
+        assert_eq!(alice_outcome.suite, CryptoSuite::Suite1);
+        assert_eq!(bob_outcome.suite, CryptoSuite::Suite1);
+
+        // A Suite0-only peer falls back to ChaCha20-Poly1305
+        let (_, init) = Handshake::initiate(&alice, SessionId::new(4));
+        let (response, _) = Handshake::respond_with_suites(&bob, &init, &offered).unwrap();
+        assert_eq!(response.selected_suite(), Some(CryptoSuite::Suite0));
+
+        // No overlap
+        let (_, init) = Handshake::initiate(&alice, SessionId::new(4));
+        assert!(matches!(
+            Handshake::respond_with_suites(&bob, &init, &[CryptoSuite::Suite1]),
+            Err(ElaraError::HandshakeFailed(_))
+        ));
+    }
+
+    #[test]
+    fn test_hybrid_suite() {
+        let alice = Identity::generate();
+        let bob = Identity::generate();
+        let offered = [CryptoSuite::Suite2, CryptoSuite::Suite0];
+
+        let (mut pending, init) =
+            Handshake::initiate_with_suites(&alice, SessionId::new(5), &offered);
+        assert_eq!(init.kem_payload.len(), KEM_PUBLIC_KEY_SIZE);
+        let encoded = init.encode();
+        assert_eq!(encoded.len(), HANDSHAKE_MESSAGE_SIZE + KEM_PUBLIC_KEY_SIZE);
+        assert_eq!(HandshakeMessage::decode(&encoded).unwrap(), init);
+
+        let (response, bob_outcome) =
+            Handshake::respond_with_suites(&bob, &init, &offered).unwrap();
+        assert_eq!(response.selected_suite(), Some(CryptoSuite::Suite2));
+        assert_eq!(response.kem_payload.len(), KEM_CIPHERTEXT_SIZE);
+        let response = HandshakeMessage::decode(&response.encode()).unwrap();
+        let alice_outcome = pending.complete(&response).unwrap();
+        assert_eq!(alice_outcome.suite, CryptoSuite::Suite2);
+        assert_eq!(alice_outcome.session_root, bob_outcome.session_root);
+
+        // The KEM payload is signed
+        let (_, mut init) = Handshake::initiate_with_suites(&alice, SessionId::new(5), &offered);
+        init.kem_payload[0] ^= 0xFF;
+        assert!(matches!(
+            Handshake::respond_with_suites(&bob, &init, &offered),
+            Err(ElaraError::InvalidSignature)
+        ));
+
+        // A Suite0-only peer falls back to the classical exchange
+        let (mut pending, init) =
+            Handshake::initiate_with_suites(&alice, SessionId::new(5), &offered);
+        let (response, bob_outcome) = Handshake::respond(&bob, &init).unwrap();
+        assert!(response.kem_payload.is_empty());
+        let alice_outcome = pending.complete(&response).unwrap();
+        assert_eq!(alice_outcome.suite, CryptoSuite::Suite0);
+        assert_eq!(alice_outcome.session_root, bob_outcome.session_root);
+    }
+
+    #[test]
+    fn test_session_mismatch_rejected() {
+        let alice = Identity::generate();
+        let bob = Identity::generate();
+
+        let (_, other_init) = Handshake::initiate(&alice, SessionId::new(2));
+        let (response, _) = Handshake::respond(&bob, &other_init).unwrap();
+
+        let (mut pending, _) = Handshake::initiate(&alice, SessionId::new(1));
+        assert!(matches!(
+            pending.complete(&response),
+            Err(ElaraError::SessionMismatch)
+        ));
+    }
+}
@@ -56,6 +56,14 @@ impl Identity {
         self.verifying_key.to_bytes()
     }
 
+    /// Get the public half of this identity
+    pub fn public_identity(&self) -> PublicIdentity {
+        PublicIdentity {
+            verifying_key: self.verifying_key,
+            node_id: self.node_id,
+        }
+    }
+
     /// Get the node ID (truncated hash of public key)
     pub fn node_id(&self) -> NodeId {
         self.node_id
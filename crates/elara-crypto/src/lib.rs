@@ -4,18 +4,24 @@
 //!
 //! Provides cryptographic primitives for the ELARA protocol:
 //! - Identity management (Ed25519)
+//! - Authenticated session handshake (X25519)
+//! - Group key schedule with epoch rekeying
 //! - AEAD encryption (ChaCha20-Poly1305)
 //! - Multi-ratchet key derivation
 //! - Replay protection
 //! - Secure frame encryption/decryption
 
 pub mod aead;
+pub mod group;
+pub mod handshake;
 pub mod identity;
 pub mod ratchet;
 pub mod replay;
 pub mod secure_frame;
 
 pub use aead::*;
+pub use group::*;
+pub use handshake::*;
 pub use identity::*;
 pub use ratchet::*;
 pub use replay::*;
@@ -100,7 +100,7 @@ impl ReplayManager {
     pub fn check(&self, node: NodeId, class: PacketClass, seq: u16) -> bool {
         self.windows
             .get(&(node, class))
-            .is_none_or(|w| w.check(seq))
+            .map_or(true, |w| w.check(seq))
     }
 
     /// Accept a packet (mark as received)
@@ -4,41 +4,168 @@
 //! - Frame encryption with AEAD
 //! - Frame decryption with validation
 //! - Multi-ratchet key selection per packet class
+//! - Key epochs with a grace period for the previous group root
+//! - Per-session crypto suite (AEAD selected by the header nibble)
+//! - Optional compression before encryption
+
+use std::time::{Duration, Instant};
 
 use elara_core::{ElaraError, ElaraResult, NodeId, PacketClass, RepresentationProfile, SessionId};
-use elara_wire::{Extensions, FixedHeader, Frame, FrameBuilder, FIXED_HEADER_SIZE};
+use elara_wire::{
+    compress_payload, decompress_frame_payload, CompressionAlgorithm, CryptoSuite, Extensions,
+    FixedHeader, Frame, FrameBuilder, FIXED_HEADER_SIZE, MAX_DECOMPRESSED_SIZE,
+};
 
 use crate::{AeadCipher, MultiRatchet, ReplayManager, KEY_SIZE};
 
+/// How long frames from the previous key epoch are still accepted
+pub const DEFAULT_EPOCH_GRACE: Duration = Duration::from_secs(5);
+
+/// Ratchet of the key epoch being retired
+struct RetiredEpoch {
+    epoch: u16,
+    ratchet: MultiRatchet,
+    expires_at: Instant,
+}
+
 /// Secure frame processor - handles encryption/decryption of frames
 pub struct SecureFrameProcessor {
     /// Session ID
     session_id: SessionId,
     /// Local node ID
     local_node_id: NodeId,
+    /// Negotiated crypto suite
+    suite: CryptoSuite,
     /// Multi-ratchet for key derivation
     ratchet: MultiRatchet,
+    /// Current key epoch (advertised in the `KeyEpoch` extension)
+    key_epoch: u16,
+    /// Previous epoch, accepted on receive until its grace period ends
+    retired: Option<RetiredEpoch>,
+    /// Grace period for the previous epoch
+    epoch_grace: Duration,
     /// Replay protection
     replay_manager: ReplayManager,
     /// Sequence counters per class
     seq_counters: [u16; 5],
+    /// Compression applied to outgoing payloads
+    compression: CompressionAlgorithm,
+    /// Limit on the size of a decompressed payload
+    max_decompressed_size: usize,
 }
 
 impl SecureFrameProcessor {
-    /// Create a new secure frame processor
+    /// Create a new secure frame processor (Suite0)
     pub fn new(session_id: SessionId, local_node_id: NodeId, session_key: [u8; KEY_SIZE]) -> Self {
+        Self::build(session_id, local_node_id, session_key, CryptoSuite::Suite0)
+    }
+
+    /// Create a processor for a negotiated crypto suite
+    pub fn with_suite(
+        session_id: SessionId,
+        local_node_id: NodeId,
+        session_key: [u8; KEY_SIZE],
+        suite: CryptoSuite,
+    ) -> ElaraResult<Self> {
+        // Fail early rather than on the first frame
+        AeadCipher::for_suite(suite, &session_key)?;
+        Ok(Self::build(session_id, local_node_id, session_key, suite))
+    }
+
+    fn build(
+        session_id: SessionId,
+        local_node_id: NodeId,
+        session_key: [u8; KEY_SIZE],
+        suite: CryptoSuite,
+    ) -> Self {
         tracing::info!(
             session_id = session_id.0,
             node_id = local_node_id.0,
+            suite = ?suite,
             "Creating secure frame processor"
         );
 
         SecureFrameProcessor {
             session_id,
             local_node_id,
+            suite,
             ratchet: MultiRatchet::new(&session_key),
+            key_epoch: 0,
+            retired: None,
+            epoch_grace: DEFAULT_EPOCH_GRACE,
             replay_manager: ReplayManager::new(),
             seq_counters: [0; 5],
+            compression: CompressionAlgorithm::None,
+            max_decompressed_size: MAX_DECOMPRESSED_SIZE,
+        }
+    }
+
+    /// Compress outgoing payloads above the size threshold
+    pub fn set_compression(&mut self, algorithm: CompressionAlgorithm) {
+        self.compression = algorithm;
+    }
+
+    /// Compression applied to outgoing payloads
+    pub fn compression(&self) -> CompressionAlgorithm {
+        self.compression
+    }
+
+    /// Limit how far a received compressed payload may expand
+    pub fn set_max_decompressed_size(&mut self, max_size: usize) {
+        self.max_decompressed_size = max_size;
+    }
+
+    /// Set how long the previous key epoch stays valid after a rekey
+    pub fn set_epoch_grace(&mut self, grace: Duration) {
+        self.epoch_grace = grace;
+    }
+
+    /// Switch to a new group root
+    ///
+    /// Outgoing frames use the new epoch immediately; frames from the
+    /// previous epoch are still decrypted until the grace period ends.
+    pub fn rekey(&mut self, epoch: u16, root: [u8; KEY_SIZE]) -> ElaraResult<()> {
+        if epoch <= self.key_epoch {
+            return Err(ElaraError::RatchetOutOfSync);
+        }
+
+        tracing::info!(
+            session_id = self.session_id.0,
+            node_id = self.local_node_id.0,
+            previous_epoch = self.key_epoch,
+            epoch = epoch,
+            "Switching key epoch"
+        );
+
+        let previous = std::mem::replace(&mut self.ratchet, MultiRatchet::new(&root));
+        self.retired = Some(RetiredEpoch {
+            epoch: self.key_epoch,
+            ratchet: previous,
+            expires_at: Instant::now() + self.epoch_grace,
+        });
+        self.key_epoch = epoch;
+        Ok(())
+    }
+
+    /// Current key epoch
+    pub fn key_epoch(&self) -> u16 {
+        self.key_epoch
+    }
+
+    /// Select the ratchet for a received key epoch
+    fn ratchet_for_epoch(&mut self, epoch: u16) -> ElaraResult<&mut MultiRatchet> {
+        if epoch == self.key_epoch {
+            return Ok(&mut self.ratchet);
+        }
+
+        if let Some(retired) = self.retired.as_ref() {
+            if Instant::now() >= retired.expires_at {
+                self.retired = None;
+            }
+        }
+        match self.retired.as_mut() {
+            Some(retired) if retired.epoch == epoch => Ok(&mut retired.ratchet),
+            _ => Err(ElaraError::RatchetOutOfSync),
         }
     }
 
@@ -56,7 +183,7 @@ impl SecureFrameProcessor {
         class: PacketClass,
         profile: RepresentationProfile,
         time_hint: i32,
-        extensions: Extensions,
+        mut extensions: Extensions,
         payload: &[u8],
     ) -> ElaraResult<Vec<u8>> {
         let payload_size = payload.len();
@@ -71,37 +198,53 @@ impl SecureFrameProcessor {
 
         // Get message key from ratchet
         let key = self.ratchet.next_message_key(class);
-        let cipher = AeadCipher::new(&key);
+        let cipher = AeadCipher::for_suite(self.suite, &key)?;
 
         // Build header
         let seq = self.next_seq(class);
         let mut header = FixedHeader::new(self.session_id, self.local_node_id);
+        header.crypto_suite = self.suite;
         header.class = class;
         header.profile = profile;
         header.time_hint = time_hint;
         header.set_seq(seq);
         header.set_window(class.replay_window_size());
 
-        // Set extension flag if needed
+        // Advertise key epoch once the group has been rekeyed
+        if self.key_epoch > 0 {
+            extensions.key_epoch = Some(self.key_epoch);
+        }
+
+        if extensions.fragment_info.is_some() {
+            header.flags.set_fragment(true);
+        }
+
+        // Compress before encrypting when it shrinks the payload
+        let compressed = compress_payload(self.compression, payload);
+        let payload = match compressed.as_deref() {
+            Some(body) => {
+                header.flags.set_compressed(true);
+                extensions.compression_hint = Some(self.compression.to_byte());
+                body
+            }
+            None => payload,
+        };
+
+        // Set extension flag and header length if needed
         if !extensions.is_empty() {
             header.flags.set_extension(true);
+            header.header_len = (FIXED_HEADER_SIZE + extensions.serialized_size()) as u16;
         }
 
-        // Serialize header for AAD
-        let mut header_bytes = [0u8; FIXED_HEADER_SIZE];
-        let _ = header.serialize(&mut header_bytes);
+        // Build frame; header and extensions are the AAD
+        let mut frame = FrameBuilder::new(header).extensions(extensions).build();
+        let aad = frame.associated_data();
 
         // Derive nonce from header parameters
         let nonce = crate::derive_nonce(self.local_node_id, seq, class);
 
         // Encrypt payload (returns ciphertext with tag appended)
-        let ciphertext = cipher.encrypt(&nonce, &header_bytes, payload)?;
-
-        // Build complete frame
-        let frame = FrameBuilder::new(header)
-            .extensions(extensions)
-            .payload(ciphertext)
-            .build();
+        frame.payload = cipher.encrypt(&nonce, &aad, payload)?;
 
         let result = frame.serialize()?;
         
@@ -118,7 +261,6 @@ impl SecureFrameProcessor {
 
     /// Decrypt a received frame
     pub fn decrypt_frame(&mut self, data: &[u8]) -> ElaraResult<DecryptedFrame> {
-        
         tracing::debug!(
             session_id = self.session_id.0,
             data_size = data.len(),
@@ -138,38 +280,62 @@ impl SecureFrameProcessor {
             return Err(ElaraError::SessionMismatch);
         }
 
+        // Check crypto suite
+        if frame.header.crypto_suite != self.suite {
+            tracing::warn!(
+                expected_suite = ?self.suite,
+                received_suite = ?frame.header.crypto_suite,
+                "Crypto suite mismatch"
+            );
+            return Err(ElaraError::CryptoSuiteMismatch {
+                expected: self.suite.to_nibble(),
+                actual: frame.header.crypto_suite.to_nibble(),
+            });
+        }
+
         // Check replay
         let seq = frame.header.seq();
         let class = frame.header.class;
         let node_id = frame.header.node_id;
 
-        if let Err(e) = self.replay_manager.accept(node_id, class, seq) {
+        if !self.replay_manager.check(node_id, class, seq) {
             tracing::warn!(
                 node_id = node_id.0,
                 class = ?class,
                 seq = seq,
-                error = ?e,
                 "Replay protection rejected frame"
             );
-            return Err(e);
+            return Err(ElaraError::ReplayDetected(seq as u32));
         }
 
+        // Select the ratchet for the frame's key epoch
+        let epoch = frame.extensions.key_epoch.unwrap_or(0);
+        let (session_id, suite) = (self.session_id, self.suite);
+        let ratchet = self.ratchet_for_epoch(epoch).map_err(|e| {
+            tracing::warn!(
+                node_id = node_id.0,
+                session_id = session_id.0,
+                epoch = epoch,
+                "Frame from unknown or expired key epoch"
+            );
+            e
+        })?;
+
         // Get decryption key (need to sync ratchet if needed)
-        let ratchet = self.ratchet.get(class);
-        let key = ratchet.message_key();
-        let cipher = AeadCipher::new(&key);
+        let key = ratchet.get(class).message_key();
+        let cipher = AeadCipher::for_suite(suite, &key)?;
 
         // Derive nonce
         let nonce = crate::derive_nonce(node_id, seq, class);
 
-        // Get AAD (header bytes)
-        let aad = &data[..FIXED_HEADER_SIZE];
+        // Get AAD (header and extension bytes, as received)
+        let aad = &data[..frame.header.header_len as usize];
 
         // Decrypt payload (ciphertext includes auth tag)
         let plaintext = cipher.decrypt(&nonce, aad, &frame.payload).map_err(|e| {
             tracing::error!(
                 node_id = node_id.0,
-                session_id = self.session_id.0,
+                session_id = session_id.0,
                 seq = seq,
                 error = ?e,
                 "Frame decryption failed"
@@ -178,7 +344,27 @@ impl SecureFrameProcessor {
         })?;
 
         // Advance ratchet after successful decryption
-        self.ratchet.get_mut(class).advance_message();
+        ratchet.get_mut(class).advance_message();
+
+        // Only an authenticated frame may move the replay window
+        self.replay_manager.accept(node_id, class, seq)?;
+
+        let plaintext = decompress_frame_payload(
+            &frame.header,
+            &frame.extensions,
+            plaintext,
+            self.max_decompressed_size,
+        )
+        .map_err(|e| {
+            tracing::warn!(
+                node_id = node_id.0,
+                session_id = session_id.0,
+                seq = seq,
+                error = ?e,
+                "Frame decompression failed"
+            );
+            e
+        })?;
 
         tracing::debug!(
             node_id = node_id.0,
@@ -207,7 +393,15 @@ impl SecureFrameProcessor {
         self.local_node_id
     }
 
+    /// Get negotiated crypto suite
+    pub fn suite(&self) -> CryptoSuite {
+        self.suite
+    }
+
     /// Remove replay state for a node (on disconnect)
+    ///
+    /// This does not revoke the peer's key material; rekey the group with
+    /// [`SecureFrameProcessor::rekey`] so it cannot read further traffic.
     pub fn remove_peer(&mut self, node_id: NodeId) {
         tracing::info!(
             session_id = self.session_id.0,
@@ -422,6 +616,148 @@ mod tests {
         assert!(matches!(result, Err(ElaraError::SessionMismatch)));
     }
 
+    #[test]
+    fn test_extensions_authenticated() {
+        let (mut sender, mut receiver) = create_test_processors();
+        let mut extensions = Extensions::new();
+        extensions.fragment_info = Some(elara_wire::FragmentInfo::new(0, 2));
+        extensions.redundancy_group = Some(7);
+        let encrypted = sender
+            .encrypt_frame(
+                PacketClass::Core,
+                RepresentationProfile::Textual,
+                0,
+                extensions,
+                b"first half",
+            )
+            .unwrap();
+
+        // Flip a byte of the redundancy group id
+        let mut tampered = encrypted.clone();
+        tampered[FIXED_HEADER_SIZE + 2] ^= 0x01;
+        let frame = Frame::parse(&tampered).unwrap();
+        assert_eq!(frame.extensions.redundancy_group, Some(6));
+        assert!(matches!(
+            receiver.decrypt_frame(&tampered),
+            Err(ElaraError::DecryptionFailed)
+        ));
+
+        let (_, mut receiver) = create_test_processors();
+        let decrypted = receiver.decrypt_frame(&encrypted).unwrap();
+        assert_eq!(decrypted.payload, b"first half");
+        assert_eq!(decrypted.extensions.fragment_info.unwrap().total, 2);
+    }
+
+    fn encrypt_core(processor: &mut SecureFrameProcessor, payload: &[u8]) -> Vec<u8> {
+        processor
+            .encrypt_frame(
+                PacketClass::Core,
+                RepresentationProfile::Textual,
+                0,
+                Extensions::new(),
+                payload,
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn test_rekey_advertises_epoch() {
+        let (mut sender, mut receiver) = create_test_processors();
+        let new_root = [0x24u8; KEY_SIZE];
+        sender.rekey(1, new_root).unwrap();
+        receiver.rekey(1, new_root).unwrap();
+
+        let encrypted = encrypt_core(&mut sender, b"epoch one");
+        assert_eq!(
+            Frame::parse(&encrypted).unwrap().extensions.key_epoch,
+            Some(1)
+        );
+
+        let decrypted = receiver.decrypt_frame(&encrypted).unwrap();
+        assert_eq!(decrypted.payload, b"epoch one");
+        assert!(matches!(
+            sender.rekey(1, new_root),
+            Err(ElaraError::RatchetOutOfSync)
+        ));
+    }
+
+    #[test]
+    fn test_previous_epoch_grace_period() {
+        let (mut sender, mut receiver) = create_test_processors();
+        let in_flight = encrypt_core(&mut sender, b"sent before rekey");
+
+        receiver.rekey(1, [0x24u8; KEY_SIZE]).unwrap();
+        let decrypted = receiver.decrypt_frame(&in_flight).unwrap();
+        assert_eq!(decrypted.payload, b"sent before rekey");
+
+        // Once the grace period ends the old epoch is refused
+        let (mut sender, mut receiver) = create_test_processors();
+        let in_flight = encrypt_core(&mut sender, b"too late");
+        receiver.set_epoch_grace(Duration::ZERO);
+        receiver.rekey(1, [0x24u8; KEY_SIZE]).unwrap();
+        assert!(matches!(
+            receiver.decrypt_frame(&in_flight),
+            Err(ElaraError::RatchetOutOfSync)
+        ));
+    }
+
+    #[test]
+    fn test_suite1_roundtrip_and_mismatch() {
+        let session_id = SessionId::new(12345);
+        let session_key = [0x42u8; KEY_SIZE];
+        let gcm = |node| {
+            SecureFrameProcessor::with_suite(session_id, node, session_key, CryptoSuite::Suite1)
+                .unwrap()
+        };
+        let mut sender = gcm(NodeId::new(1));
+        let mut receiver = gcm(NodeId::new(2));
+        let mut chacha_receiver = SecureFrameProcessor::new(session_id, NodeId::new(3), session_key);
+
+        let encrypted = encrypt_core(&mut sender, b"over AES-GCM");
+        let header = Frame::parse(&encrypted).unwrap().header;
+        assert_eq!(header.crypto_suite, CryptoSuite::Suite1);
+
+        let decrypted = receiver.decrypt_frame(&encrypted).unwrap();
+        assert_eq!(decrypted.payload, b"over AES-GCM");
+        assert!(matches!(
+            chacha_receiver.decrypt_frame(&encrypted),
+            Err(ElaraError::CryptoSuiteMismatch {
+                expected: 0,
+                actual: 1
+            })
+        ));
+    }
+
+    #[test]
+    fn test_compression_roundtrip_and_limit() {
+        let (mut sender, mut receiver) = create_test_processors();
+        sender.set_compression(CompressionAlgorithm::Lz4);
+        receiver.set_max_decompressed_size(1024);
+
+        let text = b"feed item feed item feed item ".repeat(20);
+        let encrypted = encrypt_core(&mut sender, &text);
+        let frame = Frame::parse(&encrypted).unwrap();
+        assert!(frame.header.flags.is_compressed());
+        assert_eq!(
+            frame.extensions.compression_hint,
+            Some(CompressionAlgorithm::Lz4.to_byte())
+        );
+        assert!(encrypted.len() < text.len());
+        assert_eq!(receiver.decrypt_frame(&encrypted).unwrap().payload, text);
+
+        // Small payloads skip compression
+        let encrypted = encrypt_core(&mut sender, b"hi");
+        assert!(!Frame::parse(&encrypted).unwrap().header.flags.is_compressed());
+        assert_eq!(receiver.decrypt_frame(&encrypted).unwrap().payload, b"hi");
+
+        // Payloads expanding past the receiver's limit are rejected
+        let encrypted = encrypt_core(&mut sender, &[0u8; 4096]);
+        assert!(matches!(
+            receiver.decrypt_frame(&encrypted),
+            Err(ElaraError::DecompressionFailed(_))
+        ));
+    }
+
     #[test]
     fn test_batch_processor() {
         let (proc1, _proc2) = create_test_processors();
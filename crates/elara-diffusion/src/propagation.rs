@@ -163,7 +163,7 @@ impl PropagationScheduler {
         }
 
         // Sort by priority (highest first)
-        decisions.sort_by(|a, b| b.priority.cmp(&a.priority));
+        decisions.sort_by_key(|d| std::cmp::Reverse(d.priority));
 
         decisions
     }
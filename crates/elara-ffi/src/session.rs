@@ -1,7 +1,7 @@
 #![allow(clippy::missing_safety_doc)]
 //! FFI Session functions
 
-use std::ffi::{c_int, c_void};
+use std::ffi::{c_char, c_int, c_void, CStr};
 use std::ptr;
 use std::time::{Duration, Instant};
 
@@ -347,6 +347,128 @@ pub unsafe extern "C" fn elara_session_stream_metadata(
     ElaraBytes::from_vec(buf)
 }
 
+/// Keep an event log in directory `dir` and rebuild state from it
+///
+/// Required before `elara_session_state_at` and
+/// `elara_session_state_changes` can answer.
+#[no_mangle]
+pub unsafe extern "C" fn elara_session_open_history(
+    handle: *mut ElaraSessionHandle,
+    dir: *const c_char,
+) -> c_int {
+    if handle.is_null() || dir.is_null() {
+        set_last_error("Null pointer");
+        return ElaraErrorCode::InvalidArgument as c_int;
+    }
+
+    let Ok(dir) = CStr::from_ptr(dir).to_str() else {
+        set_last_error("Invalid directory");
+        return ElaraErrorCode::InvalidArgument as c_int;
+    };
+    let opened = elara_state::FileEventStore::open(dir)
+        .and_then(|store| (*handle).node.attach_event_store(Box::new(store)));
+    match opened {
+        Ok(_) => 0,
+        Err(err) => {
+            set_last_error(&err.to_string());
+            ElaraErrorCode::InternalError as c_int
+        }
+    }
+}
+
+/// Atom `state_id` as it stood at `state_time_ms`, replayed from history
+///
+/// Layout: version frontier (u16 count, then node id and clock as u64
+/// pairs), a present flag (u8), then the value (u32 length and bytes).
+#[no_mangle]
+pub unsafe extern "C" fn elara_session_state_at(
+    handle: *mut ElaraSessionHandle,
+    state_id: u64,
+    state_time_ms: i64,
+) -> ElaraBytes {
+    if handle.is_null() {
+        set_last_error("Null handle");
+        return ElaraBytes::empty();
+    }
+
+    let at = elara_core::StateTime::from_millis(state_time_ms);
+    match (*handle)
+        .node
+        .state_at(elara_core::StateId::new(state_id), at)
+    {
+        Ok(projection) => {
+            let mut buf = Vec::new();
+            encode_projection(&mut buf, &projection);
+            ElaraBytes::from_vec(buf)
+        }
+        Err(err) => {
+            set_last_error(&err.to_string());
+            ElaraBytes::empty()
+        }
+    }
+}
+
+/// Logged changes to atom `state_id` between `from_ms` and `to_ms`
+///
+/// Layout: the projection at `from_ms` and at `to_ms` (as in
+/// `elara_session_state_at`), a u32 record count, then per record its
+/// state time in ms (i64), a kind (u8: 0 event, other values reserved)
+/// and, for events, the source node id and sequence number (u64 each).
+#[no_mangle]
+pub unsafe extern "C" fn elara_session_state_changes(
+    handle: *mut ElaraSessionHandle,
+    state_id: u64,
+    from_ms: i64,
+    to_ms: i64,
+) -> ElaraBytes {
+    if handle.is_null() {
+        set_last_error("Null handle");
+        return ElaraBytes::empty();
+    }
+
+    let changes = (*handle).node.state_changes_between(
+        elara_core::StateId::new(state_id),
+        elara_core::StateTime::from_millis(from_ms),
+        elara_core::StateTime::from_millis(to_ms),
+    );
+    let changes = match changes {
+        Ok(changes) => changes,
+        Err(err) => {
+            set_last_error(&err.to_string());
+            return ElaraBytes::empty();
+        }
+    };
+
+    let mut buf = Vec::new();
+    encode_projection(&mut buf, &changes.before);
+    encode_projection(&mut buf, &changes.after);
+    buf.extend_from_slice(&(changes.records.len() as u32).to_le_bytes());
+    for record in &changes.records {
+        buf.extend_from_slice(&record.at().as_millis().to_le_bytes());
+        let elara_state::LogRecord::Applied { event, .. } = record;
+        buf.push(0);
+        buf.extend_from_slice(&event.source.to_bytes());
+        buf.extend_from_slice(&event.id.seq.to_le_bytes());
+    }
+
+    ElaraBytes::from_vec(buf)
+}
+
+fn encode_projection(buf: &mut Vec<u8>, projection: &elara_state::AtomProjection) {
+    let mut frontier = projection.frontier.to_compact();
+    frontier.sort();
+    buf.extend_from_slice(&(frontier.len() as u16).to_le_bytes());
+    for (node, clock) in frontier {
+        buf.extend_from_slice(&node.to_bytes());
+        buf.extend_from_slice(&clock.to_le_bytes());
+    }
+    let value = projection.atom.as_ref().map(|atom| atom.value.as_slice());
+    buf.push(value.is_some() as u8);
+    let value = value.unwrap_or_default();
+    buf.extend_from_slice(&(value.len() as u32).to_le_bytes());
+    buf.extend_from_slice(value);
+}
+
 /// Send a message to a peer
 #[no_mangle]
 pub unsafe extern "C" fn elara_session_send(
@@ -490,7 +612,7 @@ pub unsafe extern "C" fn elara_session_tick(handle: *mut ElaraSessionHandle) ->
 
     (*handle).node.tick();
     let elapsed = (*handle).last_activity.elapsed();
-    let new_level = if elapsed <= Duration::from_secs(2) {
+    let idle_level = if elapsed <= Duration::from_secs(2) {
         elara_core::DegradationLevel::L0_FullPerception
     } else if elapsed <= Duration::from_secs(5) {
         elara_core::DegradationLevel::L1_DistortedPerception
@@ -503,6 +625,8 @@ pub unsafe extern "C" fn elara_session_tick(handle: *mut ElaraSessionHandle) ->
     } else {
         elara_core::DegradationLevel::L5_LatentPresence
     };
+    // Diverging state degrades the session even while traffic flows
+    let new_level = idle_level.max((*handle).node.degradation_level());
 
     if new_level != (*handle).degradation {
         (*handle).degradation = new_level;
@@ -551,7 +675,7 @@ fn decode_event_blocks(
     let mut events = Vec::new();
     let mut offset = 0;
 
-    while payload.len().saturating_sub(offset) >= 13 {
+    while payload.len().saturating_sub(offset) >= 21 {
         let event_type = match elara_core::EventType::from_byte(payload[offset]) {
             Some(t) => t,
             None => break,
@@ -568,6 +692,16 @@ fn decode_event_blocks(
         };
         offset = state_end;
 
+        let seq_end = offset + 8;
+        if seq_end > payload.len() {
+            break;
+        }
+        let seq = match payload[offset..seq_end].try_into() {
+            Ok(bytes) => u64::from_le_bytes(bytes),
+            Err(_) => break,
+        };
+        offset = seq_end;
+
         let version_len_end = offset + 2;
         if version_len_end > payload.len() {
             break;
@@ -612,7 +746,6 @@ fn decode_event_blocks(
         }
         offset = delta_end;
 
-        let seq = version_ref.get(source).saturating_add(1);
         let event = elara_core::Event::new(source, seq, event_type, state_id, mutation)
             .with_version(version_ref)
             .with_time_intent(elara_core::TimeIntent::new(time_hint));
@@ -623,7 +756,7 @@ fn decode_event_blocks(
 }
 
 fn decode_version_vector(buf: &[u8]) -> Option<elara_core::VersionVector> {
-    if !buf.len().is_multiple_of(16) {
+    if buf.len() % 16 != 0 {
         return None;
     }
     let mut entries = Vec::new();
@@ -696,4 +829,33 @@ mod tests {
         unsafe { elara_session_free(session) };
         unsafe { crate::identity::elara_identity_free(identity) };
     }
+
+    #[test]
+    fn test_session_state_at_needs_history() {
+        let identity = elara_identity_generate();
+        let session = unsafe { elara_session_create(identity, 12345) };
+
+        let bytes = unsafe { elara_session_state_at(session, 7, 0) };
+        assert_eq!(bytes.len, 0);
+
+        let dir = std::env::temp_dir().join(format!("elara-ffi-history-{}", std::process::id()));
+        let path = std::ffi::CString::new(dir.to_str().unwrap()).unwrap();
+        assert_eq!(
+            unsafe { elara_session_open_history(session, path.as_ptr()) },
+            0
+        );
+
+        // Empty frontier, absent atom, empty value
+        let bytes = unsafe { elara_session_state_at(session, 7, 0) };
+        let data = unsafe { Vec::from_raw_parts(bytes.data, bytes.len, bytes.capacity) };
+        assert_eq!(data, [0, 0, 0, 0, 0, 0, 0]);
+
+        let bytes = unsafe { elara_session_state_changes(session, 7, 0, 1000) };
+        let data = unsafe { Vec::from_raw_parts(bytes.data, bytes.len, bytes.capacity) };
+        assert_eq!(data.len(), 7 + 7 + 4);
+
+        unsafe { elara_session_free(session) };
+        unsafe { crate::identity::elara_identity_free(identity) };
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
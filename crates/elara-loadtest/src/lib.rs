@@ -183,8 +183,8 @@ impl LoadTestScenario {
             // We need to collect frames first, then distribute them
             let mut all_frames: Vec<(usize, Vec<u8>)> = Vec::new();
             
-            for i in 0..nodes.len() {
-                while let Some(_frame) = nodes[i].node_mut().pop_outgoing() {
+            for (i, node) in nodes.iter_mut().enumerate() {
+                while let Some(_frame) = node.node_mut().pop_outgoing() {
                     // Serialize frame for distribution
                     // For now, just track that we have frames
                     all_frames.push((i, vec![]));
@@ -155,15 +155,13 @@ pub fn generate_test_message(size: usize) -> Vec<u8> {
 #[allow(dead_code)]
 pub fn generate_random_message(size: usize) -> Vec<u8> {
     use std::collections::hash_map::RandomState;
-    use std::hash::{BuildHasher, Hash, Hasher};
+    use std::hash::BuildHasher;
     
     let mut data = Vec::with_capacity(size);
     let hasher_builder = RandomState::new();
     
     for i in 0..size {
-        let mut hasher = hasher_builder.build_hasher();
-        i.hash(&mut hasher);
-        data.push((hasher.finish() & 0xFF) as u8);
+        data.push((hasher_builder.hash_one(i) & 0xFF) as u8);
     }
     
     data
@@ -9,3 +9,46 @@ pub mod voice;
 
 pub use text::*;
 pub use voice::*;
+
+use elara_state::MergePolicies;
+
+/// Register the MSP text and voice merge policies under their state types
+pub fn register_policies(policies: &mut MergePolicies) {
+    policies.register(STATE_TYPE_TEXT..=STATE_TYPE_TEXT, text_policy());
+    policies.register(STATE_TYPE_PRESENCE..=STATE_TYPE_PRESENCE, presence_policy());
+    policies.register(STATE_TYPE_TYPING..=STATE_TYPE_TYPING, typing_policy());
+    policies.register(STATE_TYPE_FEED..=STATE_TYPE_FEED, feed_policy());
+    policies.register(STATE_TYPE_VOICE..=STATE_TYPE_VOICE, voice_policy());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use elara_core::{DeltaLaw, Event, EventType, MutationOp, NodeId, StateAtom, StateType};
+
+    #[test]
+    fn test_registered_policies_shape_msp_atoms() {
+        let mut policies = MergePolicies::new();
+        register_policies(&mut policies);
+
+        let user = NodeId::new(1);
+        let state = text_stream_id(3);
+        let event = Event::new(
+            user,
+            1,
+            EventType::TextAppend,
+            state,
+            MutationOp::Append(b"hi".to_vec()),
+        );
+        let mut atom = StateAtom::new(state, StateType::Perceptual, user);
+        policies.for_state(state).init_atom(&mut atom, &event);
+
+        let expected = create_text_atom(3, user);
+        assert_eq!(atom.state_type, expected.state_type);
+        assert!(matches!(
+            atom.delta_law,
+            DeltaLaw::AppendOnly { max_size: 1000 }
+        ));
+        assert_eq!(atom.bounds.max_size, expected.bounds.max_size);
+    }
+}
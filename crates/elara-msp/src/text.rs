@@ -8,6 +8,7 @@
 use elara_core::{
     DeltaLaw, MessageId, NodeId, StateAtom, StateBounds, StateId, StateTime, StateType,
 };
+use elara_state::TemplatePolicy;
 
 /// State type prefixes for text profile
 pub const STATE_TYPE_TEXT: u16 = 0x0001;
@@ -480,40 +481,62 @@ impl TypingIndicator {
     }
 }
 
+/// Merge policy for text streams: append-only, 10 messages per second
+pub fn text_policy() -> TemplatePolicy {
+    TemplatePolicy::new(StateType::Core, DeltaLaw::AppendOnly { max_size: 1000 }).with_bounds(
+        StateBounds {
+            max_size: 1024 * 1024,                                  // 1MB
+            rate_limit: Some(elara_core::RateLimit::new(10, 1000)), // 10 msg/sec
+            max_entropy: 1.0,
+        },
+    )
+}
+
+/// Merge policy for feeds: append-only, 5 posts per second
+pub fn feed_policy() -> TemplatePolicy {
+    TemplatePolicy::new(StateType::Core, DeltaLaw::AppendOnly { max_size: 5000 }).with_bounds(
+        StateBounds {
+            max_size: 5 * 1024 * 1024,
+            rate_limit: Some(elara_core::RateLimit::new(5, 1000)),
+            max_entropy: 1.0,
+        },
+    )
+}
+
+/// Merge policy for presence: last writer wins
+pub fn presence_policy() -> TemplatePolicy {
+    TemplatePolicy::new(StateType::Core, DeltaLaw::LastWriterWins)
+}
+
+/// Merge policy for typing indicators: perceptual, last writer wins
+pub fn typing_policy() -> TemplatePolicy {
+    TemplatePolicy::new(StateType::Perceptual, DeltaLaw::LastWriterWins)
+}
+
 /// Create a text state atom
 pub fn create_text_atom(stream_id: u64, owner: NodeId) -> StateAtom {
     let mut atom = StateAtom::new(text_stream_id(stream_id), StateType::Core, owner);
-    atom.delta_law = DeltaLaw::AppendOnly { max_size: 1000 };
-    atom.bounds = StateBounds {
-        max_size: 1024 * 1024,                                  // 1MB
-        rate_limit: Some(elara_core::RateLimit::new(10, 1000)), // 10 msg/sec
-        max_entropy: 1.0,
-    };
+    text_policy().apply(&mut atom);
     atom
 }
 
 pub fn create_feed_atom(stream_id: u64, owner: NodeId) -> StateAtom {
     let mut atom = StateAtom::new(feed_stream_id(stream_id), StateType::Core, owner);
-    atom.delta_law = DeltaLaw::AppendOnly { max_size: 5000 };
-    atom.bounds = StateBounds {
-        max_size: 5 * 1024 * 1024,
-        rate_limit: Some(elara_core::RateLimit::new(5, 1000)),
-        max_entropy: 1.0,
-    };
+    feed_policy().apply(&mut atom);
     atom
 }
 
 /// Create a presence state atom
 pub fn create_presence_atom(user_id: NodeId) -> StateAtom {
     let mut atom = StateAtom::new(presence_id(user_id), StateType::Core, user_id);
-    atom.delta_law = DeltaLaw::LastWriterWins;
+    presence_policy().apply(&mut atom);
     atom
 }
 
 /// Create a typing state atom
 pub fn create_typing_atom(user_id: NodeId) -> StateAtom {
     let mut atom = StateAtom::new(typing_id(user_id), StateType::Perceptual, user_id);
-    atom.delta_law = DeltaLaw::LastWriterWins;
+    typing_policy().apply(&mut atom);
     atom
 }
 
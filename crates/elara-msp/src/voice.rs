@@ -12,6 +12,7 @@
 use elara_core::{
     DeltaLaw, InterpolationType, NodeId, StateAtom, StateBounds, StateId, StateTime, StateType,
 };
+use elara_state::TemplatePolicy;
 
 /// State type prefix for voice
 pub const STATE_TYPE_VOICE: u16 = 0x0010;
@@ -331,18 +332,26 @@ impl VoiceDegradationLevel {
     }
 }
 
-/// Create a voice state atom
-pub fn create_voice_atom(user_id: NodeId) -> StateAtom {
-    let mut atom = StateAtom::new(voice_id(user_id), StateType::Perceptual, user_id);
-    atom.delta_law = DeltaLaw::ContinuousBlend {
-        interpolation: InterpolationType::Linear,
-        max_deviation: 0.3,
-    };
-    atom.bounds = StateBounds {
+/// Merge policy for voice: blended samples, 100 frames per second
+pub fn voice_policy() -> TemplatePolicy {
+    TemplatePolicy::new(
+        StateType::Perceptual,
+        DeltaLaw::ContinuousBlend {
+            interpolation: InterpolationType::Linear,
+            max_deviation: 0.3,
+        },
+    )
+    .with_bounds(StateBounds {
         max_size: 1024,
         rate_limit: Some(elara_core::RateLimit::new(100, 1000)), // 100 frames/sec
         max_entropy: 1.0,
-    };
+    })
+}
+
+/// Create a voice state atom
+pub fn create_voice_atom(user_id: NodeId) -> StateAtom {
+    let mut atom = StateAtom::new(voice_id(user_id), StateType::Perceptual, user_id);
+    voice_policy().apply(&mut atom);
     atom
 }
 
@@ -183,7 +183,7 @@ async fn main() {
     
     let node5 = Arc::new(Node::with_config(disabled_config.clone()));
     
-    if let Some(_) = disabled_config.init_health_checks(node5) {
+    if disabled_config.init_health_checks(node5).is_some() {
         println!("Health checks initialized");
     } else {
         println!("✓ Health checks disabled (as configured)");
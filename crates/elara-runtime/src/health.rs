@@ -1572,10 +1572,12 @@ mod builtin_tests {
     
     #[test]
     fn test_health_check_config_validation_cache_ttl() {
-        let mut config = HealthCheckConfig::default();
+        let mut config = HealthCheckConfig {
+            cache_ttl: Duration::from_secs(1),
+            ..Default::default()
+        };
         
         // Valid cache TTL
-        config.cache_ttl = Duration::from_secs(1);
         assert!(config.validate().is_ok());
         
         // Invalid cache TTL (too short)
@@ -1589,10 +1591,12 @@ mod builtin_tests {
     
     #[test]
     fn test_health_check_config_validation_min_connections() {
-        let mut config = HealthCheckConfig::default();
+        let mut config = HealthCheckConfig {
+            min_connections: Some(1),
+            ..Default::default()
+        };
         
         // Valid min_connections
-        config.min_connections = Some(1);
         assert!(config.validate().is_ok());
         
         // Invalid min_connections (zero)
@@ -1610,10 +1614,12 @@ mod builtin_tests {
     
     #[test]
     fn test_health_check_config_validation_max_memory() {
-        let mut config = HealthCheckConfig::default();
+        let mut config = HealthCheckConfig {
+            max_memory_mb: Some(1),
+            ..Default::default()
+        };
         
         // Valid max_memory_mb
-        config.max_memory_mb = Some(1);
         assert!(config.validate().is_ok());
         
         // Invalid max_memory_mb (zero)
@@ -1631,10 +1637,12 @@ mod builtin_tests {
     
     #[test]
     fn test_health_check_config_validation_max_time_drift() {
-        let mut config = HealthCheckConfig::default();
+        let mut config = HealthCheckConfig {
+            max_time_drift_ms: Some(1),
+            ..Default::default()
+        };
         
         // Valid max_time_drift_ms
-        config.max_time_drift_ms = Some(1);
         assert!(config.validate().is_ok());
         
         // Invalid max_time_drift_ms (zero)
@@ -1656,10 +1664,12 @@ mod builtin_tests {
     
     #[test]
     fn test_health_check_config_validation_max_pending_events() {
-        let mut config = HealthCheckConfig::default();
+        let mut config = HealthCheckConfig {
+            max_pending_events: Some(1),
+            ..Default::default()
+        };
         
         // Valid max_pending_events
-        config.max_pending_events = Some(1);
         assert!(config.validate().is_ok());
         
         // Invalid max_pending_events (zero)
@@ -1,25 +1,77 @@
 //! ELARA Node - Runtime loop implementation
 
 use std::collections::{HashMap, VecDeque};
+use std::io;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use elara_core::{
-    Event, EventType, MessageId, MutationOp, NodeId, PacketClass, RepresentationProfile, SessionId,
-    StateId, StateTime, TimeIntent, VersionVector,
+    AuthorityProof, AuthorityScope, DegradationLevel, DelegationLink, ElaraError, Event, EventType,
+    MessageId, MutationOp, NodeId, PacketClass, RepresentationProfile, SessionId, StateId,
+    StateTime, TimeIntent, VersionVector,
 };
-use elara_crypto::{Identity, SecureFrameProcessor};
-use elara_state::ReconciliationEngine;
-use elara_time::TimeEngine;
+use elara_crypto::{
+    GroupKeySchedule, GroupRekey, Handshake, HandshakeConfirm, HandshakeMessage, HandshakeOutcome,
+    HandshakeRole, Identity, PublicIdentity, SecureFrameProcessor,
+};
+use elara_state::{
+    AtomChanges, AtomProjection, DegradationChange, EventStore, MergeOutcome, PartitionMergeReport,
+    ReconciliationConfig, ReconciliationEngine, ResponseAssembler, StateDigest, StateRequest,
+    StateResponse, MAX_DELEGATION_DEPTH,
+};
+use elara_time::{TimeEngine, TimeSyncProbe, TimeSyncReply};
 use elara_visual::{
     livestream_state_id, stream_visual_state_id, visual_state_id, PredictionConfig, VisualEncoder,
-    VisualPredictor, VisualState, VisualStateBuffer,
+    VisualPredictor, VisualState, VisualStateBuffer, STATE_TYPE_LIVESTREAM, STATE_TYPE_VISUAL,
+};
+use elara_voice::VoiceEncoder;
+use elara_wire::{
+    compress_payload, decompress_frame_payload, is_parity_frame, Arrival, CompressionAlgorithm,
+    CryptoSuite, Extensions, FecDecoder, FecEncoder, FixedHeader, FragmentInfo,
+    FragmentReassembler, Fragmenter, Frame, FrameBuilder, SequenceTracker, AUTH_TAG_SIZE,
+    MAX_DECOMPRESSED_SIZE, MAX_FEC_PAYLOAD,
 };
-use elara_wire::{Extensions, FixedHeader, Frame, FrameBuilder, AUTH_TAG_SIZE};
 
 use crate::observability::metrics::NodeMetrics;
 use crate::observability::ObservabilityConfig;
 
+/// Answered handshakes kept while waiting for the initiator's confirmation
+const MAX_UNCONFIRMED_JOINS: usize = 16;
+
+/// Join handle for a background health check HTTP server
+pub type HealthServerHandle = tokio::task::JoinHandle<Result<(), std::io::Error>>;
+
+/// Minimum per-event trailer: time offset (4), signature (64), chain length (1)
+const EVENT_TRAILER_SIZE: usize = 4 + 64 + 1;
+
+/// Fixed part of an event block: type, state, sequence and both length prefixes
+const EVENT_BLOCK_HEADER_SIZE: usize = 1 + 8 + 8 + 2 + 2;
+
+/// Largest digest or response chunk carried by one repair event
+const MAX_REPAIR_CHUNK: usize = 1024;
+
+/// How long before an atom may be requested again when digests are off
+const STATE_REQUEST_RETRY: Duration = Duration::from_secs(1);
+
+/// How long responses to a state request are accepted
+const STATE_RESPONSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Partition merge reports kept for the application before the oldest drop
+const MAX_PARTITION_REPORTS: usize = 64;
+
+/// State type prefix of voice atoms (`elara_msp::voice::STATE_TYPE_VOICE`)
+const STATE_TYPE_VOICE: u16 = 0x0010;
+
+/// Profile a state atom is rendered under, from its state type prefix
+fn profile_for_state(state: StateId) -> RepresentationProfile {
+    match state.state_type() {
+        STATE_TYPE_VOICE => RepresentationProfile::VoiceMinimal,
+        STATE_TYPE_VISUAL => RepresentationProfile::VideoStandard,
+        STATE_TYPE_LIVESTREAM => RepresentationProfile::StreamAsymmetric,
+        _ => RepresentationProfile::Textual,
+    }
+}
+
 /// ELARA Node configuration
 ///
 /// # Observability
@@ -148,6 +200,39 @@ pub struct NodeConfig {
     /// };
     /// ```
     pub health_checks: Option<crate::health::HealthCheckConfig>,
+    /// Crypto suites offered in session handshakes, most preferred first
+    ///
+    /// Defaults to `Suite0` (ChaCha20-Poly1305). Relays on AES-NI hardware can
+    /// list `Suite1` (AES-256-GCM) first and keep `Suite0` as a fallback.
+    pub crypto_suites: Vec<CryptoSuite>,
+    /// Compression for outgoing payloads above `COMPRESSION_THRESHOLD`
+    ///
+    /// Receivers decompress whatever the frame declares, so peers do not
+    /// need matching settings.
+    pub compression: CompressionAlgorithm,
+    /// Longest an event may wait for others of its class to share a frame
+    ///
+    /// Classes without an entry are flushed on the tick their events are
+    /// queued, so batching only packs events produced together.
+    pub max_batch_delay: HashMap<PacketClass, Duration>,
+    /// How often to send peers a digest of Core state for anti-entropy
+    ///
+    /// Peers request the atoms the digest shows them behind on. `None`
+    /// stops sending digests; peers' digests and requests are still served.
+    pub sync_interval: Option<Duration>,
+    /// Repair-class bytes per second shared by digests, requests and
+    /// responses, with up to one second's worth sent in a burst
+    pub repair_bandwidth: usize,
+    /// How often to snapshot the state field once an event store is attached
+    ///
+    /// A restarted node replays only the log written since the latest
+    /// snapshot.
+    pub snapshot_interval: Duration,
+    /// Tunables and authority and merge policies for state reconciliation
+    ///
+    /// Profiles register their merge policies here, e.g. with
+    /// `elara_msp::register_policies(&mut config.reconciliation.merge_policies)`.
+    pub reconciliation: ReconciliationConfig,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -160,6 +245,95 @@ pub struct RuntimeStats {
     pub packets_in: u64,
     pub packets_out: u64,
     pub last_tick_duration: Duration,
+    /// Events sent inside batched frames
+    pub events_batched: u64,
+    /// Batched frames sent (a fragmented batch counts once)
+    pub batches_sent: u64,
+    /// Quarantined events that expired before their dependencies arrived
+    pub quarantine_expired: u64,
+    /// State requests sent for history missing locally
+    pub state_requests_sent: u64,
+    /// State requests received from peers
+    pub state_requests_received: u64,
+    /// Digests of local state sent to peers
+    pub state_digests_sent: u64,
+    /// Response chunks sent in answer to requests
+    pub state_responses_sent: u64,
+    /// Peer responses whose events caught up local state
+    pub repairs_applied: u64,
+    /// Repair events not sent for lack of repair bandwidth
+    pub repair_events_throttled: u64,
+    /// Events rejected by their target atom's rate limit
+    pub events_rate_limited: u64,
+    /// Events rejected for exceeding their target atom's size bound
+    pub events_oversized: u64,
+    /// State field snapshots written to the event store
+    pub state_snapshots_saved: u64,
+    /// Healed partitions whose histories were merged
+    pub partitions_merged: u64,
+    /// Atoms partition merges left conflicted
+    pub partition_conflicts: u64,
+    /// Media atoms degraded for divergence
+    pub atoms_degraded: u64,
+    /// Peer frames that never arrived, by their sequence numbers
+    pub frames_lost: u64,
+    /// Peer frames that arrived behind a later one
+    pub frames_reordered: u64,
+    /// Clock probes sent to peers
+    pub time_probes_sent: u64,
+    /// Peer answers to clock probes used for time sync
+    pub time_probes_answered: u64,
+}
+
+impl RuntimeStats {
+    /// Average number of events carried per outgoing packet
+    pub fn events_per_packet(&self) -> f64 {
+        if self.batches_sent == 0 {
+            return 0.0;
+        }
+        self.events_batched as f64 / self.batches_sent as f64
+    }
+}
+
+/// Token bucket over repair-class bytes
+struct RepairBudget {
+    tokens: f64,
+    refilled: Instant,
+}
+
+impl RepairBudget {
+    fn new(bandwidth: usize) -> Self {
+        RepairBudget {
+            tokens: bandwidth as f64,
+            refilled: Instant::now(),
+        }
+    }
+
+    /// Spend `bytes` if the bucket, refilled at `bandwidth`, holds them
+    fn take(&mut self, bandwidth: usize, bytes: usize) -> bool {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.refilled).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * bandwidth as f64).min(bandwidth as f64);
+        self.refilled = now;
+        if self.tokens < bytes as f64 {
+            return false;
+        }
+        self.tokens -= bytes as f64;
+        true
+    }
+}
+
+/// Event blocks of one class and profile waiting to share a frame
+struct PendingBatch {
+    class: PacketClass,
+    profile: RepresentationProfile,
+    /// Time hint of the first event; the receiver applies it to the batch
+    time_hint: i32,
+    payload: Vec<u8>,
+    events: usize,
+    deadline: Instant,
+    /// No further event fits
+    full: bool,
 }
 
 #[derive(Clone, Debug)]
@@ -261,6 +435,13 @@ impl Default for NodeConfig {
             metrics: None,
             observability: None, // Observability disabled by default
             health_checks: None, // Health checks disabled by default
+            crypto_suites: vec![CryptoSuite::Suite0],
+            compression: CompressionAlgorithm::Lz4,
+            max_batch_delay: HashMap::new(),
+            sync_interval: Some(Duration::from_secs(1)),
+            repair_bandwidth: 64 * 1024,
+            snapshot_interval: Duration::from_secs(60),
+            reconciliation: ReconciliationConfig::default(),
         }
     }
 }
@@ -333,10 +514,7 @@ impl NodeConfig {
     pub fn init_health_checks(
         &self,
         node: Arc<Node>,
-    ) -> Option<(
-        Arc<crate::health::HealthChecker>,
-        Option<tokio::task::JoinHandle<Result<(), std::io::Error>>>,
-    )> {
+    ) -> Option<(Arc<crate::health::HealthChecker>, Option<HealthServerHandle>)> {
         use crate::health::{
             ConnectionHealthCheck, HealthChecker, MemoryHealthCheck, StateDivergenceCheck,
             TimeDriftCheck,
@@ -414,10 +592,34 @@ pub struct Node {
     /// State reconciliation engine
     state_engine: ReconciliationEngine,
     secure_processor: Option<SecureFrameProcessor>,
+    /// Pending handshake (initiator side)
+    pending_handshake: Option<Handshake>,
+    /// Answer initiator handshakes for the current session
+    accept_handshakes: bool,
+    /// Answered handshakes awaiting key confirmation, by initiator ephemeral
+    unconfirmed_joins: HashMap<[u8; 32], HandshakeOutcome>,
+    /// Peers authenticated through a handshake
+    peer_identities: HashMap<NodeId, PublicIdentity>,
+    /// Group key schedule (distributor on the session host)
+    group_keys: Option<GroupKeySchedule>,
     /// Incoming packet buffer
     incoming: VecDeque<Frame>,
     /// Outgoing packet buffer
     outgoing: VecDeque<Frame>,
+    /// Splits oversized event blocks across frames
+    fragmenter: Fragmenter,
+    /// Incoming fragments awaiting the rest of their message
+    reassembler: FragmentReassembler,
+    /// Sequence counter for fragments of unencrypted frames
+    plain_fragment_seq: u16,
+    /// Parity generation for outgoing encrypted frames
+    fec_encoder: FecEncoder,
+    /// Loss recovery for incoming frames
+    fec_decoder: FecDecoder,
+    /// Signed events waiting to be packed into frames
+    pending_batches: Vec<PendingBatch>,
+    /// Per-peer, per-class sequence windows of received frames
+    sequences: SequenceTracker,
     /// Local events to send
     local_events: Vec<Event>,
     /// Event sequence counter
@@ -432,6 +634,21 @@ pub struct Node {
     stream_visual_predictors: HashMap<u64, VisualPredictor>,
     /// Optional metrics (cloned from config for convenience)
     metrics: Option<NodeMetrics>,
+    /// Chunked peer responses being reassembled
+    responses: ResponseAssembler,
+    /// When the last digest went out
+    last_sync: Instant,
+    /// When the last clock probe went out
+    last_time_sync: Instant,
+    /// Atoms requested and when: each is asked for at most once per repair
+    /// round, and only responses for atoms still awaited are applied
+    requested: HashMap<StateId, Instant>,
+    /// Rate limit for repair-class events
+    repair_budget: RepairBudget,
+    /// When the state field was last snapshotted
+    last_snapshot: Instant,
+    /// Partition merge reports not yet taken by the application
+    partition_reports: VecDeque<PartitionMergeReport>,
 }
 
 impl Node {
@@ -447,38 +664,34 @@ impl Node {
 
     /// Create a new node with custom configuration
     pub fn with_config(config: NodeConfig) -> Self {
-        let metrics = config.metrics.clone();
-        Node {
-            identity: Identity::generate(),
-            session_id: None,
-            time_engine: TimeEngine::new(),
-            state_engine: ReconciliationEngine::new(),
-            secure_processor: None,
-            incoming: VecDeque::new(),
-            outgoing: VecDeque::new(),
-            local_events: Vec::new(),
-            event_seq: 0,
-            config,
-            stats: RuntimeStats::default(),
-            stream_metadata: HashMap::new(),
-            visual_buffers: HashMap::new(),
-            visual_predictors: HashMap::new(),
-            stream_visual_buffers: HashMap::new(),
-            stream_visual_predictors: HashMap::new(),
-            metrics,
-        }
+        Self::with_identity(Identity::generate(), config)
     }
 
     pub fn with_identity(identity: Identity, config: NodeConfig) -> Self {
         let metrics = config.metrics.clone();
+        let repair_budget = RepairBudget::new(config.repair_bandwidth);
+        let mut state_engine = ReconciliationEngine::with_config(config.reconciliation.clone());
+        state_engine.keys_mut().insert(identity.public_identity());
         Node {
             identity,
             session_id: None,
             time_engine: TimeEngine::new(),
-            state_engine: ReconciliationEngine::new(),
+            state_engine,
             secure_processor: None,
+            pending_handshake: None,
+            accept_handshakes: false,
+            unconfirmed_joins: HashMap::new(),
+            peer_identities: HashMap::new(),
+            group_keys: None,
             incoming: VecDeque::new(),
             outgoing: VecDeque::new(),
+            fragmenter: Fragmenter::new(),
+            reassembler: FragmentReassembler::new(),
+            plain_fragment_seq: 0,
+            fec_encoder: FecEncoder::new(),
+            fec_decoder: FecDecoder::new(),
+            pending_batches: Vec::new(),
+            sequences: SequenceTracker::new(),
             local_events: Vec::new(),
             event_seq: 0,
             config,
@@ -489,6 +702,13 @@ impl Node {
             stream_visual_buffers: HashMap::new(),
             stream_visual_predictors: HashMap::new(),
             metrics,
+            responses: ResponseAssembler::new(),
+            last_sync: Instant::now(),
+            last_time_sync: Instant::now(),
+            requested: HashMap::new(),
+            repair_budget,
+            last_snapshot: Instant::now(),
+            partition_reports: VecDeque::new(),
         }
     }
 
@@ -519,11 +739,10 @@ impl Node {
         );
 
         self.session_id = Some(session_id);
-        self.secure_processor = Some(SecureFrameProcessor::new(
-            session_id,
-            self.node_id(),
-            session_key,
-        ));
+        self.state_engine.set_session(Some(session_id));
+        let mut processor = SecureFrameProcessor::new(session_id, self.node_id(), session_key);
+        processor.set_compression(self.config.compression);
+        self.secure_processor = Some(processor);
 
         // Update metrics: increment active connections and total connections
         if let Some(ref metrics) = self.metrics {
@@ -548,7 +767,75 @@ impl Node {
         );
 
         self.session_id = Some(session_id);
+        self.state_engine.set_session(Some(session_id));
+        self.secure_processor = None;
+        self.pending_batches.clear();
+
+        // Update metrics: increment active connections and total connections
+        if let Some(ref metrics) = self.metrics {
+            metrics.active_connections.inc();
+            metrics.total_connections.inc();
+        }
+    }
+
+    /// Join a session by initiating an authenticated handshake
+    ///
+    /// Sends a signed `SessionJoin` frame (class `Core`) carrying an ephemeral
+    /// X25519 key. Frames stay unencrypted until the responder answers and the
+    /// session root is derived.
+    pub fn initiate_session(&mut self, session_id: SessionId) {
+        let span = tracing::span!(
+            tracing::Level::INFO,
+            "initiate_session",
+            node_id = self.node_id().0,
+            session_id = session_id.0
+        );
+        let _enter = span.enter();
+
+        self.session_id = Some(session_id);
+        self.state_engine.set_session(Some(session_id));
+        self.secure_processor = None;
+        self.pending_batches.clear();
+        self.group_keys = None;
+        self.accept_handshakes = false;
+        self.unconfirmed_joins.clear();
+
+        let (handshake, message) =
+            Handshake::initiate_with_suites(&self.identity, session_id, &self.config.crypto_suites);
+        self.pending_handshake = Some(handshake);
+        self.queue_key_exchange_frame(session_id, EventType::SessionJoin, message.encode());
+
+        // Update metrics: increment active connections and total connections
+        if let Some(ref metrics) = self.metrics {
+            metrics.active_connections.inc();
+            metrics.total_connections.inc();
+        }
+    }
+
+    /// Join a session and answer handshakes from initiating peers
+    pub fn accept_session(&mut self, session_id: SessionId) {
+        let span = tracing::span!(
+            tracing::Level::INFO,
+            "accept_session",
+            node_id = self.node_id().0,
+            session_id = session_id.0
+        );
+        let _enter = span.enter();
+
+        tracing::info!(
+            node_id = self.node_id().0,
+            session_id = session_id.0,
+            "Accepting session handshakes"
+        );
+
+        self.session_id = Some(session_id);
+        self.state_engine.set_session(Some(session_id));
         self.secure_processor = None;
+        self.pending_batches.clear();
+        self.group_keys = None;
+        self.pending_handshake = None;
+        self.accept_handshakes = true;
+        self.unconfirmed_joins.clear();
 
         // Update metrics: increment active connections and total connections
         if let Some(ref metrics) = self.metrics {
@@ -557,6 +844,90 @@ impl Node {
         }
     }
 
+    /// Check if the session key has been established
+    pub fn is_secured(&self) -> bool {
+        self.secure_processor.is_some()
+    }
+
+    /// Get the identity of a peer authenticated through a handshake
+    pub fn peer_identity(&self, node_id: NodeId) -> Option<&PublicIdentity> {
+        self.peer_identities.get(&node_id)
+    }
+
+    /// Trust a peer's signing key learned outside a handshake
+    ///
+    /// Events are only applied from sources with a known key. Handshakes
+    /// register keys automatically; unsecured sessions and group members
+    /// that never handshake with each other need them added here.
+    pub fn add_peer_identity(&mut self, identity: PublicIdentity) {
+        self.state_engine.keys_mut().insert(identity);
+    }
+
+    /// Get this node's public identity
+    pub fn public_identity(&self) -> PublicIdentity {
+        self.identity.public_identity()
+    }
+
+    /// Sign a delegation of `scope` over atom `target_state` from this node
+    /// to `delegate`, valid in the current session only
+    ///
+    /// The delegate attaches the link (after any links leading to this node)
+    /// to its events' `AuthorityProof` to act on the atom. Returns `None`
+    /// outside a session.
+    pub fn grant_delegation(
+        &self,
+        delegate: NodeId,
+        target_state: StateId,
+        scope: AuthorityScope,
+        expiry: Option<StateTime>,
+    ) -> Option<DelegationLink> {
+        let session = self.session_id?;
+        let mut link = DelegationLink::new(
+            self.node_id(),
+            delegate,
+            session,
+            target_state,
+            scope,
+            expiry,
+        );
+        link.signature = self.identity.sign(&link.signing_bytes());
+        Some(link)
+    }
+
+    /// Current group key epoch (0 until the session host first rekeys)
+    pub fn key_epoch(&self) -> Option<u16> {
+        self.secure_processor.as_ref().map(|p| p.key_epoch())
+    }
+
+    /// Remove a peer from the session
+    ///
+    /// On the session host this rotates the group root, so the removed peer
+    /// cannot decrypt new traffic once the previous epoch's grace period ends.
+    pub fn remove_peer(&mut self, node_id: NodeId) {
+        tracing::info!(
+            node_id = self.node_id().0,
+            peer_id = node_id.0,
+            "Removing peer from session"
+        );
+
+        if let Some(processor) = self.secure_processor.as_mut() {
+            processor.remove_peer(node_id);
+        }
+        self.peer_identities.remove(&node_id);
+        self.state_engine.keys_mut().remove(node_id);
+        self.state_engine.forget_peer(node_id);
+        self.time_engine.forget_peer(node_id);
+        self.sequences.remove_node(node_id);
+
+        let removed = self
+            .group_keys
+            .as_mut()
+            .is_some_and(|schedule| schedule.remove_member(node_id));
+        if removed && self.accept_handshakes {
+            self.rekey_group();
+        }
+    }
+
     /// Leave current session
     pub fn leave_session(&mut self) {
         let span = tracing::span!(
@@ -574,6 +945,9 @@ impl Node {
                 "Leaving session"
             );
 
+            // Tell the session host so it can rotate the group root
+            self.queue_session_leave();
+
             // Update metrics: decrement active connections
             if let Some(ref metrics) = self.metrics {
                 metrics.active_connections.dec();
@@ -581,7 +955,13 @@ impl Node {
         }
 
         self.session_id = None;
+        self.state_engine.set_session(None);
         self.secure_processor = None;
+        self.pending_batches.clear();
+        self.group_keys = None;
+        self.pending_handshake = None;
+        self.accept_handshakes = false;
+        self.unconfirmed_joins.clear();
     }
 
     /// Queue an incoming frame for processing
@@ -614,6 +994,30 @@ impl Node {
         frame
     }
 
+    /// Take the oldest partition merge report, if any
+    pub fn pop_partition_report(&mut self) -> Option<PartitionMergeReport> {
+        self.partition_reports.pop_front()
+    }
+
+    /// Peers currently partitioned away from this node
+    pub fn partitioned_peers(&self) -> Vec<NodeId> {
+        self.state_engine.partitions().partitioned()
+    }
+
+    /// Degradation level of the session as a whole
+    pub fn degradation_level(&self) -> DegradationLevel {
+        self.state_engine.divergence().overall()
+    }
+
+    /// Degradation level of `peer`'s state under `profile`
+    pub fn peer_degradation(
+        &self,
+        peer: NodeId,
+        profile: RepresentationProfile,
+    ) -> DegradationLevel {
+        self.state_engine.divergence().level(peer, profile)
+    }
+
     /// Queue a local event to send
     pub fn queue_local_event(&mut self, event: Event) {
         if self.local_events.len() < self.config.max_local_events {
@@ -703,6 +1107,19 @@ impl Node {
         self.queue_local_event(event);
     }
 
+    /// Revoke a node's authority (direct or delegated) over a state atom
+    pub fn queue_authority_revoke(&mut self, target_state: StateId, node: NodeId) {
+        let seq = self.next_event_seq();
+        let event = Event::new(
+            self.node_id(),
+            seq,
+            EventType::AuthorityRevoke,
+            target_state,
+            MutationOp::Set(node.to_bytes().to_vec()),
+        );
+        self.queue_local_event(event);
+    }
+
     pub fn queue_feed_append(&mut self, feed_state: StateId, data: Vec<u8>, timestamp: StateTime) {
         let seq = self.next_event_seq();
         let time_intent = self.time_intent_for(timestamp);
@@ -750,9 +1167,12 @@ impl Node {
 
         // Stage 2: Ingest packets
         let packets = self.ingest_packets();
+        let packets = self.recover_lost_frames(packets);
 
         // Stage 3: Decrypt and validate
         let validated = self.decrypt_and_validate(packets);
+        let validated = self.reassemble_fragments(validated);
+        self.record_link_quality();
 
         // Stage 4: Classify events
         let classify_start = Instant::now();
@@ -768,6 +1188,8 @@ impl Node {
 
         // Stage 5: Update time model
         self.update_time_model(&events);
+        // Before reconciling, so a heal is seen before the history it brings
+        self.check_partitions();
 
         // Stage 6: Reconcile state
         let reconcile_start = Instant::now();
@@ -779,8 +1201,13 @@ impl Node {
             );
             let _enter = span.enter();
             
-            let result = self.state_engine.process_events(events, &self.time_engine);
-            self.state_engine.control_divergence();
+            // Early events first: they were due before anything just received
+            let mut result = self.state_engine.drain_early(&self.time_engine);
+            result.absorb(self.state_engine.process_events(events, &self.time_engine));
+            let changes = self
+                .state_engine
+                .control_divergence(|atom| profile_for_state(atom.id));
+            self.degrade_media(&changes);
             
             tracing::debug!(
                 applied = result.applied,
@@ -796,16 +1223,38 @@ impl Node {
             metrics.state_sync_latency_ms.observe(sync_latency_ms);
         }
 
+        self.stats.events_rate_limited += reconcile_result.rate_limited as u64;
+        self.stats.events_oversized += reconcile_result.oversized as u64;
+
+        // Ask peers for whatever quarantined events waited on in vain
+        self.request_missing_state();
+        self.send_state_digest();
+        self.send_time_sync();
+        self.snapshot_state();
+
         // Update state reconciliation metrics
         if let Some(ref metrics) = self.metrics {
-            // Track quarantine buffer size
+            // Track quarantine buffer size and age
             let quarantine_size = self.state_engine.field().quarantine_size();
             metrics.quarantine_buffer_size.set(quarantine_size as i64);
+            let quarantine_age = self
+                .state_engine
+                .quarantine_age(self.time_engine.tau_s())
+                .unwrap_or_default();
+            metrics
+                .quarantine_oldest_age_ms
+                .set(quarantine_age.as_millis() as i64);
             
             // Track rejected events as dropped messages
             if reconcile_result.rejected > 0 {
                 metrics.messages_dropped.inc_by(reconcile_result.rejected as u64);
             }
+            metrics
+                .events_rate_limited
+                .inc_by(reconcile_result.rate_limited as u64);
+            metrics
+                .events_oversized
+                .inc_by(reconcile_result.oversized as u64);
         }
 
         // Update time drift metric (track maximum offset across all peers)
@@ -870,28 +1319,68 @@ impl Node {
         );
         let _enter = span.enter();
 
-        let Some(processor) = self.secure_processor.as_mut() else {
+        if self.secure_processor.is_none() {
             tracing::debug!("No secure processor, skipping decryption");
-            return packets;
-        };
+            return packets
+                .into_iter()
+                .filter_map(|mut frame| {
+                    let payload = std::mem::take(&mut frame.payload);
+                    match decompress_frame_payload(
+                        &frame.header,
+                        &frame.extensions,
+                        payload,
+                        MAX_DECOMPRESSED_SIZE,
+                    ) {
+                        Ok(payload) => {
+                            frame.payload = payload;
+                            Some(frame)
+                        }
+                        Err(e) => {
+                            tracing::warn!(error = %e, "Dropping undecodable plain frame");
+                            None
+                        }
+                    }
+                })
+                .collect();
+        }
 
         let initial_count = packets.len();
-        let validated: Vec<Frame> = packets
-            .into_iter()
-            .filter_map(|frame| {
-                let data = frame.serialize().ok()?;
-                let decrypted = processor.decrypt_frame(&data).ok()?;
-                let auth_tag = [0u8; AUTH_TAG_SIZE];
-                Some(Frame {
+        let mut rekeyed = 0;
+        let mut validated = Vec::with_capacity(packets.len());
+        for frame in packets {
+            // Key exchange frames are sent before the group key exists
+            if let Some(event) = Self::key_exchange_event(&frame) {
+                // Rekeys apply in arrival order so frames behind them decrypt
+                if let Some(rekey) = Self::rekey_message(&event) {
+                    self.handle_rekey(frame.header.node_id, rekey);
+                    rekeyed += 1;
+                } else {
+                    validated.push(frame);
+                }
+                continue;
+            }
+
+            let Some(processor) = self.secure_processor.as_mut() else {
+                continue;
+            };
+            let Ok(data) = frame.serialize() else {
+                continue;
+            };
+            if let Ok(decrypted) = processor.decrypt_frame(&data) {
+                // Only authenticated headers may move the sequence window;
+                // a forged seq far ahead would otherwise mark real frames
+                // lost or reordered
+                self.track_sequence(&decrypted.header);
+                validated.push(Frame {
                     header: decrypted.header,
                     extensions: decrypted.extensions,
                     payload: decrypted.payload,
-                    auth_tag,
-                })
-            })
-            .collect();
+                    auth_tag: [0u8; AUTH_TAG_SIZE],
+                });
+            }
+        }
 
-        let failed_count = initial_count - validated.len();
+        let failed_count = initial_count - validated.len() - rekeyed;
         if failed_count > 0 {
             tracing::warn!(
                 node_id = self.node_id().0,
@@ -914,6 +1403,134 @@ impl Node {
         validated
     }
 
+    /// Note a session frame's sequence number for loss and reorder
+    fn track_sequence(&mut self, header: &FixedHeader) {
+        let in_session = self
+            .secure_processor
+            .as_ref()
+            .is_some_and(|processor| processor.session_id() == header.session_id);
+        if !in_session {
+            return;
+        }
+        let arrival = self
+            .sequences
+            .observe(header.node_id, header.class, header.seq());
+        if let Arrival::Reordered(_) = arrival {
+            self.stats.frames_reordered += 1;
+        }
+    }
+
+    /// Stage 2b: Rebuild lost frames from FEC parity
+    ///
+    /// Parity frames are consumed here; recovered frames join the batch and
+    /// are authenticated like any other.
+    fn recover_lost_frames(&mut self, packets: Vec<Frame>) -> Vec<Frame> {
+        let now = Instant::now();
+        self.fec_decoder.expire(now);
+
+        let mut frames = Vec::with_capacity(packets.len());
+        for frame in packets {
+            if frame.extensions.redundancy_group.is_none() {
+                frames.push(frame);
+                continue;
+            }
+            match self.fec_decoder.receive(&frame, now) {
+                Ok(recovered) => {
+                    if !recovered.is_empty() {
+                        tracing::debug!(
+                            source = frame.header.node_id.0,
+                            recovered = recovered.len(),
+                            "Recovered lost frames from parity"
+                        );
+                    }
+                    for lost in recovered {
+                        Self::insert_in_seq_order(&mut frames, lost);
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!(error = %e, "Rejected malformed FEC frame");
+                }
+            }
+            if !is_parity_frame(&frame) {
+                frames.push(frame);
+            }
+        }
+        frames
+    }
+
+    /// Insert a recovered frame ahead of later frames from the same sender
+    ///
+    /// Receivers advance their ratchet per frame, so a recovered frame must
+    /// be decrypted before the ones the sender encrypted after it.
+    fn insert_in_seq_order(frames: &mut Vec<Frame>, frame: Frame) {
+        let later = frames.iter().position(|f| {
+            f.header.node_id == frame.header.node_id
+                && f.header.class == frame.header.class
+                && (f.header.seq().wrapping_sub(frame.header.seq()) as i16) > 0
+        });
+        match later {
+            Some(index) => frames.insert(index, frame),
+            None => frames.push(frame),
+        }
+    }
+
+    /// Stage 3b: Rebuild fragmented frames
+    ///
+    /// Incomplete messages stay buffered until their class timeout expires.
+    fn reassemble_fragments(&mut self, packets: Vec<Frame>) -> Vec<Frame> {
+        let now = Instant::now();
+        let expired = self.reassembler.expire(now);
+        if expired > 0 {
+            tracing::debug!(expired = expired, "Dropped incomplete fragmented messages");
+        }
+
+        let mut frames = Vec::with_capacity(packets.len());
+        for frame in packets {
+            match self.reassembler.insert(frame, now) {
+                Ok(Some(frame)) => frames.push(frame),
+                Ok(None) => {}
+                Err(e) => {
+                    tracing::warn!(error = %e, "Rejected malformed fragment");
+                }
+            }
+        }
+        frames
+    }
+
+    /// Feed loss and reorder seen in peers' sequence numbers to the time
+    /// model, per peer and in aggregate
+    ///
+    /// Loss counts frames once they leave the sequence window, so late
+    /// frames are not mistaken for lost ones.
+    fn record_link_quality(&mut self) {
+        let (mut lost, mut settled) = (0, 0);
+        for peer in self.sequences.nodes() {
+            let (peer_lost, peer_settled) = self.sequences.take_settled_from(peer);
+            if peer_settled > 0 {
+                self.time_engine
+                    .record_peer_loss(peer, peer_lost as u32, peer_settled as u32);
+            }
+            let peer_depth = self.sequences.reorder_depth_from(peer);
+            self.time_engine
+                .record_peer_reorder(peer, peer_depth as u32);
+            lost += peer_lost;
+            settled += peer_settled;
+        }
+        if settled > 0 {
+            self.time_engine.record_loss(lost as u32, settled as u32);
+            self.stats.frames_lost += lost;
+        }
+        let depth = self.sequences.reorder_depth();
+        self.time_engine.record_reorder(depth as u32);
+
+        if let Some(ref metrics) = self.metrics {
+            metrics.packets_lost.inc_by(lost);
+            let loss_permille = (self.sequences.loss_rate() * 1000.0).round();
+            metrics.packet_loss_permille.set(loss_permille as i64);
+            metrics.reorder_depth.set(depth as i64);
+        }
+    }
+
     /// Stage 4: Extract events from validated packets
     fn classify_events(&mut self, packets: Vec<Frame>) -> Vec<Event> {
         let span = tracing::span!(
@@ -928,15 +1545,18 @@ impl Node {
 
         for frame in packets {
             let source = frame.header.node_id;
-            let time_hint = frame.header.time_hint;
             let packet_class = frame.header.class;
+            self.time_engine.heard_from(source);
             
             // Track message size
             if let Some(ref metrics) = self.metrics {
                 metrics.message_size_bytes.observe(frame.payload.len() as f64);
             }
 
-            let frame_events = self.decode_event_blocks(&frame.payload, source, time_hint);
+            let frame_events = match Self::key_exchange_event(&frame) {
+                Some(event) => vec![event],
+                None => Self::decode_event_blocks(&frame.payload, source),
+            };
             tracing::trace!(
                 source = source.0,
                 event_count = frame_events.len(),
@@ -944,45 +1564,699 @@ impl Node {
                 "Decoded events from frame"
             );
             
-            for event in &frame_events {
-                self.handle_event_side_effects(event);
+            for event in frame_events {
+                if packet_class == PacketClass::Core {
+                    if let Some(message) = Self::handshake_message(&event) {
+                        self.handle_handshake(source, message);
+                        continue;
+                    }
+                    if let Some(confirm) = Self::handshake_confirm(&event) {
+                        self.handle_handshake_confirm(source, confirm);
+                        continue;
+                    }
+                    if let Some(rekey) = Self::rekey_message(&event) {
+                        self.handle_rekey(source, rekey);
+                        continue;
+                    }
+                }
+                // Unverified events still reach reconciliation, which rejects
+                // them, but must not trigger side effects on the way
+                let verified = self.state_engine.verify_signature(&event).is_ok();
+                if event.event_type.packet_class() == PacketClass::Repair {
+                    if verified {
+                        self.handle_repair_event(&event);
+                    }
+                    continue;
+                }
+                if event.event_type == EventType::SessionLeave {
+                    if verified {
+                        self.remove_peer(event.source);
+                    }
+                    continue;
+                }
+                if matches!(
+                    event.event_type,
+                    EventType::TimeSync | EventType::TimeCorrection
+                ) {
+                    if verified {
+                        self.handle_time_sync_event(&event);
+                    }
+                    continue;
+                }
+                if verified {
+                    self.handle_event_side_effects(&event);
+                }
+                events.push(event);
             }
-            events.extend(frame_events);
         }
 
         tracing::debug!(event_count = events.len(), "Event classification complete");
         events
     }
 
-    fn handle_event_side_effects(&mut self, event: &Event) {
-        match event.event_type {
-            EventType::StreamStart => {
-                let stream_id = event.target_state.instance();
-                let started_at = event.absolute_time(self.time_engine.tau_s());
+    /// Extract a handshake message from a `SessionJoin` event
+    fn handshake_message(event: &Event) -> Option<HandshakeMessage> {
+        if event.event_type != EventType::SessionJoin {
+            return None;
+        }
+        match &event.mutation {
+            MutationOp::Set(data) => HandshakeMessage::decode(data).ok(),
+            _ => None,
+        }
+    }
 
-                if let MutationOp::Set(data) = &event.mutation {
-                    self.stream_metadata.insert(
-                        stream_id,
-                        StreamMetadata {
-                            source: event.source,
-                            started_at,
-                            data: data.clone(),
-                        },
-                    );
-                }
+    /// Extract a handshake key confirmation from a `SessionJoin` event
+    fn handshake_confirm(event: &Event) -> Option<HandshakeConfirm> {
+        if event.event_type != EventType::SessionJoin {
+            return None;
+        }
+        match &event.mutation {
+            MutationOp::Set(data) => HandshakeConfirm::decode(data).ok(),
+            _ => None,
+        }
+    }
 
-                let field = self.state_engine_mut().field_mut();
-                if field.get(event.target_state).is_none() {
-                    field.create_atom(
-                        event.target_state,
-                        elara_core::StateType::Core,
-                        event.source,
-                    );
-                }
+    /// Extract a group rekey from a `SessionSync` event
+    fn rekey_message(event: &Event) -> Option<GroupRekey> {
+        if event.event_type != EventType::SessionSync {
+            return None;
+        }
+        match &event.mutation {
+            MutationOp::Set(data) => GroupRekey::decode(data).ok(),
+            _ => None,
+        }
+    }
 
-                let visual_state = stream_visual_state_id(stream_id);
-                if field.get(visual_state).is_none() {
-                    field.create_atom(
+    /// Extract the plaintext handshake, confirmation or group rekey a frame
+    /// carries
+    ///
+    /// These are authenticated by their own signatures, so their event block
+    /// omits the signed trailer to keep an ML-KEM handshake in one frame.
+    fn key_exchange_event(frame: &Frame) -> Option<Event> {
+        if frame.header.class != PacketClass::Core {
+            return None;
+        }
+        let mut events = Self::decode_blocks(&frame.payload, frame.header.node_id, false);
+        let event = events.pop()?;
+        let is_key_exchange = Self::handshake_message(&event).is_some()
+            || Self::handshake_confirm(&event).is_some()
+            || Self::rekey_message(&event).is_some();
+        (events.is_empty() && is_key_exchange).then_some(event)
+    }
+
+    /// Queue a plaintext `Core` frame carrying a handshake, confirmation or
+    /// group rekey
+    fn queue_key_exchange_frame(
+        &mut self,
+        session_id: SessionId,
+        event_type: EventType,
+        data: Vec<u8>,
+    ) {
+        if self.outgoing.len() >= self.config.max_outgoing_buffer {
+            if let Some(ref metrics) = self.metrics {
+                metrics.messages_dropped.inc();
+            }
+            tracing::warn!("Outgoing buffer full, dropping key exchange frame");
+            return;
+        }
+
+        let seq = self.next_event_seq();
+        let event = Event::new(
+            self.node_id(),
+            seq,
+            event_type,
+            StateId::ZERO,
+            MutationOp::Set(data),
+        );
+        let payload = Self::encode_block(&event, false);
+
+        let mut header = FixedHeader::new(session_id, self.node_id());
+        header.class = PacketClass::Core;
+        header.profile = RepresentationProfile::Textual;
+
+        let frame = FrameBuilder::new(header).payload(payload).build();
+        self.outgoing.push_back(frame);
+
+        if let Some(ref metrics) = self.metrics {
+            metrics.messages_sent.inc();
+        }
+    }
+
+    /// Drive the handshake state machine with a received message
+    fn handle_handshake(&mut self, source: NodeId, message: HandshakeMessage) {
+        if self.session_id != Some(message.session_id) {
+            tracing::debug!(
+                source = source.0,
+                session_id = message.session_id.0,
+                "Ignoring handshake for another session"
+            );
+            return;
+        }
+
+        // The signing key must belong to the node that sent the frame
+        if message.node_id() != Some(source) {
+            tracing::warn!(
+                source = source.0,
+                "Handshake identity does not match frame source"
+            );
+            if let Some(ref metrics) = self.metrics {
+                metrics.failed_connections.inc();
+            }
+            return;
+        }
+
+        let result = match message.role {
+            HandshakeRole::Initiator => {
+                if !self.accept_handshakes {
+                    tracing::debug!(source = source.0, "Not accepting handshakes");
+                    return;
+                }
+                if self.unconfirmed_joins.len() >= MAX_UNCONFIRMED_JOINS
+                    && !self
+                        .unconfirmed_joins
+                        .contains_key(&message.ephemeral_public)
+                {
+                    tracing::warn!(source = source.0, "Too many unconfirmed handshakes");
+                    return;
+                }
+                // Later members must use the suite the group already runs on
+                let preference = match self.secure_processor.as_ref() {
+                    Some(processor) => vec![processor.suite()],
+                    None => self.config.crypto_suites.clone(),
+                };
+                // The initiator message may be a replay, so the root is only
+                // installed once the initiator confirms it
+                Handshake::respond_with_suites(&self.identity, &message, &preference).map(
+                    |(response, outcome)| {
+                        self.queue_key_exchange_frame(
+                            response.session_id,
+                            EventType::SessionJoin,
+                            response.encode(),
+                        );
+                        self.unconfirmed_joins
+                            .insert(message.ephemeral_public, outcome);
+                    },
+                )
+            }
+            HandshakeRole::Responder => {
+                let Some(pending) = self.pending_handshake.as_mut() else {
+                    tracing::debug!(source = source.0, "No pending handshake");
+                    return;
+                };
+                pending.complete(&message).map(|outcome| {
+                    self.pending_handshake = None;
+                    self.queue_key_exchange_frame(
+                        outcome.session_id,
+                        EventType::SessionJoin,
+                        outcome.confirmation().encode(),
+                    );
+                    self.install_session_root(outcome);
+                })
+            }
+        };
+
+        if let Err(e) = result {
+            tracing::warn!(source = source.0, error = %e, "Handshake failed");
+            if let Some(ref metrics) = self.metrics {
+                metrics.failed_connections.inc();
+            }
+        }
+    }
+
+    /// Admit a peer whose key confirmation matches an answered handshake
+    fn handle_handshake_confirm(&mut self, source: NodeId, confirm: HandshakeConfirm) {
+        if !self.accept_handshakes || self.session_id != Some(confirm.session_id) {
+            return;
+        }
+        let Some(outcome) = self.unconfirmed_joins.get(&confirm.initiator_ephemeral) else {
+            tracing::debug!(source = source.0, "No handshake awaiting this confirmation");
+            return;
+        };
+        let result = if outcome.peer.node_id() != source {
+            Err(ElaraError::HandshakeFailed(
+                "confirmation from another node".into(),
+            ))
+        } else {
+            outcome.verify_confirmation(&confirm)
+        };
+
+        match result {
+            Ok(()) => {
+                let outcome = self
+                    .unconfirmed_joins
+                    .remove(&confirm.initiator_ephemeral)
+                    .expect("checked above");
+                self.install_session_root(outcome);
+            }
+            Err(e) => {
+                tracing::warn!(source = source.0, error = %e, "Handshake failed");
+                if let Some(ref metrics) = self.metrics {
+                    metrics.failed_connections.inc();
+                }
+            }
+        }
+    }
+
+    /// Key the session with a root derived by a handshake
+    fn install_session_root(&mut self, outcome: HandshakeOutcome) {
+        tracing::info!(
+            node_id = self.node_id().0,
+            peer_id = outcome.peer.node_id().0,
+            session_id = outcome.session_id.0,
+            suite = ?outcome.suite,
+            "Session key established"
+        );
+
+        let peer_id = outcome.peer.node_id();
+        let root = outcome.session_root;
+        let processor = if self.accept_handshakes && self.secure_processor.is_some() {
+            None
+        } else {
+            match SecureFrameProcessor::with_suite(
+                outcome.session_id,
+                self.node_id(),
+                root,
+                outcome.suite,
+            ) {
+                Ok(mut processor) => {
+                    processor.set_compression(self.config.compression);
+                    Some(processor)
+                }
+                Err(e) => {
+                    tracing::warn!(peer_id = peer_id.0, error = %e, "Cannot key negotiated suite");
+                    if let Some(ref metrics) = self.metrics {
+                        metrics.failed_connections.inc();
+                    }
+                    return;
+                }
+            }
+        };
+        self.state_engine.keys_mut().insert(outcome.peer.clone());
+        self.peer_identities.insert(peer_id, outcome.peer);
+
+        if self.accept_handshakes {
+            // Session host: admit the peer and rotate the group root
+            let local_id = self.node_id();
+            self.group_keys
+                .get_or_insert_with(|| GroupKeySchedule::new(outcome.session_id, local_id, root))
+                .add_member(peer_id, root);
+            if processor.is_some() {
+                self.secure_processor = processor;
+            }
+            self.rekey_group();
+        } else {
+            // The pairwise root keys the session until the host's first rekey
+            let mut schedule = GroupKeySchedule::new(outcome.session_id, self.node_id(), root);
+            schedule.add_member(peer_id, root);
+            self.group_keys = Some(schedule);
+            self.secure_processor = processor;
+        }
+    }
+
+    /// Rotate the group root and send it to every remaining member
+    fn rekey_group(&mut self) {
+        let Some(schedule) = self.group_keys.as_mut() else {
+            return;
+        };
+        let rekeys = match schedule.rekey() {
+            Ok(rekeys) => rekeys,
+            Err(e) => {
+                tracing::warn!(error = %e, "Group rekey failed");
+                return;
+            }
+        };
+        let session_id = schedule.session_id();
+        let (epoch, root) = (schedule.epoch(), *schedule.root());
+
+        if let Some(processor) = self.secure_processor.as_mut() {
+            if let Err(e) = processor.rekey(epoch, root) {
+                tracing::warn!(epoch = epoch, error = %e, "Failed to install group root");
+            }
+        }
+        for rekey in rekeys {
+            self.queue_key_exchange_frame(session_id, EventType::SessionSync, rekey.encode());
+        }
+    }
+
+    /// Install a group root received from the session host
+    fn handle_rekey(&mut self, source: NodeId, rekey: GroupRekey) {
+        // Rekeys for every member travel together; skip the others' copies
+        if rekey.recipient != self.node_id() {
+            return;
+        }
+        let Some(schedule) = self.group_keys.as_mut() else {
+            tracing::debug!(source = source.0, "No group key schedule for rekey");
+            return;
+        };
+
+        let result =
+            schedule
+                .accept(source, &rekey)
+                .and_then(|root| match self.secure_processor.as_mut() {
+                    Some(processor) => processor.rekey(rekey.epoch, root),
+                    None => Err(ElaraError::SessionNotFound),
+                });
+        if let Err(e) = result {
+            tracing::warn!(source = source.0, epoch = rekey.epoch, error = %e, "Group rekey rejected");
+            if let Some(ref metrics) = self.metrics {
+                metrics.failed_connections.inc();
+            }
+        }
+    }
+
+    /// Send an encrypted `SessionLeave` before dropping the session key
+    fn queue_session_leave(&mut self) {
+        let seq = self.next_event_seq();
+        let mut event = Event::new(
+            self.node_id(),
+            seq,
+            EventType::SessionLeave,
+            StateId::ZERO,
+            MutationOp::Delete,
+        );
+        self.sign_event(&mut event);
+        let payload = Self::encode_event_block(&event);
+
+        let Some(processor) = self.secure_processor.as_mut() else {
+            return;
+        };
+        if let Ok(bytes) = processor.encrypt_frame(
+            PacketClass::Core,
+            RepresentationProfile::Textual,
+            0,
+            Extensions::new(),
+            &payload,
+        ) {
+            if let Ok(frame) = Frame::parse(&bytes) {
+                self.outgoing.push_back(frame);
+            }
+        }
+    }
+
+    /// Expire quarantined events and queue requests for the missing ranges
+    fn request_missing_state(&mut self) {
+        let now = self.time_engine.tau_s();
+        let before = self.state_engine.field().quarantine_size();
+        let requests = self.state_engine.expire_quarantine(now);
+        let expired = before - self.state_engine.field().quarantine_size();
+        self.stats.quarantine_expired += expired as u64;
+        if let Some(ref metrics) = self.metrics {
+            metrics.quarantine_expired.inc_by(expired as u64);
+        }
+
+        for request in requests {
+            self.send_state_request(request);
+        }
+    }
+
+    /// Track partitions and queue a report for each settled merge
+    fn check_partitions(&mut self) {
+        let reports = self.state_engine.check_partitions(&self.time_engine);
+        if let Some(ref metrics) = self.metrics {
+            let partitioned = self.state_engine.partitions().partitioned().len();
+            metrics.partitioned_peers.set(partitioned as i64);
+        }
+
+        for report in reports {
+            let conflicted = report.count(MergeOutcome::Conflicted) as u64;
+            self.stats.partitions_merged += 1;
+            self.stats.partition_conflicts += conflicted;
+            if let Some(ref metrics) = self.metrics {
+                metrics.partition_merges.inc();
+                metrics.partition_conflicts.inc_by(conflicted);
+            }
+            if self.partition_reports.len() >= MAX_PARTITION_REPORTS {
+                self.partition_reports.pop_front();
+            }
+            self.partition_reports.push_back(report);
+        }
+    }
+
+    /// Degrade the media atoms of groups whose level got worse
+    ///
+    /// Visual and voice atoms are decoded, reduced with their own degrade
+    /// paths and re-encoded in place. Atoms that do not decode, or already
+    /// sit at the new level or below, are left as they are.
+    fn degrade_media(&mut self, changes: &[DegradationChange]) {
+        let mut degraded = 0;
+        for change in changes.iter().filter(|change| change.is_worse()) {
+            tracing::debug!(
+                peer = change.peer.0,
+                profile = ?change.profile,
+                from = ?change.from,
+                to = ?change.to,
+                "Degrading peer state"
+            );
+            for atom in self.state_engine.field_mut().atoms.values_mut() {
+                if atom.authority.owners.iter().min() != Some(&change.peer)
+                    || profile_for_state(atom.id) != change.profile
+                {
+                    continue;
+                }
+                let value = match atom.id.state_type() {
+                    STATE_TYPE_VISUAL | STATE_TYPE_LIVESTREAM => {
+                        match VisualEncoder::decode(&atom.value) {
+                            Ok(state) if change.to.is_worse_than(state.degradation) => {
+                                VisualEncoder::encode(&state.degrade(change.to))
+                            }
+                            _ => continue,
+                        }
+                    }
+                    STATE_TYPE_VOICE => match VoiceEncoder::decode_state(&atom.value) {
+                        Ok(mut state) if change.to.is_worse_than(state.degradation) => {
+                            state.degrade(change.to);
+                            VoiceEncoder::encode_state(&state)
+                        }
+                        _ => continue,
+                    },
+                    _ => continue,
+                };
+                atom.value = value;
+                degraded += 1;
+            }
+        }
+
+        self.stats.atoms_degraded += degraded;
+        if let Some(ref metrics) = self.metrics {
+            metrics
+                .degradation_level
+                .set(self.degradation_level().level() as i64);
+        }
+    }
+
+    /// Snapshot the state field once per `snapshot_interval`
+    fn snapshot_state(&mut self) {
+        if self.state_engine.event_store().is_none()
+            || self.last_snapshot.elapsed() < self.config.snapshot_interval
+        {
+            return;
+        }
+        self.last_snapshot = Instant::now();
+        match self.state_engine.save_snapshot(self.time_engine.tau_s()) {
+            Ok(()) => self.stats.state_snapshots_saved += 1,
+            Err(err) => tracing::warn!(error = %err, "Failed to save state snapshot"),
+        }
+    }
+
+    /// Start a new repair round once per `sync_interval`
+    ///
+    /// Each round lets atoms be requested again, stops awaiting requests
+    /// that went unanswered and sends peers a digest of Core state.
+    fn send_state_digest(&mut self) {
+        let round = self.config.sync_interval.unwrap_or(STATE_REQUEST_RETRY);
+        if self.last_sync.elapsed() < round {
+            return;
+        }
+        self.last_sync = Instant::now();
+        self.requested
+            .retain(|_, asked| asked.elapsed() < STATE_RESPONSE_TIMEOUT);
+        if self.config.sync_interval.is_none() || !self.in_session() {
+            return;
+        }
+
+        let digest = self.state_engine.digest();
+        if digest.is_empty() {
+            return;
+        }
+        let events = StateDigest::split(digest, MAX_REPAIR_CHUNK)
+            .iter()
+            .map(|digest| {
+                let seq = self.next_event_seq();
+                digest.to_event(self.node_id(), seq)
+            })
+            .collect();
+        let sent = self.queue_repair_events(events);
+        self.stats.state_digests_sent += sent as u64;
+    }
+
+    /// Ask peers for an atom, at most once per sync round
+    fn send_state_request(&mut self, request: StateRequest) {
+        let round = self.last_sync;
+        if self
+            .requested
+            .get(&request.state)
+            .is_some_and(|&asked| asked >= round)
+        {
+            return;
+        }
+        tracing::debug!(
+            target_state = request.state.0,
+            ranges = request.missing.len(),
+            "Requesting missing state"
+        );
+        let seq = self.next_event_seq();
+        let event = request.to_event(self.node_id(), seq);
+        // Unsent requests stay unrecorded, to try again once bandwidth allows
+        if self.queue_repair_events(vec![event]) > 0 {
+            self.requested.insert(request.state, Instant::now());
+            self.stats.state_requests_sent += 1;
+        }
+    }
+
+    /// Queue repair events if the repair budget covers all of them
+    ///
+    /// Returns how many were queued: all or none, so a snapshot is never
+    /// sent with chunks missing.
+    fn queue_repair_events(&mut self, events: Vec<Event>) -> usize {
+        let bytes = events
+            .iter()
+            .map(|event| Self::encode_event_block(event).len())
+            .sum();
+        if !self.repair_budget.take(self.config.repair_bandwidth, bytes) {
+            tracing::debug!(
+                events = events.len(),
+                bytes = bytes,
+                "Repair bandwidth exhausted, not sending"
+            );
+            self.stats.repair_events_throttled += events.len() as u64;
+            return 0;
+        }
+        let count = events.len();
+        for event in events {
+            self.queue_local_event(event);
+        }
+        count
+    }
+
+    /// Handle a repair-class event; these never mutate state directly
+    fn handle_repair_event(&mut self, event: &Event) {
+        if let Some(digest) = StateDigest::from_event(event) {
+            for request in self.state_engine.requests_for(&digest) {
+                self.send_state_request(request);
+            }
+        } else if let Some(request) = StateRequest::from_event(event) {
+            tracing::debug!(
+                source = event.source.0,
+                target_state = request.state.0,
+                ranges = request.missing.len(),
+                "State request received"
+            );
+            self.stats.state_requests_received += 1;
+            let Some(history) = self.state_engine.answer(&request) else {
+                return;
+            };
+            let events = StateResponse::split(&history, MAX_REPAIR_CHUNK)
+                .iter()
+                .map(|chunk| {
+                    let seq = self.next_event_seq();
+                    chunk.to_event(self.node_id(), seq)
+                })
+                .collect();
+            let sent = self.queue_repair_events(events);
+            self.stats.state_responses_sent += sent as u64;
+        } else if let Some(response) = StateResponse::from_event(event) {
+            let awaited = self
+                .requested
+                .get(&response.state)
+                .is_some_and(|asked| asked.elapsed() < STATE_RESPONSE_TIMEOUT);
+            if !awaited {
+                tracing::debug!(
+                    source = event.source.0,
+                    target_state = response.state.0,
+                    "Dropping unrequested state response"
+                );
+                return;
+            }
+            let Some(history) = self.responses.insert(event.source, response) else {
+                return;
+            };
+            let state = history.state;
+            let result = self.state_engine.apply_repair(history, &self.time_engine);
+            if result.merged > 0 {
+                self.requested.remove(&state);
+                self.stats.repairs_applied += 1;
+            }
+        }
+    }
+
+    /// Probe peers' clocks once per adaptive time sync interval
+    fn send_time_sync(&mut self) {
+        if !self.in_session() || self.time_engine.network().peers.is_empty() {
+            return;
+        }
+        if self.last_time_sync.elapsed() < self.time_engine.time_sync_interval() {
+            return;
+        }
+        self.last_time_sync = Instant::now();
+
+        let probe = TimeSyncProbe {
+            origin: self.time_engine.probe_origin(),
+        };
+        let seq = self.next_event_seq();
+        self.queue_local_event(probe.to_event(self.node_id(), seq));
+        self.stats.time_probes_sent += 1;
+    }
+
+    /// Answer peers' clock probes and learn from answers to ours
+    fn handle_time_sync_event(&mut self, event: &Event) {
+        if let Some(probe) = TimeSyncProbe::from_event(event) {
+            let reply = self.time_engine.answer_probe(event.source, &probe);
+            let seq = self.next_event_seq();
+            self.queue_local_event(reply.to_event(self.node_id(), seq));
+        } else if let Some(reply) = TimeSyncReply::from_event(event) {
+            if reply.requester != self.node_id() {
+                return;
+            }
+            if let Some(rtt) = self.time_engine.update_from_time_sync(event.source, &reply) {
+                tracing::trace!(
+                    source = event.source.0,
+                    rtt_us = rtt.as_micros() as u64,
+                    "Clock probe answered"
+                );
+                self.stats.time_probes_answered += 1;
+            }
+        }
+    }
+
+    fn handle_event_side_effects(&mut self, event: &Event) {
+        match event.event_type {
+            EventType::StreamStart => {
+                let stream_id = event.target_state.instance();
+                let started_at = event.absolute_time(self.time_engine.tau_s());
+
+                if let MutationOp::Set(data) = &event.mutation {
+                    self.stream_metadata.insert(
+                        stream_id,
+                        StreamMetadata {
+                            source: event.source,
+                            started_at,
+                            data: data.clone(),
+                        },
+                    );
+                }
+
+                let field = self.state_engine_mut().field_mut();
+                if field.get(event.target_state).is_none() {
+                    field.create_atom(
+                        event.target_state,
+                        elara_core::StateType::Core,
+                        event.source,
+                    );
+                }
+
+                let visual_state = stream_visual_state_id(stream_id);
+                if field.get(visual_state).is_none() {
+                    field.create_atom(
                         visual_state,
                         elara_core::StateType::Perceptual,
                         event.source,
@@ -1085,9 +2359,7 @@ impl Node {
         let signed_events = events
             .into_iter()
             .map(|mut event| {
-                // Sign event
-                let signature = self.identity.sign(&event.mutation.encode());
-                event.authority_proof.signature = signature;
+                self.sign_event(&mut event);
                 event
             })
             .collect();
@@ -1096,23 +2368,32 @@ impl Node {
         signed_events
     }
 
+    /// Sign an event's source, target, version, time intent and mutation
+    fn sign_event(&self, event: &mut Event) {
+        let session = self.session_id.unwrap_or(SessionId::ZERO);
+        event.authority_proof.signature = self.identity.sign(&event.signing_bytes(session));
+    }
+
     /// Stage 11: Build packets from authorized events
-    fn build_packets(&mut self, _events: Vec<Event>) {
+    fn build_packets(&mut self, events: Vec<Event>) {
         let span = tracing::span!(
             tracing::Level::DEBUG,
             "build_packets",
             node_id = self.node_id().0,
-            event_count = _events.len()
+            event_count = events.len()
         );
         let _enter = span.enter();
 
+        let batches = self.batch_events(events);
         let Some(processor) = self.secure_processor.as_mut() else {
-            self.build_plain_packets(_events);
+            self.build_plain_packets(batches);
             return;
         };
+        self.fec_encoder.set_loss_rate(self.time_engine.network().loss_rate);
+        let now = Instant::now();
 
         let mut packets_built = 0;
-        for event in _events {
+        for batch in batches {
             if self.outgoing.len() >= self.config.max_outgoing_buffer {
                 // Buffer full - drop message
                 if let Some(ref metrics) = self.metrics {
@@ -1122,41 +2403,65 @@ impl Node {
                 break;
             }
 
-            let class = Self::class_for_event(&event);
-            let profile = Self::profile_for_event(&event);
-            let time_hint = event.time_intent.ts_offset();
-            let payload = Self::encode_event_block(&event);
-
-            if let Ok(bytes) =
-                processor.encrypt_frame(class, profile, time_hint, Extensions::new(), &payload)
-            {
-                if let Ok(frame) = Frame::parse(&bytes) {
-                    self.outgoing.push_back(frame);
-                    packets_built += 1;
-                    
-                    // Update metrics: increment messages_sent
-                    if let Some(ref metrics) = self.metrics {
-                        metrics.messages_sent.inc();
-                        metrics.message_size_bytes.observe(bytes.len() as f64);
+            let Some(chunks) = Self::split_payload(&self.fragmenter, &batch.payload) else {
+                continue;
+            };
+            if self.outgoing.len() + chunks.len() > self.config.max_outgoing_buffer {
+                if let Some(ref metrics) = self.metrics {
+                    metrics.messages_dropped.inc();
+                }
+                tracing::warn!("Outgoing buffer full, dropping fragmented message");
+                break;
+            }
+
+            self.stats.events_batched += batch.events as u64;
+            self.stats.batches_sent += 1;
+            for (fragment_info, chunk) in chunks {
+                let mut extensions = Extensions::new();
+                extensions.fragment_info = fragment_info;
+                if chunk.len() <= MAX_FEC_PAYLOAD {
+                    extensions.redundancy_group = self.fec_encoder.group_for(batch.class);
+                }
+                if let Ok(bytes) = processor.encrypt_frame(
+                    batch.class,
+                    batch.profile,
+                    batch.time_hint,
+                    extensions,
+                    chunk,
+                ) {
+                    if let Ok(frame) = Frame::parse(&bytes) {
+                        let parity = self.fec_encoder.push(&frame, now).unwrap_or_default();
+                        self.outgoing.push_back(frame);
+                        self.outgoing.extend(parity);
+                        packets_built += 1;
+
+                        // Update metrics: increment messages_sent
+                        if let Some(ref metrics) = self.metrics {
+                            metrics.messages_sent.inc();
+                            metrics.message_size_bytes.observe(bytes.len() as f64);
+                        }
                     }
                 }
             }
         }
 
+        // Groups a sparse stream never fills still get their parity
+        self.outgoing.extend(self.fec_encoder.flush_expired(now));
+
         tracing::debug!(packets_built = packets_built, "Packets built");
     }
 
-    fn build_plain_packets(&mut self, events: Vec<Event>) {
+    fn build_plain_packets(&mut self, batches: Vec<PendingBatch>) {
         let span = tracing::span!(
             tracing::Level::DEBUG,
             "build_plain_packets",
             node_id = self.node_id().0,
-            event_count = events.len()
+            batch_count = batches.len()
         );
         let _enter = span.enter();
 
         let mut packets_built = 0;
-        for event in events {
+        for batch in batches {
             if self.outgoing.len() >= self.config.max_outgoing_buffer {
                 // Buffer full - drop message
                 if let Some(ref metrics) = self.metrics {
@@ -1166,36 +2471,152 @@ impl Node {
                 break;
             }
 
-            let class = Self::class_for_event(&event);
-            let profile = Self::profile_for_event(&event);
-            let time_hint = event.time_intent.ts_offset();
-            let payload = Self::encode_event_block(&event);
+            let Some(chunks) = Self::split_payload(&self.fragmenter, &batch.payload) else {
+                continue;
+            };
+            if self.outgoing.len() + chunks.len() > self.config.max_outgoing_buffer {
+                if let Some(ref metrics) = self.metrics {
+                    metrics.messages_dropped.inc();
+                }
+                tracing::warn!("Outgoing buffer full, dropping fragmented message");
+                break;
+            }
+
+            self.stats.events_batched += batch.events as u64;
+            self.stats.batches_sent += 1;
+            let session_id = self.session_id.unwrap_or(SessionId::ZERO);
+            for (fragment_info, chunk) in chunks {
+                let mut header = FixedHeader::new(session_id, self.node_id());
+                header.class = batch.class;
+                header.profile = batch.profile;
+                header.time_hint = batch.time_hint;
+
+                let mut extensions = Extensions::new();
+                if let Some(info) = fragment_info {
+                    // Consecutive sequence numbers group the fragments
+                    header.flags.set_fragment(true);
+                    header.set_seq(self.plain_fragment_seq);
+                    self.plain_fragment_seq = self.plain_fragment_seq.wrapping_add(1);
+                    extensions.fragment_info = Some(info);
+                }
+
+                let compressed = compress_payload(self.config.compression, chunk);
+                if compressed.is_some() {
+                    header.flags.set_compressed(true);
+                    extensions.compression_hint = Some(self.config.compression.to_byte());
+                }
+
+                let frame = FrameBuilder::new(header)
+                    .extensions(extensions)
+                    .payload(compressed.unwrap_or_else(|| chunk.to_vec()))
+                    .build();
+                self.outgoing.push_back(frame);
+                packets_built += 1;
+
+                // Update metrics: increment messages_sent
+                if let Some(ref metrics) = self.metrics {
+                    metrics.messages_sent.inc();
+                    metrics.message_size_bytes.observe(chunk.len() as f64);
+                }
+            }
+        }
+
+        tracing::debug!(packets_built = packets_built, "Plain packets built");
+    }
 
-            let session_id = self.session_id.unwrap_or(SessionId::ZERO);
-            let mut header = FixedHeader::new(session_id, self.node_id());
-            header.class = class;
-            header.profile = profile;
-            header.time_hint = time_hint;
+    /// Pack events into per-class batches and take those ready to send
+    ///
+    /// Events of the same class and profile share a frame until it would
+    /// exceed one fragment's payload. A batch leaves once it is full or its
+    /// class's `max_batch_delay` has elapsed. A single oversized block gets
+    /// a batch of its own and is fragmented when sent.
+    fn batch_events(&mut self, events: Vec<Event>) -> Vec<PendingBatch> {
+        let now = Instant::now();
+        let max_payload = self.fragmenter.max_payload();
+
+        for event in events {
+            let class = event.event_type.packet_class();
+            let profile = Self::profile_for_event(&event);
+            let block = Self::encode_event_block(&event);
+
+            let open = self
+                .pending_batches
+                .iter_mut()
+                .find(|b| !b.full && b.class == class && b.profile == profile);
+            match open {
+                Some(batch) if batch.payload.len() + block.len() <= max_payload => {
+                    batch.payload.extend_from_slice(&block);
+                    batch.events += 1;
+                }
+                open => {
+                    if let Some(batch) = open {
+                        batch.full = true;
+                    }
+                    let delay = self
+                        .config
+                        .max_batch_delay
+                        .get(&class)
+                        .copied()
+                        .unwrap_or_default();
+                    self.pending_batches.push(PendingBatch {
+                        class,
+                        profile,
+                        time_hint: event.time_intent.ts_offset(),
+                        payload: block,
+                        events: 1,
+                        deadline: now + delay,
+                        full: false,
+                    });
+                }
+            }
+        }
 
-            let frame = FrameBuilder::new(header).payload(payload.clone()).build();
-            self.outgoing.push_back(frame);
-            packets_built += 1;
+        let (ready, waiting) = std::mem::take(&mut self.pending_batches)
+            .into_iter()
+            .partition(|b| b.full || b.deadline <= now);
+        self.pending_batches = waiting;
+        ready
+    }
 
-            // Update metrics: increment messages_sent
-            if let Some(ref metrics) = self.metrics {
-                metrics.messages_sent.inc();
-                metrics.message_size_bytes.observe(payload.len() as f64);
+    /// Split an event block into frame payloads
+    ///
+    /// Blocks that fit one frame come back whole, without fragment info.
+    fn split_payload<'a>(
+        fragmenter: &Fragmenter,
+        payload: &'a [u8],
+    ) -> Option<Vec<(Option<FragmentInfo>, &'a [u8])>> {
+        if !fragmenter.needs_split(payload.len()) {
+            return Some(vec![(None, payload)]);
+        }
+        match fragmenter.split(payload) {
+            Ok(chunks) => Some(
+                chunks
+                    .into_iter()
+                    .map(|(info, chunk)| (Some(info), chunk))
+                    .collect(),
+            ),
+            Err(e) => {
+                tracing::warn!(
+                    payload_size = payload.len(),
+                    error = %e,
+                    "Dropping event too large to fragment"
+                );
+                None
             }
         }
+    }
 
-        tracing::debug!(packets_built = packets_built, "Plain packets built");
+    fn decode_event_blocks(payload: &[u8], source: NodeId) -> Vec<Event> {
+        Self::decode_blocks(payload, source, true)
     }
 
-    fn decode_event_blocks(&self, payload: &[u8], source: NodeId, time_hint: i32) -> Vec<Event> {
+    /// Decode event blocks, with or without the time/signature trailer
+    fn decode_blocks(payload: &[u8], source: NodeId, signed: bool) -> Vec<Event> {
+        let trailer_size = if signed { EVENT_TRAILER_SIZE } else { 0 };
         let mut events = Vec::new();
         let mut offset = 0;
 
-        while payload.len().saturating_sub(offset) >= 13 {
+        while payload.len().saturating_sub(offset) >= EVENT_BLOCK_HEADER_SIZE + trailer_size {
             let event_type = match EventType::from_byte(payload[offset]) {
                 Some(t) => t,
                 None => break,
@@ -1212,6 +2633,16 @@ impl Node {
             };
             offset = state_end;
 
+            let seq_end = offset + 8;
+            if seq_end > payload.len() {
+                break;
+            }
+            let seq = match payload[offset..seq_end].try_into() {
+                Ok(bytes) => u64::from_le_bytes(bytes),
+                Err(_) => break,
+            };
+            offset = seq_end;
+
             let version_len_end = offset + 2;
             if version_len_end > payload.len() {
                 break;
@@ -1256,10 +2687,45 @@ impl Node {
             }
             offset = delta_end;
 
-            let seq = version_ref.get(source).saturating_add(1);
-            let event = Event::new(source, seq, event_type, state_id, mutation)
-                .with_version(version_ref)
-                .with_time_intent(TimeIntent::new(time_hint));
+            let mut event = Event::new(source, seq, event_type, state_id, mutation)
+                .with_version(version_ref);
+
+            if signed {
+                let trailer_end = offset + EVENT_TRAILER_SIZE;
+                if trailer_end > payload.len() {
+                    break;
+                }
+                let time_offset = i32::from_le_bytes([
+                    payload[offset],
+                    payload[offset + 1],
+                    payload[offset + 2],
+                    payload[offset + 3],
+                ]);
+                let mut signature = [0u8; 64];
+                signature.copy_from_slice(&payload[offset + 4..offset + 68]);
+                let chain_len = payload[offset + 68] as usize;
+                offset = trailer_end;
+
+                let mut proof = AuthorityProof::new(signature);
+                if chain_len > 0 {
+                    let mut chain = Vec::with_capacity(chain_len);
+                    for _ in 0..chain_len {
+                        let Some((link, used)) = DelegationLink::decode(&payload[offset..]) else {
+                            break;
+                        };
+                        chain.push(link);
+                        offset += used;
+                    }
+                    if chain.len() != chain_len {
+                        break;
+                    }
+                    proof = proof.with_delegation(chain);
+                }
+
+                event = event
+                    .with_time_intent(TimeIntent::new(time_offset))
+                    .with_authority_proof(proof);
+            }
             events.push(event);
         }
 
@@ -1267,9 +2733,15 @@ impl Node {
     }
 
     fn encode_event_block(event: &Event) -> Vec<u8> {
+        Self::encode_block(event, true)
+    }
+
+    /// Encode an event block, with or without the time/signature trailer
+    fn encode_block(event: &Event, signed: bool) -> Vec<u8> {
         let mut buf = Vec::new();
         buf.push(event.event_type.to_byte());
         buf.extend_from_slice(&event.target_state.to_bytes());
+        buf.extend_from_slice(&event.id.seq.to_le_bytes());
 
         let version = Self::encode_version_vector(&event.version_ref);
         buf.extend_from_slice(&(version.len() as u16).to_le_bytes());
@@ -1279,6 +2751,18 @@ impl Node {
         buf.extend_from_slice(&(delta.len() as u16).to_le_bytes());
         buf.extend_from_slice(&delta);
 
+        if signed {
+            buf.extend_from_slice(&event.time_intent.ts_offset().to_le_bytes());
+            buf.extend_from_slice(&event.authority_proof.signature);
+
+            let chain = event.authority_proof.delegation_chain.as_deref().unwrap_or(&[]);
+            let chain = &chain[..chain.len().min(MAX_DELEGATION_DEPTH)];
+            buf.push(chain.len() as u8);
+            for link in chain {
+                buf.extend_from_slice(&link.encode());
+            }
+        }
+
         buf
     }
 
@@ -1294,7 +2778,7 @@ impl Node {
     }
 
     fn decode_version_vector(buf: &[u8]) -> Option<VersionVector> {
-        if !buf.len().is_multiple_of(16) {
+        if buf.len() % 16 != 0 {
             return None;
         }
         let mut entries = Vec::new();
@@ -1312,26 +2796,6 @@ impl Node {
         Some(VersionVector::from_compact(entries))
     }
 
-    fn class_for_event(event: &Event) -> PacketClass {
-        match event.event_type {
-            EventType::StateRequest | EventType::StateResponse | EventType::GapFill => {
-                PacketClass::Repair
-            }
-            EventType::VoiceFrame | EventType::VoiceMute => PacketClass::Perceptual,
-            EventType::TypingStart | EventType::TypingStop | EventType::PresenceUpdate => {
-                PacketClass::Perceptual
-            }
-            EventType::VisualKeyframe | EventType::VisualDelta => PacketClass::Perceptual,
-            EventType::TextAppend
-            | EventType::TextEdit
-            | EventType::TextDelete
-            | EventType::TextReact => PacketClass::Core,
-            EventType::FeedAppend | EventType::FeedDelete => PacketClass::Core,
-            EventType::StreamStart | EventType::StreamEnd => PacketClass::Core,
-            _ => PacketClass::Core,
-        }
-    }
-
     fn profile_for_event(event: &Event) -> RepresentationProfile {
         match event.event_type {
             EventType::VoiceFrame | EventType::VoiceMute => RepresentationProfile::VoiceMinimal,
@@ -1507,6 +2971,43 @@ impl Node {
         &self.stats
     }
 
+    /// Keep an event log in `store` and rebuild state from what it holds
+    ///
+    /// Archive nodes (`NodeClassSet::archive()`) attach a `FileEventStore`
+    /// so their Core state survives restarts and keeps answering peers'
+    /// state requests. Returns the number of log records replayed.
+    pub fn attach_event_store(&mut self, store: Box<dyn EventStore>) -> io::Result<usize> {
+        self.state_engine.set_event_store(store);
+        let replayed = self.state_engine.restore()?;
+        self.last_snapshot = Instant::now();
+        Ok(replayed)
+    }
+
+    /// Atom `state_id` as it stood at `time`, replayed from the event log
+    ///
+    /// Unlike `visual_state_at`, this covers any atom and any time the
+    /// attached event store remembers, for moderation and debugging.
+    pub fn state_at(&self, state_id: StateId, time: StateTime) -> io::Result<AtomProjection> {
+        self.state_engine.atom_at(state_id, time)
+    }
+
+    /// Logged changes to atom `state_id` between `from` and `to`
+    pub fn state_changes_between(
+        &self,
+        state_id: StateId,
+        from: StateTime,
+        to: StateTime,
+    ) -> io::Result<AtomChanges> {
+        self.state_engine.changes_between(state_id, from, to)
+    }
+
+    /// Current misbehaviour score of `peer` from bounds violations
+    pub fn peer_misbehaviour(&self, peer: NodeId) -> f64 {
+        self.state_engine
+            .misbehaviour()
+            .score(peer, self.time_engine.tau_s())
+    }
+
     /// Check if node is in a session
     pub fn in_session(&self) -> bool {
         self.session_id.is_some()
@@ -1531,6 +3032,9 @@ mod tests {
     use super::*;
     use elara_core::{PacketClass, RepresentationProfile};
     use elara_msp::text::{feed_stream_id as feed_id, FeedItem as MspFeedItem};
+    use elara_state::AtomEvents;
+    use elara_time::MIN_TIME_SYNC_INTERVAL;
+    use elara_wire::FEC_FLUSH_DELAY;
 
     #[test]
     fn test_node_creation() {
@@ -1577,6 +3081,214 @@ mod tests {
         assert_eq!(node.stats().local_events_queued, 1);
     }
 
+    #[test]
+    fn test_msp_policies_shape_remote_text() {
+        let mut config = NodeConfig::default();
+        elara_msp::register_policies(&mut config.reconciliation.merge_policies);
+        let mut alice = Node::new();
+        let mut bob = Node::with_config(config);
+        let session_id = SessionId::new(95);
+        bob.accept_session(session_id);
+        alice.initiate_session(session_id);
+        handshake(&mut alice, &mut bob);
+
+        let chat = elara_msp::text::text_stream_id(1);
+        let event = text_event(&mut alice, chat, b"hi");
+        alice.queue_local_event(event);
+        alice.tick();
+        deliver(&mut alice, &mut bob);
+        bob.tick();
+
+        let atom = bob.state_engine().field().get(chat).unwrap();
+        assert!(matches!(
+            atom.delta_law,
+            elara_core::DeltaLaw::AppendOnly { max_size: 1000 }
+        ));
+        assert!(atom.bounds.rate_limit.is_some());
+    }
+
+    #[test]
+    fn test_appends_from_one_source_each_apply_once() {
+        let mut config = NodeConfig::default();
+        elara_msp::register_policies(&mut config.reconciliation.merge_policies);
+        let mut alice = Node::new();
+        let mut bob = Node::with_config(config);
+        let session_id = SessionId::new(96);
+        bob.accept_session(session_id);
+        alice.initiate_session(session_id);
+        handshake(&mut alice, &mut bob);
+
+        let chat = elara_msp::text::text_stream_id(2);
+        let mut frames = Vec::new();
+        for text in [b"one;", b"two;"] {
+            let event = text_event(&mut alice, chat, text);
+            alice.queue_local_event(event);
+            alice.tick();
+            while let Some(frame) = alice.pop_outgoing() {
+                frames.push(frame.clone());
+                bob.queue_incoming(frame);
+            }
+            bob.tick();
+        }
+        let value = |node: &Node| node.state_engine().field().get(chat).unwrap().value.clone();
+        assert_eq!(value(&bob), b"one;two;");
+
+        // Redelivered frames leave the log as it was
+        for frame in frames {
+            bob.queue_incoming(frame);
+        }
+        bob.tick();
+        assert_eq!(value(&bob), b"one;two;");
+    }
+
+    #[test]
+    fn test_time_sync_probe_measures_rtt() {
+        let mut alice = Node::new();
+        let mut bob = Node::new();
+        let session_id = SessionId::new(96);
+        bob.accept_session(session_id);
+        alice.initiate_session(session_id);
+        handshake(&mut alice, &mut bob);
+
+        std::thread::sleep(MIN_TIME_SYNC_INTERVAL);
+        alice.tick();
+        assert_eq!(alice.stats().time_probes_sent, 1);
+        deliver(&mut alice, &mut bob);
+        bob.tick();
+        deliver(&mut bob, &mut alice);
+        alice.tick();
+
+        assert_eq!(alice.stats().time_probes_answered, 1);
+        let peer = alice
+            .time_engine()
+            .network()
+            .get_peer(bob.node_id())
+            .unwrap();
+        assert!(peer.is_probed());
+        assert!(peer.rtt > 0.0);
+        assert!(alice.time_engine().network().latency_mean > 0.0);
+        // The next probe waits for the interval
+        assert_eq!(alice.stats().time_probes_sent, 1);
+    }
+
+    #[test]
+    fn test_sequence_gaps_drive_loss_and_reorder() {
+        let mut alice = Node::new();
+        let mut bob = Node::new();
+        let session_id = SessionId::new(97);
+        bob.accept_session(session_id);
+        alice.initiate_session(session_id);
+        handshake(&mut alice, &mut bob);
+
+        let mut frames = Vec::new();
+        for i in 0..80 {
+            let event = text_event(&mut alice, StateId::new(100 + i), b"seq");
+            alice.queue_local_event(event);
+            alice.tick();
+            while let Some(frame) = alice.pop_outgoing() {
+                frames.push(frame);
+            }
+        }
+        assert_eq!(frames.len(), 80);
+
+        // Lose two frames and swap two others. The receive ratchet moves in
+        // lockstep, so frames past a gap no longer decrypt; feed their
+        // headers to the tracker as decryption would
+        frames.swap(20, 21);
+        for (i, frame) in frames.iter().enumerate() {
+            if i != 10 && i != 11 {
+                bob.track_sequence(&frame.header);
+            }
+        }
+        bob.record_link_quality();
+
+        assert_eq!(bob.stats().frames_lost, 2);
+        assert_eq!(bob.stats().frames_reordered, 1);
+        let network = bob.time_engine().network();
+        assert!(network.loss_rate > 0.0);
+        assert_eq!(network.reorder_depth, 1);
+        let peer = network.get_peer(alice.node_id()).unwrap();
+        assert_eq!(peer.loss_rate, network.loss_rate);
+        assert_eq!(peer.reorder_depth, 1);
+    }
+
+    #[test]
+    fn test_forged_sequence_leaves_link_stats() {
+        let mut alice = Node::new();
+        let mut bob = Node::new();
+        let session_id = SessionId::new(98);
+        bob.accept_session(session_id);
+        alice.initiate_session(session_id);
+        handshake(&mut alice, &mut bob);
+
+        let mut frames = Vec::new();
+        for i in 0..80 {
+            let event = text_event(&mut alice, StateId::new(200 + i), b"seq");
+            alice.queue_local_event(event);
+            alice.tick();
+            while let Some(frame) = alice.pop_outgoing() {
+                frames.push(frame);
+            }
+        }
+
+        // Midway, a copy of a real frame claims a sequence far ahead. It
+        // fails to authenticate, so it moves neither the link stats nor
+        // the replay window
+        let mut forged = frames[0].clone();
+        let ahead = forged.header.seq().wrapping_add(1000);
+        forged.header.set_seq(ahead);
+        let later = frames.split_off(40);
+        for frame in frames {
+            bob.queue_incoming(frame);
+        }
+        bob.queue_incoming(forged);
+        for frame in later {
+            bob.queue_incoming(frame);
+        }
+        bob.tick();
+
+        assert_eq!(bob.stats().frames_lost, 0);
+        assert_eq!(bob.stats().frames_reordered, 0);
+        assert_eq!(bob.time_engine().network().reorder_depth, 0);
+        for i in 0..80 {
+            assert!(bob.state_engine().field().contains(StateId::new(200 + i)));
+        }
+    }
+
+    #[test]
+    fn test_only_requested_responses_applied() {
+        let mut alice = Node::new();
+        let mut bob = Node::new();
+        bob.add_peer_identity(alice.public_identity());
+        let state_id = StateId::new(120);
+        let mut event = text_event(&mut alice, state_id, b"repair");
+        alice.sign_event(&mut event);
+        let history = AtomEvents {
+            state: state_id,
+            events: vec![event],
+        };
+        // Carol relays alice's signed event
+        let carol = NodeId::new(0xCA401);
+        let respond = |bob: &mut Node| {
+            for chunk in StateResponse::split(&history, MAX_REPAIR_CHUNK) {
+                bob.handle_repair_event(&chunk.to_event(carol, 1));
+            }
+        };
+
+        // Unsolicited responses are dropped before reassembly
+        respond(&mut bob);
+        assert_eq!(bob.stats().repairs_applied, 0);
+        assert!(bob.state_engine().field().get(state_id).is_none());
+
+        bob.requested.insert(state_id, Instant::now());
+        respond(&mut bob);
+        assert_eq!(bob.stats().repairs_applied, 1);
+        assert!(bob.requested.is_empty());
+        let atom = bob.state_engine().field().get(state_id).unwrap();
+        assert_eq!(atom.value, b"repair".to_vec());
+        assert!(atom.authority.owners.contains(&alice.node_id()));
+    }
+
     #[test]
     fn test_prediction_entropy_advances() {
         let mut node = Node::new();
@@ -1610,19 +3322,695 @@ mod tests {
         assert!(!node.in_session());
     }
 
-    fn build_payload(event_type: EventType, state_id: StateId, mutation: MutationOp) -> Vec<u8> {
-        let mut buf = Vec::new();
-        buf.push(event_type.to_byte());
-        buf.extend_from_slice(&state_id.to_bytes());
+    fn deliver(from: &mut Node, to: &mut Node) {
+        while let Some(frame) = from.pop_outgoing() {
+            to.queue_incoming(frame);
+        }
+        to.tick();
+    }
 
-        // Empty version vector
-        buf.extend_from_slice(&(0u16).to_le_bytes());
+    /// Run a join, response, confirmation and the host's rekey
+    fn handshake(initiator: &mut Node, host: &mut Node) {
+        deliver(initiator, host);
+        deliver(host, initiator);
+        deliver(initiator, host);
+        deliver(host, initiator);
+    }
 
-        let delta = mutation.encode();
-        buf.extend_from_slice(&(delta.len() as u16).to_le_bytes());
-        buf.extend_from_slice(&delta);
+    #[test]
+    fn test_handshake_establishes_session_key() {
+        let mut alice = Node::new();
+        let mut bob = Node::new();
+        let session_id = SessionId::new(77);
+
+        bob.accept_session(session_id);
+        alice.initiate_session(session_id);
+        assert!(!alice.is_secured());
+
+        // Join, then response; the responder waits for the confirmation
+        deliver(&mut alice, &mut bob);
+        assert!(!bob.is_secured());
+        deliver(&mut bob, &mut alice);
+        assert!(alice.is_secured());
+        deliver(&mut alice, &mut bob);
+        assert!(bob.is_secured());
+
+        assert!(alice.peer_identity(bob.node_id()).is_some());
+        assert!(bob.peer_identity(alice.node_id()).is_some());
+
+        // Encrypted traffic now flows under the derived key
+        let state_id = StateId::new(5);
+        let seq = alice.next_event_seq();
+        alice.queue_local_event(Event::new(
+            alice.node_id(),
+            seq,
+            EventType::TextAppend,
+            state_id,
+            MutationOp::Append(b"hi".to_vec()),
+        ));
+        alice.tick();
+        deliver(&mut alice, &mut bob);
 
-        buf
+        assert_eq!(bob.state_engine().field().get(state_id).unwrap().value, b"hi");
+    }
+
+    #[test]
+    fn test_replayed_join_keeps_member_root() {
+        let mut host = Node::new();
+        let mut alice = Node::new();
+        let session_id = SessionId::new(83);
+
+        host.accept_session(session_id);
+        alice.initiate_session(session_id);
+        let join = alice.outgoing.front().unwrap().clone();
+        handshake(&mut alice, &mut host);
+        assert_eq!(alice.key_epoch(), Some(1));
+
+        // A captured join is answered but never confirmed
+        host.queue_incoming(join);
+        host.tick();
+        assert!(host.pop_outgoing().is_some());
+        assert_eq!(host.unconfirmed_joins.len(), 1);
+        assert_eq!(host.key_epoch(), Some(1));
+
+        // Alice still receives the next group root under her real one
+        host.rekey_group();
+        deliver(&mut host, &mut alice);
+        assert_eq!(alice.key_epoch(), Some(2));
+
+        let state_id = StateId::new(10);
+        let event = text_event(&mut host, state_id, b"still in");
+        host.queue_local_event(event);
+        host.tick();
+        deliver(&mut host, &mut alice);
+        assert_eq!(
+            alice.state_engine().field().get(state_id).unwrap().value,
+            b"still in"
+        );
+    }
+
+    fn text_event(node: &mut Node, state_id: StateId, text: &[u8]) -> Event {
+        let seq = node.next_event_seq();
+        Event::new(
+            node.node_id(),
+            seq,
+            EventType::TextAppend,
+            state_id,
+            MutationOp::Append(text.to_vec()),
+        )
+    }
+
+    #[test]
+    fn test_group_rekey_on_join_and_removal() {
+        let mut host = Node::new();
+        let mut alice = Node::new();
+        let mut bob = Node::new();
+        let session_id = SessionId::new(79);
+
+        host.accept_session(session_id);
+        alice.initiate_session(session_id);
+        handshake(&mut alice, &mut host);
+        assert_eq!(alice.key_epoch(), Some(1));
+
+        // A second join rotates the root for everyone once confirmed
+        bob.initiate_session(session_id);
+        deliver(&mut bob, &mut host);
+        deliver(&mut host, &mut bob);
+        assert_eq!(host.key_epoch(), Some(1));
+        deliver(&mut bob, &mut host);
+        let frames: Vec<Frame> = std::iter::from_fn(|| host.pop_outgoing()).collect();
+        for frame in frames {
+            alice.queue_incoming(frame.clone());
+            bob.queue_incoming(frame);
+        }
+        alice.tick();
+        bob.tick();
+        assert_eq!(host.key_epoch(), Some(2));
+        assert_eq!(alice.key_epoch(), Some(2));
+        assert_eq!(bob.key_epoch(), Some(2));
+
+        // Removing Bob rotates the root without him
+        host.remove_peer(bob.node_id());
+        assert_eq!(host.key_epoch(), Some(3));
+        let state_id = StateId::new(6);
+        let event = text_event(&mut host, state_id, b"members only");
+        host.queue_local_event(event);
+        host.tick();
+        let frames: Vec<Frame> = std::iter::from_fn(|| host.pop_outgoing()).collect();
+        for frame in frames {
+            alice.queue_incoming(frame.clone());
+            bob.queue_incoming(frame);
+        }
+        alice.tick();
+        bob.tick();
+
+        assert_eq!(alice.key_epoch(), Some(3));
+        assert_eq!(bob.key_epoch(), Some(2));
+        assert_eq!(
+            alice.state_engine().field().get(state_id).unwrap().value,
+            b"members only"
+        );
+        assert!(bob.state_engine().field().get(state_id).is_none());
+    }
+
+    #[test]
+    fn test_session_leave_triggers_rekey() {
+        let mut host = Node::new();
+        let mut alice = Node::new();
+        let session_id = SessionId::new(80);
+
+        host.accept_session(session_id);
+        alice.initiate_session(session_id);
+        handshake(&mut alice, &mut host);
+        assert_eq!(host.key_epoch(), Some(1));
+
+        alice.leave_session();
+        deliver(&mut alice, &mut host);
+        assert_eq!(host.key_epoch(), Some(2));
+        assert!(host.peer_identity(alice.node_id()).is_none());
+    }
+
+    #[test]
+    fn test_handshake_negotiates_aes_gcm() {
+        let gcm_first = NodeConfig {
+            crypto_suites: vec![CryptoSuite::Suite1, CryptoSuite::Suite0],
+            ..Default::default()
+        };
+        let mut relay = Node::with_config(gcm_first.clone());
+        let mut alice = Node::with_config(gcm_first);
+        let mut bob = Node::new();
+        let session_id = SessionId::new(81);
+
+        relay.accept_session(session_id);
+        alice.initiate_session(session_id);
+        handshake(&mut alice, &mut relay);
+        assert!(alice.is_secured());
+
+        // A Suite0-only peer cannot join a group already running on Suite1
+        bob.initiate_session(session_id);
+        deliver(&mut bob, &mut relay);
+        assert!(relay.peer_identity(bob.node_id()).is_none());
+
+        let state_id = StateId::new(7);
+        let event = text_event(&mut alice, state_id, b"gcm");
+        alice.queue_local_event(event);
+        alice.tick();
+        let frame = alice.pop_outgoing().unwrap();
+        assert_eq!(frame.header.crypto_suite, CryptoSuite::Suite1);
+        relay.queue_incoming(frame);
+        relay.tick();
+        assert_eq!(relay.state_engine().field().get(state_id).unwrap().value, b"gcm");
+    }
+
+    #[test]
+    fn test_handshake_negotiates_hybrid_suite() {
+        let hybrid = NodeConfig {
+            crypto_suites: vec![CryptoSuite::Suite2, CryptoSuite::Suite0],
+            ..Default::default()
+        };
+        let mut relay = Node::with_config(hybrid.clone());
+        let mut alice = Node::with_config(hybrid.clone());
+        let session_id = SessionId::new(82);
+
+        relay.accept_session(session_id);
+        alice.initiate_session(session_id);
+
+        // The ML-KEM key still fits a single frame
+        let init = alice.pop_outgoing().unwrap();
+        assert!(init.serialize().is_ok());
+        relay.queue_incoming(init);
+        relay.tick();
+        let response = relay.pop_outgoing().unwrap();
+        assert!(response.serialize().is_ok());
+        alice.queue_incoming(response);
+        alice.tick();
+        assert!(alice.is_secured());
+        deliver(&mut alice, &mut relay);
+
+        let state_id = StateId::new(8);
+        let event = text_event(&mut alice, state_id, b"pq");
+        alice.queue_local_event(event);
+        alice.tick();
+        let frame = alice.pop_outgoing().unwrap();
+        assert_eq!(frame.header.crypto_suite, CryptoSuite::Suite2);
+        relay.queue_incoming(frame);
+        relay.tick();
+        assert_eq!(relay.state_engine().field().get(state_id).unwrap().value, b"pq");
+
+        // A Suite0-only responder falls back to the classical handshake
+        let mut classic = Node::new();
+        let mut carol = Node::with_config(hybrid);
+        classic.accept_session(session_id);
+        carol.initiate_session(session_id);
+        handshake(&mut carol, &mut classic);
+        assert!(carol.is_secured());
+        let event = text_event(&mut carol, state_id, b"classic");
+        carol.queue_local_event(event);
+        carol.tick();
+        let frame = carol.pop_outgoing().unwrap();
+        assert_eq!(frame.header.crypto_suite, CryptoSuite::Suite0);
+    }
+
+    #[test]
+    fn test_large_event_fragmented() {
+        let mut alice = Node::new();
+        let mut bob = Node::new();
+        let session_id = SessionId::new(83);
+
+        bob.accept_session(session_id);
+        alice.initiate_session(session_id);
+        handshake(&mut alice, &mut bob);
+
+        let state_id = StateId::new(9);
+        let text = vec![b'x'; 4000];
+        let event = text_event(&mut alice, state_id, &text);
+        alice.queue_local_event(event);
+        alice.tick();
+
+        let mut frames = Vec::new();
+        while let Some(frame) = alice.pop_outgoing() {
+            assert!(frame.serialize().is_ok());
+            assert!(frame.header.flags.is_fragment());
+            frames.push(frame);
+        }
+        assert_eq!(frames.len(), 4);
+
+        // Nothing is applied until the last fragment arrives
+        let last = frames.pop().unwrap();
+        for frame in frames {
+            bob.queue_incoming(frame);
+        }
+        bob.tick();
+        assert!(bob.state_engine().field().get(state_id).is_none());
+        bob.queue_incoming(last);
+        bob.tick();
+        assert_eq!(bob.state_engine().field().get(state_id).unwrap().value, text);
+    }
+
+    #[test]
+    fn test_large_event_fragmented_unsecured() {
+        let mut alice = Node::new();
+        let mut bob = Node::new();
+        let session_id = SessionId::new(84);
+        alice.join_session_unsecured(session_id);
+        bob.join_session_unsecured(session_id);
+        bob.add_peer_identity(alice.public_identity());
+
+        let state_id = StateId::new(10);
+        let text = vec![b'y'; 3000];
+        let event = text_event(&mut alice, state_id, &text);
+        alice.queue_local_event(event);
+        alice.tick();
+        deliver(&mut alice, &mut bob);
+        assert_eq!(bob.state_engine().field().get(state_id).unwrap().value, text);
+    }
+
+    #[test]
+    fn test_text_payload_compressed() {
+        let mut alice = Node::new();
+        let mut bob = Node::new();
+        let session_id = SessionId::new(86);
+
+        bob.accept_session(session_id);
+        alice.initiate_session(session_id);
+        handshake(&mut alice, &mut bob);
+
+        let state_id = StateId::new(15);
+        let text = b"status: online; ".repeat(40);
+        let event = text_event(&mut alice, state_id, &text);
+        alice.queue_local_event(event);
+        alice.tick();
+
+        let frame = alice.pop_outgoing().unwrap();
+        assert!(frame.header.flags.is_compressed());
+        assert!(frame.payload.len() < text.len());
+        bob.queue_incoming(frame);
+        bob.tick();
+        assert_eq!(bob.state_engine().field().get(state_id).unwrap().value, text);
+    }
+
+    #[test]
+    fn test_events_batched_into_one_frame() {
+        let mut alice = Node::new();
+        let mut bob = Node::new();
+        let session_id = SessionId::new(87);
+
+        bob.accept_session(session_id);
+        alice.initiate_session(session_id);
+        handshake(&mut alice, &mut bob);
+
+        for i in 0..5 {
+            let event = text_event(&mut alice, StateId::new(21 + i), b"batched");
+            alice.queue_local_event(event);
+        }
+        alice.tick();
+
+        let frame = alice.pop_outgoing().unwrap();
+        assert!(alice.pop_outgoing().is_none());
+        assert_eq!(alice.stats().events_per_packet(), 5.0);
+
+        bob.queue_incoming(frame);
+        bob.tick();
+        for i in 0..5 {
+            let atom = bob.state_engine().field().get(StateId::new(21 + i));
+            assert_eq!(atom.unwrap().value, b"batched");
+        }
+    }
+
+    #[test]
+    fn test_archive_node_restores_state_after_restart() {
+        let dir = std::env::temp_dir().join(format!("elara-archive-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let session_id = SessionId::new(92);
+        let state_id = StateId::new(31);
+
+        let mut alice = Node::new();
+        let mut archive = Node::with_config(NodeConfig {
+            snapshot_interval: Duration::ZERO,
+            ..Default::default()
+        });
+        let store = elara_state::FileEventStore::open(&dir).unwrap();
+        assert_eq!(archive.attach_event_store(Box::new(store)).unwrap(), 0);
+        archive.accept_session(session_id);
+        alice.initiate_session(session_id);
+        handshake(&mut alice, &mut archive);
+
+        let event = text_event(&mut alice, state_id, b"kept");
+        alice.queue_local_event(event);
+        alice.tick();
+        deliver(&mut alice, &mut archive);
+        assert!(archive.stats().state_snapshots_saved > 0);
+        drop(archive);
+
+        let mut restarted = Node::new();
+        let store = elara_state::FileEventStore::open(&dir).unwrap();
+        restarted.attach_event_store(Box::new(store)).unwrap();
+        let atom = restarted.state_engine().field().get(state_id).unwrap();
+        assert_eq!(atom.value, b"kept");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_state_at_replays_event_log() {
+        let session_id = SessionId::new(93);
+        let state_id = StateId::new(32);
+
+        let mut alice = Node::new();
+        let mut archive = Node::new();
+        let store = elara_state::MemoryEventStore::new();
+        archive.attach_event_store(Box::new(store)).unwrap();
+        archive.accept_session(session_id);
+        alice.initiate_session(session_id);
+        handshake(&mut alice, &mut archive);
+
+        let first = text_event(&mut alice, state_id, b"one");
+        alice.queue_local_event(first);
+        alice.tick();
+        deliver(&mut alice, &mut archive);
+        let t1 = archive.time_engine().tau_s();
+        archive.tick();
+
+        let mut version = VersionVector::new();
+        version.increment(alice.node_id());
+        let second = text_event(&mut alice, state_id, b"two").with_version(version);
+        alice.queue_local_event(second);
+        alice.tick();
+        deliver(&mut alice, &mut archive);
+        let t2 = archive.time_engine().tau_s();
+
+        let then = archive.state_at(state_id, t1).unwrap();
+        assert_eq!(then.atom.unwrap().value, b"one");
+        assert_eq!(then.frontier.get(alice.node_id()), 1);
+
+        let changes = archive.state_changes_between(state_id, t1, t2).unwrap();
+        assert_eq!(changes.records.len(), 1);
+        assert_eq!(changes.after.atom.unwrap().value, b"onetwo");
+        assert_eq!(changes.after.frontier.get(alice.node_id()), 2);
+    }
+
+    #[test]
+    fn test_partition_heal_reports_merge() {
+        let mut alice = Node::new();
+        let mut bob = Node::new();
+        let session_id = SessionId::new(94);
+        let state_id = StateId::new(33);
+        alice
+            .state_engine_mut()
+            .set_partition_detector(elara_state::PartitionDetector::new(
+                Duration::from_millis(150),
+                Duration::from_millis(60),
+            ));
+
+        bob.accept_session(session_id);
+        alice.initiate_session(session_id);
+        handshake(&mut alice, &mut bob);
+
+        let first = text_event(&mut bob, state_id, b"before");
+        bob.queue_local_event(first);
+        bob.tick();
+        deliver(&mut bob, &mut alice);
+
+        // Bob keeps writing, but nothing reaches alice until the heal
+        let mut version = VersionVector::new();
+        version.increment(bob.node_id());
+        let second = text_event(&mut bob, state_id, b" after").with_version(version);
+        bob.queue_local_event(second);
+        bob.tick();
+        for _ in 0..20 {
+            alice.tick();
+        }
+        assert_eq!(alice.partitioned_peers(), vec![bob.node_id()]);
+        assert!(alice.pop_partition_report().is_none());
+
+        deliver(&mut bob, &mut alice);
+        for _ in 0..10 {
+            alice.tick();
+        }
+        assert!(alice.partitioned_peers().is_empty());
+        let report = alice.pop_partition_report().unwrap();
+        assert_eq!(report.peers, vec![bob.node_id()]);
+        assert_eq!(report.atoms, vec![(state_id, MergeOutcome::FastForward)]);
+        assert_eq!(alice.stats().partitions_merged, 1);
+        let atom = alice.state_engine().field().get(state_id).unwrap();
+        assert_eq!(atom.value, b"before after");
+    }
+
+    #[test]
+    fn test_divergence_degrades_stale_visual_state() {
+        let mut node = Node::new();
+        let peer = NodeId::new(7);
+        let state_id = visual_state_id(peer);
+        let visual = VisualState::keyframe(peer, StateTime::from_millis(0), 1);
+        {
+            let field = node.state_engine_mut().field_mut();
+            let atom = field.create_atom(state_id, elara_core::StateType::Perceptual, peer);
+            atom.value = VisualEncoder::encode(&visual);
+            atom.entropy.level = 0.7;
+        }
+
+        node.tick();
+
+        let level = DegradationLevel::L3_SymbolicPresence;
+        assert_eq!(
+            node.peer_degradation(peer, RepresentationProfile::VideoStandard),
+            level
+        );
+        assert_eq!(node.degradation_level(), level);
+        assert_eq!(node.stats().atoms_degraded, 1);
+        let atom = node.state_engine().field().get(state_id).unwrap();
+        assert_eq!(
+            VisualEncoder::decode(&atom.value).unwrap().degradation,
+            level
+        );
+    }
+
+    #[test]
+    fn test_text_flood_rate_limited() {
+        let mut alice = Node::new();
+        let mut bob = Node::new();
+        let session_id = SessionId::new(91);
+
+        bob.accept_session(session_id);
+        alice.initiate_session(session_id);
+        handshake(&mut alice, &mut bob);
+
+        // 10 msg/sec per source
+        let atom = elara_msp::text::create_text_atom(3, alice.node_id());
+        let state_id = atom.id;
+        bob.state_engine_mut().field_mut().insert(atom);
+
+        let mut version = VersionVector::new();
+        for _ in 0..11 {
+            let event = text_event(&mut alice, state_id, b"spam").with_version(version.clone());
+            version.increment(alice.node_id());
+            alice.queue_local_event(event);
+        }
+        alice.tick();
+        deliver(&mut alice, &mut bob);
+
+        assert_eq!(bob.stats().events_rate_limited, 1);
+        assert!(bob.peer_misbehaviour(alice.node_id()) > 0.0);
+        let value = &bob.state_engine().field().get(state_id).unwrap().value;
+        assert_eq!(value.len(), 10 * b"spam".len());
+    }
+
+    #[test]
+    fn test_batches_split_at_frame_size() {
+        let mut alice = Node::new();
+        alice.join_session_unsecured(SessionId::new(88));
+
+        // Three 500-byte events cannot share one frame
+        for i in 0..3 {
+            let event = text_event(&mut alice, StateId::new(31 + i), &[i as u8; 500]);
+            alice.queue_local_event(event);
+        }
+        alice.tick();
+
+        let mut frames = 0;
+        while alice.pop_outgoing().is_some() {
+            frames += 1;
+        }
+        assert_eq!(frames, 2);
+        assert_eq!(alice.stats().events_per_packet(), 1.5);
+    }
+
+    #[test]
+    fn test_batch_delay_holds_events() {
+        let mut config = NodeConfig::default();
+        config
+            .max_batch_delay
+            .insert(PacketClass::Core, Duration::from_millis(20));
+        let mut alice = Node::with_config(config);
+        alice.join_session_unsecured(SessionId::new(89));
+
+        let event = text_event(&mut alice, StateId::new(41), b"first");
+        alice.queue_local_event(event);
+        alice.tick();
+        assert!(alice.pop_outgoing().is_none());
+
+        let event = text_event(&mut alice, StateId::new(42), b"second");
+        alice.queue_local_event(event);
+        std::thread::sleep(Duration::from_millis(25));
+        alice.tick();
+
+        assert!(alice.pop_outgoing().is_some());
+        assert!(alice.pop_outgoing().is_none());
+        assert_eq!(alice.stats().events_per_packet(), 2.0);
+    }
+
+    #[test]
+    fn test_fec_recovers_lost_frames() {
+        let mut alice = Node::new();
+        let mut bob = Node::new();
+        let session_id = SessionId::new(85);
+
+        bob.accept_session(session_id);
+        alice.initiate_session(session_id);
+        handshake(&mut alice, &mut bob);
+
+        // ~15% loss selects Reed-Solomon with two parity frames per four
+        for _ in 0..40 {
+            alice.time_engine.record_loss(15, 100);
+        }
+        // One event per tick so each travels in its own frame
+        let mut frames = Vec::new();
+        for i in 0..4 {
+            let event = text_event(&mut alice, StateId::new(11 + i), b"fec");
+            alice.queue_local_event(event);
+            alice.tick();
+            while let Some(frame) = alice.pop_outgoing() {
+                frames.push(frame);
+            }
+        }
+        let (parity, data): (Vec<_>, Vec<_>) = frames.into_iter().partition(is_parity_frame);
+        assert_eq!((data.len(), parity.len()), (4, 2));
+
+        // Lose the middle two data frames
+        for (i, frame) in data.into_iter().enumerate() {
+            if i == 0 || i == 3 {
+                bob.queue_incoming(frame);
+            }
+        }
+        for frame in parity {
+            bob.queue_incoming(frame);
+        }
+        bob.tick();
+        for i in 0..4 {
+            let atom = bob.state_engine().field().get(StateId::new(11 + i));
+            assert_eq!(atom.unwrap().value, b"fec");
+        }
+    }
+
+    #[test]
+    fn test_fec_flushes_sparse_group() {
+        let mut alice = Node::new();
+        let mut bob = Node::new();
+        let session_id = SessionId::new(86);
+
+        bob.accept_session(session_id);
+        alice.initiate_session(session_id);
+        handshake(&mut alice, &mut bob);
+        for _ in 0..40 {
+            alice.time_engine.record_loss(15, 100);
+        }
+
+        // Two frames never fill a group of four
+        let mut frames = Vec::new();
+        for i in 0..2 {
+            let event = text_event(&mut alice, StateId::new(31 + i), b"sparse");
+            alice.queue_local_event(event);
+            alice.tick();
+            frames.extend(std::iter::from_fn(|| alice.pop_outgoing()));
+        }
+        assert!(!frames.iter().any(is_parity_frame));
+
+        // The next tick after the flush delay sends parity for them. Hold
+        // off clock probes, which would otherwise be due by then
+        alice.last_time_sync = Instant::now() + Duration::from_secs(60);
+        std::thread::sleep(FEC_FLUSH_DELAY);
+        alice.tick();
+        frames.extend(std::iter::from_fn(|| alice.pop_outgoing()));
+        let (parity, data): (Vec<_>, Vec<_>) = frames.into_iter().partition(is_parity_frame);
+        assert_eq!(data.len(), 2);
+        assert!(!parity.is_empty());
+
+        // Lose the first data frame
+        bob.queue_incoming(data[1].clone());
+        for frame in parity {
+            bob.queue_incoming(frame);
+        }
+        bob.tick();
+        for i in 0..2 {
+            let atom = bob.state_engine().field().get(StateId::new(31 + i));
+            assert_eq!(atom.unwrap().value, b"sparse");
+        }
+    }
+
+    #[test]
+    fn test_handshake_ignored_when_not_accepting() {
+        let mut alice = Node::new();
+        let mut bob = Node::new();
+        let session_id = SessionId::new(78);
+
+        bob.join_session_unsecured(session_id);
+        alice.initiate_session(session_id);
+
+        deliver(&mut alice, &mut bob);
+        assert!(!bob.is_secured());
+        assert!(bob.pop_outgoing().is_none());
+        assert!(bob.state_engine().field().get(StateId::ZERO).is_none());
+    }
+
+    fn build_payload(
+        identity: &Identity,
+        event_type: EventType,
+        state_id: StateId,
+        mutation: MutationOp,
+    ) -> Vec<u8> {
+        let mut event = Event::new(identity.node_id(), 1, event_type, state_id, mutation);
+        event.authority_proof.signature = identity.sign(&event.signing_bytes(SessionId::ZERO));
+        Node::encode_event_block(&event)
     }
 
     fn incoming_frame_for(
@@ -1643,10 +4031,13 @@ mod tests {
     #[test]
     fn test_stream_start_side_effects_on_incoming_frame() {
         let mut node = Node::new();
+        let broadcaster = Identity::generate();
+        node.add_peer_identity(broadcaster.public_identity());
         let stream_id = 42u64;
         let target_state = livestream_state_id(stream_id);
 
         let payload = build_payload(
+            &broadcaster,
             EventType::StreamStart,
             target_state,
             MutationOp::Set(vec![1, 2, 3]),
@@ -1654,7 +4045,7 @@ mod tests {
 
         let frame = incoming_frame_for(
             SessionId::new(1),
-            NodeId::new(9000),
+            broadcaster.node_id(),
             PacketClass::Core,
             RepresentationProfile::StreamAsymmetric,
             0,
@@ -1670,20 +4061,120 @@ mod tests {
         assert!(node.stream_metadata(stream_id).is_some());
     }
 
+    #[test]
+    fn test_forged_event_rejected_without_side_effects() {
+        let mut node = Node::new();
+        let broadcaster = Identity::generate();
+        let mallory = Identity::generate();
+        node.add_peer_identity(broadcaster.public_identity());
+        let stream_id = 43u64;
+
+        // Mallory signs with her own key but claims the broadcaster's NodeId
+        let mut event = Event::new(
+            broadcaster.node_id(),
+            1,
+            EventType::StreamStart,
+            livestream_state_id(stream_id),
+            MutationOp::Set(vec![1, 2, 3]),
+        );
+        event.authority_proof.signature = mallory.sign(&event.signing_bytes(SessionId::ZERO));
+        let frame = incoming_frame_for(
+            SessionId::new(1),
+            broadcaster.node_id(),
+            PacketClass::Core,
+            RepresentationProfile::StreamAsymmetric,
+            0,
+            Node::encode_event_block(&event),
+        );
+        node.queue_incoming(frame);
+
+        // An unknown signer is rejected as well
+        let payload = build_payload(
+            &mallory,
+            EventType::StateCreate,
+            StateId::new(44),
+            MutationOp::Set(vec![4]),
+        );
+        let frame = incoming_frame_for(
+            SessionId::new(1),
+            mallory.node_id(),
+            PacketClass::Core,
+            RepresentationProfile::Textual,
+            0,
+            payload,
+        );
+        node.queue_incoming(frame);
+        node.tick();
+
+        let field = node.state_engine().field();
+        assert!(field.is_empty());
+        assert!(node.stream_metadata(stream_id).is_none());
+    }
+
+    #[test]
+    fn test_delegated_moderator_and_revocation() {
+        let session_id = SessionId::new(90);
+        let mut broadcaster = Node::new();
+        let mut moderator = Node::new();
+        let mut viewer = Node::new();
+        for node in [&mut broadcaster, &mut moderator, &mut viewer] {
+            node.join_session_unsecured(session_id);
+        }
+        viewer.add_peer_identity(broadcaster.public_identity());
+        viewer.add_peer_identity(moderator.public_identity());
+
+        let (chat, title) = (StateId::new(51), StateId::new(52));
+        for (state, text) in [(chat, b"a"), (title, b"t")] {
+            let event = text_event(&mut broadcaster, state, text);
+            broadcaster.queue_local_event(event);
+        }
+        broadcaster.tick();
+        deliver(&mut broadcaster, &mut viewer);
+
+        // The moderator proves the broadcaster's grant to the viewer
+        let link = broadcaster
+            .grant_delegation(moderator.node_id(), chat, AuthorityScope::Append, None)
+            .unwrap();
+        let proof = AuthorityProof::new([0u8; 64]).with_delegation(vec![link]);
+        let moderate = |moderator: &mut Node, viewer: &mut Node, state: StateId| {
+            let version = viewer.state_engine().field().get(state).unwrap().version.clone();
+            let event = text_event(moderator, state, b"m")
+                .with_version(version)
+                .with_authority_proof(proof.clone());
+            moderator.queue_local_event(event);
+            moderator.tick();
+            deliver(moderator, viewer);
+        };
+        moderate(&mut moderator, &mut viewer, chat);
+        assert_eq!(viewer.state_engine().field().get(chat).unwrap().value, b"am");
+        // The grant covers the chat only, not the broadcaster's other atoms
+        moderate(&mut moderator, &mut viewer, title);
+        assert_eq!(viewer.state_engine().field().get(title).unwrap().value, b"t");
+
+        broadcaster.queue_authority_revoke(chat, moderator.node_id());
+        broadcaster.tick();
+        deliver(&mut broadcaster, &mut viewer);
+        moderate(&mut moderator, &mut viewer, chat);
+        assert_eq!(viewer.state_engine().field().get(chat).unwrap().value, b"am");
+    }
+
     #[test]
     fn test_stream_end_removes_atoms() {
         let mut node = Node::new();
+        let broadcaster = Identity::generate();
+        node.add_peer_identity(broadcaster.public_identity());
         let stream_id = 99u64;
 
         // Start
         let start_payload = build_payload(
+            &broadcaster,
             EventType::StreamStart,
             livestream_state_id(stream_id),
             MutationOp::Set(vec![9, 9, 9]),
         );
         let start_frame = incoming_frame_for(
             SessionId::new(1),
-            NodeId::new(7000),
+            broadcaster.node_id(),
             PacketClass::Core,
             RepresentationProfile::StreamAsymmetric,
             0,
@@ -1694,13 +4185,14 @@ mod tests {
 
         // End
         let end_payload = build_payload(
+            &broadcaster,
             EventType::StreamEnd,
             livestream_state_id(stream_id),
             MutationOp::Delete,
         );
         let end_frame = incoming_frame_for(
             SessionId::new(1),
-            NodeId::new(7000),
+            broadcaster.node_id(),
             PacketClass::Core,
             RepresentationProfile::StreamAsymmetric,
             0,
@@ -1718,6 +4210,8 @@ mod tests {
     #[test]
     fn test_feed_append_roundtrip() {
         let mut node = Node::new();
+        let author = Identity::generate();
+        node.add_peer_identity(author.public_identity());
         let feed_state = feed_id(7);
 
         // Build a feed item and encode via MSP
@@ -1730,13 +4224,14 @@ mod tests {
         let encoded_item = item.encode();
 
         let payload = build_payload(
+            &author,
             EventType::FeedAppend,
             feed_state,
             MutationOp::Append(encoded_item),
         );
         let frame = incoming_frame_for(
             SessionId::new(1),
-            NodeId::new(5000),
+            author.node_id(),
             PacketClass::Core,
             RepresentationProfile::Textual,
             0,
@@ -122,7 +122,7 @@ pub enum LogLevel {
 
 impl LogLevel {
     /// Convert to filter directive string for EnvFilter
-    fn to_filter_directive(&self) -> &'static str {
+    fn to_filter_directive(self) -> &'static str {
         match self {
             LogLevel::Trace => "trace",
             LogLevel::Debug => "debug",
@@ -223,7 +223,7 @@ pub fn init_logging(config: LoggingConfig) -> Result<(), LoggingError> {
     let env_filter = EnvFilter::try_from_default_env()
         .or_else(|_| {
             // If RUST_LOG is not set, use the configured level as default
-            EnvFilter::try_new(format!("{}", config.level.to_filter_directive()))
+            EnvFilter::try_new(config.level.to_filter_directive())
         })
         .map_err(|e| LoggingError::SetGlobalDefaultFailed(format!("Failed to create EnvFilter: {}", e)))?;
 
@@ -87,14 +87,11 @@ impl MetricsRegistry {
         let name = name.into();
         let mut counters = self.counters.write();
 
-        if counters.contains_key(&name) {
-            // Return existing counter
-            counters.get(&name).unwrap().clone()
-        } else {
-            let counter = Counter::new(name.clone(), labels);
-            counters.insert(name, counter.clone());
-            counter
-        }
+        // Return existing counter if already registered
+        counters
+            .entry(name.clone())
+            .or_insert_with(|| Counter::new(name, labels))
+            .clone()
     }
 
     /// Registers a new gauge with the given name and labels.
@@ -110,14 +107,11 @@ impl MetricsRegistry {
         let name = name.into();
         let mut gauges = self.gauges.write();
 
-        if gauges.contains_key(&name) {
-            // Return existing gauge
-            gauges.get(&name).unwrap().clone()
-        } else {
-            let gauge = Gauge::new(name.clone(), labels);
-            gauges.insert(name, gauge.clone());
-            gauge
-        }
+        // Return existing gauge if already registered
+        gauges
+            .entry(name.clone())
+            .or_insert_with(|| Gauge::new(name, labels))
+            .clone()
     }
 
     /// Registers a new histogram with the given name, buckets, and labels.
@@ -141,14 +135,11 @@ impl MetricsRegistry {
         let name = name.into();
         let mut histograms = self.histograms.write();
 
-        if histograms.contains_key(&name) {
-            // Return existing histogram
-            histograms.get(&name).unwrap().clone()
-        } else {
-            let histogram = Histogram::new(name.clone(), buckets, labels);
-            histograms.insert(name, histogram.clone());
-            histogram
-        }
+        // Return existing histogram if already registered
+        histograms
+            .entry(name.clone())
+            .or_insert_with(|| Histogram::new(name, buckets, labels))
+            .clone()
     }
 
     /// Gets a counter by name.
@@ -554,13 +545,13 @@ mod tests {
         assert!(p95 <= p99);
 
         // p50 should be around 50
-        assert!(p50 >= 40.0 && p50 <= 60.0);
+        assert!((40.0..=60.0).contains(&p50));
 
         // p95 should be around 95
-        assert!(p95 >= 90.0 && p95 <= 100.0);
+        assert!((90.0..=100.0).contains(&p95));
 
         // p99 should be around 99
-        assert!(p99 >= 90.0 && p99 <= 100.0);
+        assert!((90.0..=100.0).contains(&p99));
     }
 
     #[test]
@@ -807,6 +798,11 @@ mod tests {
         assert!(counter_names.contains(&"elara_messages_sent".to_string()));
         assert!(counter_names.contains(&"elara_messages_received".to_string()));
         assert!(counter_names.contains(&"elara_messages_dropped".to_string()));
+        assert!(counter_names.contains(&"elara_events_rate_limited_total".to_string()));
+        assert!(counter_names.contains(&"elara_events_oversized_total".to_string()));
+        assert!(counter_names.contains(&"elara_partition_merges_total".to_string()));
+        assert!(counter_names.contains(&"elara_partition_conflicts_total".to_string()));
+        assert!(counter_names.contains(&"elara_packets_lost_total".to_string()));
 
         let gauge_names = registry.gauge_names();
         assert!(gauge_names.contains(&"elara_active_connections".to_string()));
@@ -814,6 +810,11 @@ mod tests {
         assert!(gauge_names.contains(&"elara_cpu_usage_percent".to_string()));
         assert!(gauge_names.contains(&"elara_time_drift_ms".to_string()));
         assert!(gauge_names.contains(&"elara_state_divergence_count".to_string()));
+        assert!(gauge_names.contains(&"elara_quarantine_oldest_age_ms".to_string()));
+        assert!(gauge_names.contains(&"elara_partitioned_peers".to_string()));
+        assert!(gauge_names.contains(&"elara_degradation_level".to_string()));
+        assert!(gauge_names.contains(&"elara_packet_loss_permille".to_string()));
+        assert!(gauge_names.contains(&"elara_reorder_depth".to_string()));
 
         let histogram_names = registry.histogram_names();
         assert!(histogram_names.contains(&"elara_message_size_bytes".to_string()));
@@ -925,6 +926,39 @@ pub struct NodeMetrics {
     /// Number of events in the quarantine buffer.
     /// Events are quarantined when they have missing dependencies.
     pub quarantine_buffer_size: Gauge,
+
+    /// Age in milliseconds of the longest-waiting quarantined event.
+    pub quarantine_oldest_age_ms: Gauge,
+
+    /// Quarantined events evicted after their TTL without their dependencies.
+    pub quarantine_expired: Counter,
+
+    /// Events rejected for exceeding their target atom's rate limit.
+    pub events_rate_limited: Counter,
+
+    /// Events rejected for growing their target atom past its size bound.
+    pub events_oversized: Counter,
+
+    /// Peers currently partitioned away from this node.
+    pub partitioned_peers: Gauge,
+
+    /// Healed partitions whose histories were merged.
+    pub partition_merges: Counter,
+
+    /// Atoms a partition merge left conflicted under their delta law.
+    pub partition_conflicts: Counter,
+
+    /// Degradation level of the session, from 0 (L0) to 5 (L5).
+    pub degradation_level: Gauge,
+
+    /// Peer frames that never arrived, detected from sequence gaps.
+    pub packets_lost: Counter,
+
+    /// Share of recent peer frames missing, in thousandths.
+    pub packet_loss_permille: Gauge,
+
+    /// Deepest reorder among recent peer frames, in frames.
+    pub reorder_depth: Gauge,
 }
 
 impl NodeMetrics {
@@ -1033,6 +1067,21 @@ impl NodeMetrics {
             registry.register_gauge("elara_state_divergence_count", vec![]);
         let quarantine_buffer_size =
             registry.register_gauge("elara_quarantine_buffer_size", vec![]);
+        let quarantine_oldest_age_ms =
+            registry.register_gauge("elara_quarantine_oldest_age_ms", vec![]);
+        let quarantine_expired =
+            registry.register_counter("elara_quarantine_expired_total", vec![]);
+        let events_rate_limited =
+            registry.register_counter("elara_events_rate_limited_total", vec![]);
+        let events_oversized = registry.register_counter("elara_events_oversized_total", vec![]);
+        let partitioned_peers = registry.register_gauge("elara_partitioned_peers", vec![]);
+        let partition_merges = registry.register_counter("elara_partition_merges_total", vec![]);
+        let partition_conflicts =
+            registry.register_counter("elara_partition_conflicts_total", vec![]);
+        let degradation_level = registry.register_gauge("elara_degradation_level", vec![]);
+        let packets_lost = registry.register_counter("elara_packets_lost_total", vec![]);
+        let packet_loss_permille = registry.register_gauge("elara_packet_loss_permille", vec![]);
+        let reorder_depth = registry.register_gauge("elara_reorder_depth", vec![]);
 
         Self {
             // Connection metrics
@@ -1058,6 +1107,17 @@ impl NodeMetrics {
             time_drift_ms,
             state_divergence_count,
             quarantine_buffer_size,
+            quarantine_oldest_age_ms,
+            quarantine_expired,
+            events_rate_limited,
+            events_oversized,
+            partitioned_peers,
+            partition_merges,
+            partition_conflicts,
+            degradation_level,
+            packets_lost,
+            packet_loss_permille,
+            reorder_depth,
         }
     }
 
@@ -1130,6 +1190,61 @@ impl NodeMetrics {
     pub fn quarantine_buffer_size(&self) -> &Gauge {
         &self.quarantine_buffer_size
     }
+
+    /// Returns a reference to the quarantine oldest age gauge.
+    pub fn quarantine_oldest_age_ms(&self) -> &Gauge {
+        &self.quarantine_oldest_age_ms
+    }
+
+    /// Returns a reference to the quarantine expired counter.
+    pub fn quarantine_expired(&self) -> &Counter {
+        &self.quarantine_expired
+    }
+
+    /// Returns a reference to the rate-limited events counter.
+    pub fn events_rate_limited(&self) -> &Counter {
+        &self.events_rate_limited
+    }
+
+    /// Returns a reference to the oversized events counter.
+    pub fn events_oversized(&self) -> &Counter {
+        &self.events_oversized
+    }
+
+    /// Returns a reference to the partitioned peers gauge.
+    pub fn partitioned_peers(&self) -> &Gauge {
+        &self.partitioned_peers
+    }
+
+    /// Returns a reference to the partition merges counter.
+    pub fn partition_merges(&self) -> &Counter {
+        &self.partition_merges
+    }
+
+    /// Returns a reference to the partition conflicts counter.
+    pub fn partition_conflicts(&self) -> &Counter {
+        &self.partition_conflicts
+    }
+
+    /// Returns a reference to the degradation level gauge.
+    pub fn degradation_level(&self) -> &Gauge {
+        &self.degradation_level
+    }
+
+    /// Returns a reference to the lost packets counter.
+    pub fn packets_lost(&self) -> &Counter {
+        &self.packets_lost
+    }
+
+    /// Returns a reference to the packet loss gauge.
+    pub fn packet_loss_permille(&self) -> &Gauge {
+        &self.packet_loss_permille
+    }
+
+    /// Returns a reference to the reorder depth gauge.
+    pub fn reorder_depth(&self) -> &Gauge {
+        &self.reorder_depth
+    }
 }
 
 impl std::fmt::Debug for NodeMetrics {
@@ -1149,6 +1264,17 @@ impl std::fmt::Debug for NodeMetrics {
             .field("time_drift_ms", &self.time_drift_ms.get())
             .field("state_divergence_count", &self.state_divergence_count.get())
             .field("quarantine_buffer_size", &self.quarantine_buffer_size.get())
+            .field("quarantine_oldest_age_ms", &self.quarantine_oldest_age_ms.get())
+            .field("quarantine_expired", &self.quarantine_expired.get())
+            .field("events_rate_limited", &self.events_rate_limited.get())
+            .field("events_oversized", &self.events_oversized.get())
+            .field("partitioned_peers", &self.partitioned_peers.get())
+            .field("partition_merges", &self.partition_merges.get())
+            .field("partition_conflicts", &self.partition_conflicts.get())
+            .field("degradation_level", &self.degradation_level.get())
+            .field("packets_lost", &self.packets_lost.get())
+            .field("packet_loss_permille", &self.packet_loss_permille.get())
+            .field("reorder_depth", &self.reorder_depth.get())
             .finish()
     }
 }
@@ -197,7 +197,7 @@ impl MetricsServer {
 
     /// Returns true if the server is currently running.
     pub fn is_running(&self) -> bool {
-        self.handle.as_ref().map_or(false, |h| !h.is_finished())
+        self.handle.as_ref().is_some_and(|h| !h.is_finished())
     }
 
     /// Returns the configured bind address.
@@ -132,7 +132,7 @@ pub fn reset_observability_for_testing() {
 ///     }),
 /// };
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct ObservabilityConfig {
     /// Optional logging configuration. If `None`, logging is not initialized.
     pub logging: Option<LoggingConfig>,
@@ -144,16 +144,6 @@ pub struct ObservabilityConfig {
     pub metrics_server: Option<MetricsServerConfig>,
 }
 
-impl Default for ObservabilityConfig {
-    fn default() -> Self {
-        Self {
-            logging: None,
-            tracing: None,
-            metrics_server: None,
-        }
-    }
-}
-
 /// Handle for managing the observability system lifecycle.
 ///
 /// This handle provides graceful shutdown for all initialized observability components.
@@ -257,7 +247,7 @@ impl ObservabilityHandle {
     pub fn is_metrics_server_running(&self) -> bool {
         self.metrics_server
             .as_ref()
-            .map_or(false, |s| s.is_running())
+            .is_some_and(|s| s.is_running())
     }
 }
 
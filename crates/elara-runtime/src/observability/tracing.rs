@@ -321,42 +321,10 @@ async fn init_otlp_exporter(
     Ok(tracer_provider)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[tokio::test]
-    async fn test_tracing_config_validation() {
-        // Test invalid sampling rate
-        let config = TracingConfig {
-            service_name: "test".to_string(),
-            exporter: TracingExporter::None,
-            sampling_rate: 1.5,
-            resource_attributes: vec![],
-        };
-
-        let result = init_tracing(config).await;
-        assert!(matches!(result, Err(TracingError::InvalidSamplingRate(_))));
-    }
-
-    #[tokio::test]
-    async fn test_disabled_tracing() {
-        let config = TracingConfig {
-            service_name: "test".to_string(),
-            exporter: TracingExporter::None,
-            sampling_rate: 1.0,
-            resource_attributes: vec![],
-        };
-
-        let result = init_tracing(config).await;
-        assert!(result.is_ok());
-    }
-}
-
-/// Span helper functions for common tracing patterns.
-///
-/// These functions create pre-configured spans for common operations in the ELARA Protocol,
-/// making it easier to maintain consistent tracing across the codebase.
+// Span helper functions for common tracing patterns.
+//
+// These functions create pre-configured spans for common operations in the ELARA Protocol,
+// making it easier to maintain consistent tracing across the codebase.
 
 /// Create a span for message send operations.
 ///
@@ -571,3 +539,35 @@ pub fn span_classify_events(node_id: u64, packet_count: usize) -> Span {
         packet_count = packet_count,
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_tracing_config_validation() {
+        // Test invalid sampling rate
+        let config = TracingConfig {
+            service_name: "test".to_string(),
+            exporter: TracingExporter::None,
+            sampling_rate: 1.5,
+            resource_attributes: vec![],
+        };
+
+        let result = init_tracing(config).await;
+        assert!(matches!(result, Err(TracingError::InvalidSamplingRate(_))));
+    }
+
+    #[tokio::test]
+    async fn test_disabled_tracing() {
+        let config = TracingConfig {
+            service_name: "test".to_string(),
+            exporter: TracingExporter::None,
+            sampling_rate: 1.0,
+            resource_attributes: vec![],
+        };
+
+        let result = init_tracing(config).await;
+        assert!(result.is_ok());
+    }
+}
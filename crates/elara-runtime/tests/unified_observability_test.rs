@@ -235,6 +235,13 @@ async fn test_node_config_observability_field() {
         max_local_events: 1000,
         metrics: None,
         health_checks: None,
+        crypto_suites: vec![elara_wire::CryptoSuite::Suite0],
+        compression: elara_wire::CompressionAlgorithm::Lz4,
+        max_batch_delay: std::collections::HashMap::new(),
+        sync_interval: Some(Duration::from_secs(1)),
+        repair_bandwidth: 64 * 1024,
+        snapshot_interval: Duration::from_secs(60),
+        reconciliation: Default::default(),
         observability: Some(ObservabilityConfig {
             logging: Some(LoggingConfig {
                 level: LogLevel::Info,
@@ -0,0 +1,373 @@
+//! Authority verification
+//!
+//! A `NodeId` is only a truncated hash of a public key, so anyone can claim
+//! one. Events prove their source with an Ed25519 signature over
+//! [`Event::signing_bytes`], checked here against the public keys of known
+//! peers.
+//!
+//! Nodes without direct authority over an atom may present a delegation
+//! chain: signed links leading from one of the atom's authorities down to
+//! the event source, each narrowing the scope granted. Every link is bound
+//! to one atom and one session.
+
+use std::collections::HashMap;
+
+use elara_core::{
+    AuthorityScope, AuthoritySet, DelegationLink, Event, NodeId, RejectReason, SessionId, StateId,
+    StateTime,
+};
+use elara_crypto::PublicIdentity;
+
+/// Longest delegation chain accepted
+pub const MAX_DELEGATION_DEPTH: usize = 8;
+
+/// Public keys of known peers, keyed by `NodeId`
+#[derive(Clone, Debug, Default)]
+pub struct KeyDirectory {
+    keys: HashMap<NodeId, PublicIdentity>,
+}
+
+impl KeyDirectory {
+    pub fn new() -> Self {
+        KeyDirectory::default()
+    }
+
+    /// Register a peer key under the `NodeId` derived from it
+    pub fn insert(&mut self, identity: PublicIdentity) {
+        self.keys.insert(identity.node_id(), identity);
+    }
+
+    /// Forget a peer key
+    pub fn remove(&mut self, node: NodeId) -> Option<PublicIdentity> {
+        self.keys.remove(&node)
+    }
+
+    /// Look up a peer key
+    pub fn get(&self, node: NodeId) -> Option<&PublicIdentity> {
+        self.keys.get(&node)
+    }
+
+    /// Check if a key is known for a node
+    pub fn contains(&self, node: NodeId) -> bool {
+        self.keys.contains_key(&node)
+    }
+
+    /// Number of known keys
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Check if empty
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Verify an event's authority signature in `session` against its
+    /// source's key
+    pub fn verify_event(&self, event: &Event, session: SessionId) -> Result<(), RejectReason> {
+        let key = self.get(event.source).ok_or(RejectReason::UnknownSigner)?;
+        if key.verify(
+            &event.signing_bytes(session),
+            &event.authority_proof.signature,
+        ) {
+            Ok(())
+        } else {
+            Err(RejectReason::InvalidSignature)
+        }
+    }
+
+    /// Verify a delegation link's signature against its delegator's key
+    pub fn verify_link(&self, link: &DelegationLink) -> Result<(), RejectReason> {
+        let key = self
+            .get(link.delegator)
+            .ok_or(RejectReason::UnknownSigner)?;
+        if key.verify(&link.signing_bytes(), &link.signature) {
+            Ok(())
+        } else {
+            Err(RejectReason::InvalidSignature)
+        }
+    }
+
+    /// Verify a delegation chain from `authority` down to `claimant`
+    ///
+    /// The chain must start at an owner or delegate of atom `target_state`,
+    /// link each delegate to the next delegator, and end at the claimant.
+    /// Every link must name that atom and `session`, be signed, unexpired
+    /// at `now`, and no wider than the scope it was granted from. Returns
+    /// the scope the claimant ends up holding.
+    pub fn verify_delegation_chain(
+        &self,
+        chain: &[DelegationLink],
+        authority: &AuthoritySet,
+        claimant: NodeId,
+        target_state: StateId,
+        session: SessionId,
+        now: StateTime,
+    ) -> Result<AuthorityScope, RejectReason> {
+        let first = chain.first().ok_or(RejectReason::Unauthorized)?;
+        if chain.len() > MAX_DELEGATION_DEPTH {
+            return Err(RejectReason::Unauthorized);
+        }
+
+        let mut scope = if authority.owners.contains(&first.delegator) {
+            AuthorityScope::Full
+        } else {
+            authority
+                .delegates
+                .get(&first.delegator)
+                .cloned()
+                .ok_or(RejectReason::Unauthorized)?
+        };
+        let mut current = first.delegator;
+
+        for link in chain {
+            if link.delegator != current {
+                return Err(RejectReason::Unauthorized);
+            }
+            if link.target_state != target_state || link.session != session {
+                return Err(RejectReason::Unauthorized);
+            }
+            if authority.is_revoked(&link.delegator) || authority.is_revoked(&link.delegate) {
+                return Err(RejectReason::AuthorityRevoked);
+            }
+            if link.expiry.is_some_and(|expiry| expiry < now) {
+                return Err(RejectReason::Unauthorized);
+            }
+            if !scope.allows(&link.scope) {
+                return Err(RejectReason::Unauthorized);
+            }
+            self.verify_link(link)?;
+
+            scope = link.scope.clone();
+            current = link.delegate;
+        }
+
+        if current == claimant {
+            Ok(scope)
+        } else {
+            Err(RejectReason::Unauthorized)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use elara_core::{EventType, MutationOp, StateId, TimeIntent};
+    use elara_crypto::Identity;
+
+    const ATOM: StateId = StateId(1);
+    const SESSION: SessionId = SessionId(1);
+
+    fn signed_event(identity: &Identity) -> Event {
+        let mut event = Event::new(
+            identity.node_id(),
+            1,
+            EventType::TextAppend,
+            StateId::new(1),
+            MutationOp::Append(b"hi".to_vec()),
+        );
+        event.authority_proof.signature = identity.sign(&event.signing_bytes(SESSION));
+        event
+    }
+
+    #[test]
+    fn test_verify_event() {
+        let alice = Identity::generate();
+        let mut directory = KeyDirectory::new();
+
+        let event = signed_event(&alice);
+        assert!(matches!(
+            directory.verify_event(&event, SESSION),
+            Err(RejectReason::UnknownSigner)
+        ));
+
+        directory.insert(alice.public_identity());
+        assert!(directory.verify_event(&event, SESSION).is_ok());
+
+        // Any signed field changed after signing breaks the signature
+        let shifted = event.clone().with_time_intent(TimeIntent::new(1000));
+        assert!(matches!(
+            directory.verify_event(&shifted, SESSION),
+            Err(RejectReason::InvalidSignature)
+        ));
+
+        // So does replaying the event into another session
+        assert!(matches!(
+            directory.verify_event(&event, SessionId::new(2)),
+            Err(RejectReason::InvalidSignature)
+        ));
+    }
+
+    fn link(
+        delegator: &Identity,
+        delegate: NodeId,
+        scope: AuthorityScope,
+        expiry: Option<StateTime>,
+    ) -> DelegationLink {
+        let mut link =
+            DelegationLink::new(delegator.node_id(), delegate, SESSION, ATOM, scope, expiry);
+        link.signature = delegator.sign(&link.signing_bytes());
+        link
+    }
+
+    #[test]
+    fn test_delegation_chain() {
+        let owner = Identity::generate();
+        let moderator = Identity::generate();
+        let helper = Identity::generate();
+        let mut directory = KeyDirectory::new();
+        for identity in [&owner, &moderator, &helper] {
+            directory.insert(identity.public_identity());
+        }
+        let authority = AuthoritySet::with_owner(owner.node_id());
+        let now = StateTime::from_millis(1000);
+
+        let chain = vec![
+            link(&owner, moderator.node_id(), AuthorityScope::Full, None),
+            link(
+                &moderator,
+                helper.node_id(),
+                AuthorityScope::Append,
+                Some(StateTime::from_millis(2000)),
+            ),
+        ];
+        assert_eq!(
+            directory.verify_delegation_chain(
+                &chain,
+                &authority,
+                helper.node_id(),
+                ATOM,
+                SESSION,
+                now
+            ),
+            Ok(AuthorityScope::Append)
+        );
+
+        // Wrong claimant, expired link
+        assert!(directory
+            .verify_delegation_chain(&chain, &authority, moderator.node_id(), ATOM, SESSION, now)
+            .is_err());
+        assert!(directory
+            .verify_delegation_chain(
+                &chain,
+                &authority,
+                helper.node_id(),
+                ATOM,
+                SESSION,
+                StateTime::from_millis(3000)
+            )
+            .is_err());
+
+        // Revoking the middle link breaks the chain
+        let mut revoked = authority.clone();
+        revoked.revoke(moderator.node_id());
+        assert_eq!(
+            directory.verify_delegation_chain(
+                &chain,
+                &revoked,
+                helper.node_id(),
+                ATOM,
+                SESSION,
+                now
+            ),
+            Err(RejectReason::AuthorityRevoked)
+        );
+    }
+
+    #[test]
+    fn test_delegation_cannot_widen_or_be_forged() {
+        let owner = Identity::generate();
+        let moderator = Identity::generate();
+        let helper = Identity::generate();
+        let mut directory = KeyDirectory::new();
+        for identity in [&owner, &moderator, &helper] {
+            directory.insert(identity.public_identity());
+        }
+        let authority = AuthoritySet::with_owner(owner.node_id());
+        let now = StateTime::ZERO;
+
+        // Append cannot be re-granted as Full
+        let widened = vec![
+            link(&owner, moderator.node_id(), AuthorityScope::Append, None),
+            link(&moderator, helper.node_id(), AuthorityScope::Full, None),
+        ];
+        assert_eq!(
+            directory.verify_delegation_chain(
+                &widened,
+                &authority,
+                helper.node_id(),
+                ATOM,
+                SESSION,
+                now
+            ),
+            Err(RejectReason::Unauthorized)
+        );
+
+        // A link claiming to be from the owner but signed by the moderator
+        let mut forged = link(&moderator, moderator.node_id(), AuthorityScope::Full, None);
+        forged.delegator = owner.node_id();
+        assert_eq!(
+            directory.verify_delegation_chain(
+                &[forged],
+                &authority,
+                moderator.node_id(),
+                ATOM,
+                SESSION,
+                now
+            ),
+            Err(RejectReason::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn test_delegation_bound_to_atom_and_session() {
+        let owner = Identity::generate();
+        let moderator = Identity::generate();
+        let mut directory = KeyDirectory::new();
+        for identity in [&owner, &moderator] {
+            directory.insert(identity.public_identity());
+        }
+        // The owner holds both atoms but delegated only the first
+        let authority = AuthoritySet::with_owner(owner.node_id());
+        let grant = link(&owner, moderator.node_id(), AuthorityScope::Full, None);
+        let chain = vec![grant];
+        let verify = |target_state, session| {
+            directory.verify_delegation_chain(
+                &chain,
+                &authority,
+                moderator.node_id(),
+                target_state,
+                session,
+                StateTime::ZERO,
+            )
+        };
+        assert_eq!(verify(ATOM, SESSION), Ok(AuthorityScope::Full));
+        assert_eq!(
+            verify(StateId::new(2), SESSION),
+            Err(RejectReason::Unauthorized)
+        );
+        assert_eq!(
+            verify(ATOM, SessionId::new(2)),
+            Err(RejectReason::Unauthorized)
+        );
+    }
+
+    #[test]
+    fn test_claimed_node_id_rejected() {
+        let alice = Identity::generate();
+        let mallory = Identity::generate();
+        let mut directory = KeyDirectory::new();
+        directory.insert(alice.public_identity());
+        directory.insert(mallory.public_identity());
+
+        // Mallory signs an event claiming to be Alice
+        let mut forged = signed_event(&mallory);
+        forged.source = alice.node_id();
+        forged.authority_proof.signature = mallory.sign(&forged.signing_bytes(SESSION));
+        assert!(matches!(
+            directory.verify_event(&forged, SESSION),
+            Err(RejectReason::InvalidSignature)
+        ));
+    }
+}
@@ -0,0 +1,159 @@
+//! State bounds enforcement - per-source rate limits and misbehaviour scores
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use elara_core::{NodeId, RateLimit, RejectReason, StateId, StateTime};
+
+/// Time for a misbehaviour score to decay to half its value
+pub const MISBEHAVIOUR_HALF_LIFE: Duration = Duration::from_secs(10);
+
+/// Token bucket for one (source, atom) pair
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    refilled: StateTime,
+}
+
+/// Token-bucket rate limiter keyed by (source, atom)
+///
+/// Each bucket holds up to `max_events` tokens and refills at
+/// `max_events` per `window_ms` of state time, so a source may burst a
+/// full window at once but not sustain more than the atom's rate.
+#[derive(Debug, Default)]
+pub struct InfluenceLimiter {
+    buckets: HashMap<(NodeId, StateId), Bucket>,
+}
+
+impl InfluenceLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Take a token for `source` writing to `state`
+    pub fn allow(
+        &mut self,
+        source: NodeId,
+        state: StateId,
+        limit: &RateLimit,
+        now: StateTime,
+    ) -> bool {
+        let capacity = limit.max_events as f64;
+        let bucket = self.buckets.entry((source, state)).or_insert(Bucket {
+            tokens: capacity,
+            refilled: now,
+        });
+
+        let elapsed_ms = (now - bucket.refilled).as_secs_f64() * 1000.0;
+        let rate = capacity / limit.window_ms.max(1) as f64;
+        bucket.tokens = (bucket.tokens + elapsed_ms * rate).min(capacity);
+        bucket.refilled = bucket.refilled.max(now);
+
+        if bucket.tokens < 1.0 {
+            return false;
+        }
+        bucket.tokens -= 1.0;
+        true
+    }
+
+    /// Drop every bucket for a removed atom
+    pub fn forget(&mut self, state: StateId) {
+        self.buckets.retain(|(_, id), _| *id != state);
+    }
+}
+
+/// Decaying per-peer misbehaviour scores
+///
+/// Bounds violations add a penalty to the offending source; scores halve
+/// every `MISBEHAVIOUR_HALF_LIFE` of state time, so bursts are forgiven
+/// while sustained abuse keeps a peer's score high.
+#[derive(Debug, Default)]
+pub struct MisbehaviourScores {
+    scores: HashMap<NodeId, (f64, StateTime)>,
+}
+
+impl MisbehaviourScores {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Penalty a rejection adds, zero for reasons that are not misbehaviour
+    pub fn penalty(reason: &RejectReason) -> f64 {
+        match reason {
+            RejectReason::RateLimitExceeded => 1.0,
+            RejectReason::StateBoundsExceeded => 4.0,
+            _ => 0.0,
+        }
+    }
+
+    /// Record a rejection of an event from `node`
+    pub fn record(&mut self, node: NodeId, reason: &RejectReason, now: StateTime) {
+        let penalty = Self::penalty(reason);
+        if penalty > 0.0 {
+            let score = self.score(node, now) + penalty;
+            self.scores.insert(node, (score, now));
+        }
+    }
+
+    /// Current score for `node`
+    pub fn score(&self, node: NodeId, now: StateTime) -> f64 {
+        let Some(&(score, at)) = self.scores.get(&node) else {
+            return 0.0;
+        };
+        let halvings = (now - at).as_secs_f64() / MISBEHAVIOUR_HALF_LIFE.as_secs_f64();
+        score * 0.5f64.powf(halvings)
+    }
+
+    /// Peers whose score is at least `threshold`, highest first
+    pub fn above(&self, threshold: f64, now: StateTime) -> Vec<(NodeId, f64)> {
+        let mut peers: Vec<(NodeId, f64)> = self
+            .scores
+            .keys()
+            .map(|&node| (node, self.score(node, now)))
+            .filter(|&(_, score)| score >= threshold)
+            .collect();
+        peers.sort_by(|a, b| b.1.total_cmp(&a.1));
+        peers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_bursts_then_refills() {
+        let mut limiter = InfluenceLimiter::new();
+        let limit = RateLimit::new(2, 1000);
+        let (node, state) = (NodeId::new(1), StateId::new(1));
+        let t0 = StateTime::from_millis(0);
+
+        assert!(limiter.allow(node, state, &limit, t0));
+        assert!(limiter.allow(node, state, &limit, t0));
+        assert!(!limiter.allow(node, state, &limit, t0));
+        // Buckets are per source
+        assert!(limiter.allow(NodeId::new(2), state, &limit, t0));
+
+        // Half a window refills one token
+        assert!(limiter.allow(node, state, &limit, StateTime::from_millis(500)));
+        assert!(!limiter.allow(node, state, &limit, StateTime::from_millis(500)));
+    }
+
+    #[test]
+    fn test_scores_accumulate_and_decay() {
+        let mut scores = MisbehaviourScores::new();
+        let node = NodeId::new(7);
+        let t0 = StateTime::from_millis(0);
+
+        scores.record(node, &RejectReason::RateLimitExceeded, t0);
+        scores.record(node, &RejectReason::StateBoundsExceeded, t0);
+        scores.record(node, &RejectReason::TooLate, t0);
+        assert_eq!(scores.score(node, t0), 5.0);
+
+        let later = t0 + MISBEHAVIOUR_HALF_LIFE;
+        assert!((scores.score(node, later) - 2.5).abs() < 1e-9);
+        let score = scores.score(node, later);
+        assert_eq!(scores.above(2.0, later), vec![(node, score)]);
+        assert!(scores.above(3.0, later).is_empty());
+    }
+}
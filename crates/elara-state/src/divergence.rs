@@ -0,0 +1,213 @@
+//! Divergence control - mapping field entropy onto the degradation ladder
+
+use std::collections::HashMap;
+
+use elara_core::{DegradationLevel, NodeId, RepresentationProfile, StateAtom};
+
+use crate::StateField;
+
+/// Entropy at which each step from L1 to L5 begins
+pub const DEFAULT_DEGRADATION_LADDER: [f64; 5] = [0.2, 0.4, 0.6, 0.8, 0.95];
+
+/// How far entropy must fall below a step before fidelity comes back
+pub const DEGRADATION_HYSTERESIS: f64 = 0.05;
+
+/// A (peer, profile) group whose degradation level moved
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DegradationChange {
+    pub peer: NodeId,
+    pub profile: RepresentationProfile,
+    pub from: DegradationLevel,
+    pub to: DegradationLevel,
+}
+
+impl DegradationChange {
+    /// Check if fidelity went down
+    pub fn is_worse(&self) -> bool {
+        self.to.is_worse_than(self.from)
+    }
+}
+
+/// Maps the entropy distribution of the field to degradation levels
+///
+/// Atoms are grouped by owning peer and representation profile, and each
+/// group degrades with its mean entropy. The overall level follows the
+/// entropy of the 75th-percentile atom, so one stale atom does not degrade
+/// the whole session. Levels drop as soon as entropy crosses a step, but
+/// only recover once it is `DEGRADATION_HYSTERESIS` below it.
+#[derive(Debug)]
+pub struct DivergenceController {
+    ladder: [f64; 5],
+    levels: HashMap<(NodeId, RepresentationProfile), DegradationLevel>,
+    overall: DegradationLevel,
+}
+
+impl DivergenceController {
+    pub fn new(ladder: [f64; 5]) -> Self {
+        DivergenceController {
+            ladder,
+            levels: HashMap::new(),
+            overall: DegradationLevel::L0_FullPerception,
+        }
+    }
+
+    /// Level for an entropy value, ignoring the current level
+    pub fn level_for(&self, entropy: f64) -> DegradationLevel {
+        let steps = self.ladder.iter().filter(|&&step| entropy >= step).count();
+        DegradationLevel::all()[steps]
+    }
+
+    /// Next level from `current`, recovering only past the hysteresis
+    fn settle(&self, current: DegradationLevel, entropy: f64) -> DegradationLevel {
+        let level = self.level_for(entropy);
+        if level.is_worse_than(current) {
+            return level;
+        }
+        self.level_for(entropy + DEGRADATION_HYSTERESIS)
+            .min(current)
+            .max(level)
+    }
+
+    /// Level of `peer`'s state under `profile`
+    pub fn level(&self, peer: NodeId, profile: RepresentationProfile) -> DegradationLevel {
+        self.levels
+            .get(&(peer, profile))
+            .copied()
+            .unwrap_or(DegradationLevel::L0_FullPerception)
+    }
+
+    /// Level of the field as a whole
+    pub fn overall(&self) -> DegradationLevel {
+        self.overall
+    }
+
+    /// Reassess every group, returning those whose level moved
+    pub fn assess(
+        &mut self,
+        field: &StateField,
+        profile_of: impl Fn(&StateAtom) -> RepresentationProfile,
+    ) -> Vec<DegradationChange> {
+        let mut groups: HashMap<(NodeId, RepresentationProfile), (f64, usize)> = HashMap::new();
+        let mut entropies = Vec::with_capacity(field.atoms.len());
+        for atom in field.atoms.values() {
+            entropies.push(atom.entropy.level);
+            let Some(&owner) = atom.authority.owners.iter().min() else {
+                continue;
+            };
+            let group = groups.entry((owner, profile_of(atom))).or_default();
+            group.0 += atom.entropy.level;
+            group.1 += 1;
+        }
+
+        self.levels.retain(|key, _| groups.contains_key(key));
+        let mut changes = Vec::new();
+        for (key, (total, count)) in groups {
+            let from = self.level(key.0, key.1);
+            let to = self.settle(from, total / count as f64);
+            if to != from {
+                changes.push(DegradationChange {
+                    peer: key.0,
+                    profile: key.1,
+                    from,
+                    to,
+                });
+            }
+            self.levels.insert(key, to);
+        }
+
+        entropies.sort_by(f64::total_cmp);
+        let p75 = entropies
+            .get(entropies.len().saturating_sub(1) * 3 / 4)
+            .copied()
+            .unwrap_or_default();
+        self.overall = self.settle(self.overall, p75);
+        changes
+    }
+}
+
+impl Default for DivergenceController {
+    fn default() -> Self {
+        Self::new(DEFAULT_DEGRADATION_LADDER)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use elara_core::{StateId, StateType};
+
+    fn field_with(entropies: &[(NodeId, u64, f64)]) -> StateField {
+        let mut field = StateField::new();
+        for &(owner, id, entropy) in entropies {
+            let atom = field.create_atom(StateId::new(id), StateType::Perceptual, owner);
+            atom.entropy.level = entropy;
+        }
+        field
+    }
+
+    #[test]
+    fn test_ladder_with_hysteresis() {
+        let controller = DivergenceController::default();
+        assert_eq!(
+            controller.level_for(0.0),
+            DegradationLevel::L0_FullPerception
+        );
+        assert_eq!(
+            controller.level_for(0.45),
+            DegradationLevel::L2_FragmentedPerception
+        );
+        assert_eq!(
+            controller.level_for(1.0),
+            DegradationLevel::L5_LatentPresence
+        );
+
+        let current = DegradationLevel::L2_FragmentedPerception;
+        assert_eq!(controller.settle(current, 0.38), current);
+        assert_eq!(
+            controller.settle(current, 0.3),
+            DegradationLevel::L1_DistortedPerception
+        );
+        assert_eq!(
+            controller.settle(current, 0.7),
+            DegradationLevel::L3_SymbolicPresence
+        );
+    }
+
+    #[test]
+    fn test_levels_per_peer_and_profile() {
+        let (alice, bob) = (NodeId::new(1), NodeId::new(2));
+        let profile_of = |atom: &StateAtom| {
+            if atom.id.0 >= 10 {
+                RepresentationProfile::VideoStandard
+            } else {
+                RepresentationProfile::Textual
+            }
+        };
+        let mut controller = DivergenceController::default();
+        let mut field = field_with(&[(alice, 1, 0.0), (alice, 10, 0.7), (bob, 11, 0.1)]);
+
+        let changes = controller.assess(&field, profile_of);
+        assert_eq!(
+            changes,
+            vec![DegradationChange {
+                peer: alice,
+                profile: RepresentationProfile::VideoStandard,
+                from: DegradationLevel::L0_FullPerception,
+                to: DegradationLevel::L3_SymbolicPresence,
+            }]
+        );
+        assert!(changes[0].is_worse());
+        assert_eq!(
+            controller.level(alice, RepresentationProfile::Textual),
+            DegradationLevel::L0_FullPerception
+        );
+        // One stale atom out of three leaves the session at full perception
+        assert_eq!(controller.overall(), DegradationLevel::L0_FullPerception);
+
+        // Fresh data restores the group
+        field.get_mut(StateId::new(10)).unwrap().entropy.reset();
+        let changes = controller.assess(&field, profile_of);
+        assert_eq!(changes[0].to, DegradationLevel::L0_FullPerception);
+        assert!(!changes[0].is_worse());
+    }
+}
@@ -0,0 +1,197 @@
+//! Early event buffer - events that arrived ahead of the reality window
+
+use std::collections::BTreeMap;
+
+use elara_core::{Event, PacketClass, StateTime};
+
+/// Default maximum number of buffered events
+pub const DEFAULT_EARLY_BUFFER_CAPACITY: usize = 1024;
+
+/// A buffered event with its absolute time fixed on arrival
+#[derive(Debug)]
+struct BufferedEvent {
+    event: Event,
+    class: PacketClass,
+}
+
+/// Bounded buffer of events beyond the prediction horizon
+///
+/// Events are ordered by absolute `StateTime` and released once τs
+/// advances far enough to bring them into the reality window. When full,
+/// droppable classes make room first; essential events only displace
+/// others due further in the future.
+#[derive(Debug)]
+pub struct EarlyEventBuffer {
+    /// Events keyed by (absolute time, arrival order)
+    events: BTreeMap<(StateTime, u64), BufferedEvent>,
+    /// Maximum number of buffered events
+    capacity: usize,
+    /// Arrival counter, breaks ties between equal times
+    arrivals: u64,
+    /// Events dropped on overflow
+    dropped: u64,
+}
+
+impl EarlyEventBuffer {
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_EARLY_BUFFER_CAPACITY)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        EarlyEventBuffer {
+            events: BTreeMap::new(),
+            capacity,
+            arrivals: 0,
+            dropped: 0,
+        }
+    }
+
+    /// Buffer an event of `class` due at absolute time `at`
+    ///
+    /// Returns `false` if the buffer is full and the event was dropped.
+    pub fn insert(&mut self, event: Event, class: PacketClass, at: StateTime) -> bool {
+        if self.events.len() >= self.capacity && !self.make_room(class, at) {
+            self.dropped += 1;
+            return false;
+        }
+
+        self.arrivals += 1;
+        self.events
+            .insert((at, self.arrivals), BufferedEvent { event, class });
+        true
+    }
+
+    /// Evict one event to admit a `class` event due at `at`
+    fn make_room(&mut self, class: PacketClass, at: StateTime) -> bool {
+        // Furthest-out droppable event goes first
+        let victim = self
+            .events
+            .iter()
+            .rev()
+            .find(|(_, buffered)| buffered.class.is_droppable())
+            .map(|(key, _)| *key);
+        let victim = match victim {
+            Some(key) => Some(key),
+            None if class.is_droppable() => None,
+            // Only essential events left: keep the ones due soonest
+            None => self.events.keys().next_back().copied().filter(|key| key.0 > at),
+        };
+
+        match victim {
+            Some(key) => {
+                self.events.remove(&key);
+                self.dropped += 1;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remove every event due at or before `until`, earliest first
+    pub fn drain_until(&mut self, until: StateTime) -> Vec<(Event, StateTime)> {
+        let mut due = Vec::new();
+        while let Some(entry) = self.events.first_entry() {
+            if entry.key().0 > until {
+                break;
+            }
+            let ((at, _), buffered) = entry.remove_entry();
+            due.push((buffered.event, at));
+        }
+        due
+    }
+
+    /// Absolute time of the earliest buffered event
+    pub fn next_due(&self) -> Option<StateTime> {
+        self.events.keys().next().map(|(at, _)| *at)
+    }
+
+    /// Number of buffered events
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Check if empty
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Events dropped on overflow
+    pub fn dropped_count(&self) -> u64 {
+        self.dropped
+    }
+}
+
+impl Default for EarlyEventBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use elara_core::{EventType, MutationOp, NodeId, StateId};
+
+    fn event(seq: u64) -> Event {
+        Event::new(
+            NodeId::new(1),
+            seq,
+            EventType::StateUpdate,
+            StateId::new(1),
+            MutationOp::Set(vec![seq as u8]),
+        )
+    }
+
+    fn at(ms: i64) -> StateTime {
+        StateTime::from_millis(ms)
+    }
+
+    fn drain_seqs(buffer: &mut EarlyEventBuffer) -> Vec<u64> {
+        buffer
+            .drain_until(at(i64::MAX / 1000))
+            .iter()
+            .map(|(e, _)| e.id.seq)
+            .collect()
+    }
+
+    #[test]
+    fn test_drains_in_time_order() {
+        let mut buffer = EarlyEventBuffer::new();
+        buffer.insert(event(1), PacketClass::Core, at(300));
+        buffer.insert(event(2), PacketClass::Core, at(100));
+        buffer.insert(event(3), PacketClass::Core, at(200));
+
+        let due = buffer.drain_until(at(200));
+        let seqs: Vec<u64> = due.iter().map(|(e, _)| e.id.seq).collect();
+        assert_eq!(seqs, vec![2, 3]);
+        assert_eq!(buffer.len(), 1);
+        assert_eq!(buffer.next_due(), Some(at(300)));
+    }
+
+    #[test]
+    fn test_overflow_keeps_essential_events_due_soonest() {
+        let mut buffer = EarlyEventBuffer::with_capacity(2);
+        assert!(buffer.insert(event(1), PacketClass::Core, at(100)));
+        assert!(buffer.insert(event(2), PacketClass::Core, at(300)));
+
+        // An essential event due sooner displaces the furthest one
+        assert!(buffer.insert(event(3), PacketClass::Core, at(200)));
+        // One due later than everything buffered is dropped
+        assert!(!buffer.insert(event(4), PacketClass::Core, at(400)));
+        assert_eq!(buffer.dropped_count(), 2);
+        assert_eq!(drain_seqs(&mut buffer), vec![1, 3]);
+    }
+
+    #[test]
+    fn test_overflow_drops_droppable_classes_first() {
+        let mut buffer = EarlyEventBuffer::with_capacity(2);
+        assert!(buffer.insert(event(1), PacketClass::Cosmetic, at(100)));
+        assert!(buffer.insert(event(2), PacketClass::Perceptual, at(300)));
+
+        // Cosmetic makes room even though the Perceptual event is further out
+        assert!(buffer.insert(event(3), PacketClass::Core, at(400)));
+        // A droppable event never displaces an essential one
+        assert!(!buffer.insert(event(4), PacketClass::Enhancement, at(50)));
+        assert_eq!(drain_seqs(&mut buffer), vec![2, 3]);
+    }
+}
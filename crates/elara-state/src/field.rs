@@ -1,8 +1,9 @@
 //! State field - collection of state atoms
 
 use std::collections::HashMap;
+use std::time::Duration;
 
-use elara_core::{NodeId, StateAtom, StateId, StateTime, StateType};
+use elara_core::{Event, NodeId, StateAtom, StateId, StateTime, StateType};
 
 /// State field - the local reality
 #[derive(Debug, Default)]
@@ -16,11 +17,26 @@ pub struct StateField {
 /// Event waiting for dependencies
 #[derive(Debug)]
 pub struct QuarantinedEvent {
-    pub event_data: Vec<u8>,
-    pub missing_deps: Vec<StateId>,
+    pub event: Event,
+    /// Version-vector entries the target atom must reach first
+    pub missing_deps: Vec<(NodeId, u64)>,
+    /// Absolute time the event was intended for
+    pub intended_at: StateTime,
     pub quarantined_at: StateTime,
 }
 
+impl QuarantinedEvent {
+    /// Whether the target atom has caught up with every missing entry
+    fn is_ready(&self, atoms: &HashMap<StateId, StateAtom>) -> bool {
+        let Some(atom) = atoms.get(&self.event.target_state) else {
+            return false;
+        };
+        self.missing_deps
+            .iter()
+            .all(|&(node, clock)| atom.version.get(node) >= clock)
+    }
+}
+
 impl StateField {
     pub fn new() -> Self {
         StateField::default()
@@ -83,24 +99,41 @@ impl StateField {
     }
 
     /// Add event to quarantine
-    pub fn quarantine(&mut self, event_data: Vec<u8>, missing_deps: Vec<StateId>, now: StateTime) {
+    pub fn quarantine(
+        &mut self,
+        event: Event,
+        missing_deps: Vec<(NodeId, u64)>,
+        intended_at: StateTime,
+        now: StateTime,
+    ) {
         self.quarantine.push(QuarantinedEvent {
-            event_data,
+            event,
             missing_deps,
+            intended_at,
             quarantined_at: now,
         });
     }
 
     /// Get quarantined events that can now be processed
-    pub fn release_quarantine(&mut self) -> Vec<Vec<u8>> {
+    pub fn release_quarantine(&mut self) -> Vec<QuarantinedEvent> {
         let atoms = &self.atoms;
-        let (ready, still_waiting): (Vec<_>, Vec<_>) = self
+        let (ready, still_waiting): (Vec<_>, Vec<_>) =
+            self.quarantine.drain(..).partition(|e| e.is_ready(atoms));
+
+        self.quarantine = still_waiting;
+        ready
+    }
+
+    /// Remove quarantined events older than `ttl`
+    pub fn expire_quarantine(&mut self, now: StateTime, ttl: Duration) -> Vec<QuarantinedEvent> {
+        let cutoff = now.saturating_sub(ttl);
+        let (expired, still_waiting): (Vec<_>, Vec<_>) = self
             .quarantine
             .drain(..)
-            .partition(|e| e.missing_deps.iter().all(|dep| atoms.contains_key(dep)));
+            .partition(|e| e.quarantined_at < cutoff);
 
         self.quarantine = still_waiting;
-        ready.into_iter().map(|e| e.event_data).collect()
+        expired
     }
 
     /// Get the number of quarantined events
@@ -108,6 +141,11 @@ impl StateField {
         self.quarantine.len()
     }
 
+    /// When the longest-waiting quarantined event arrived
+    pub fn oldest_quarantined(&self) -> Option<StateTime> {
+        self.quarantine.iter().map(|e| e.quarantined_at).min()
+    }
+
     /// Calculate total memory usage
     pub fn memory_size(&self) -> usize {
         self.atoms.values().map(|a| a.memory_size()).sum()
@@ -157,4 +195,42 @@ mod tests {
         let core_atoms: Vec<_> = field.iter_by_type(StateType::Core).collect();
         assert_eq!(core_atoms.len(), 2);
     }
+
+    #[test]
+    fn test_quarantine_release_and_expiry() {
+        use elara_core::{EventType, MutationOp};
+
+        let mut field = StateField::new();
+        let owner = NodeId::new(1);
+        let id = StateId::new(100);
+        field.create_atom(id, StateType::Core, owner);
+
+        let event = |seq| {
+            Event::new(
+                owner,
+                seq,
+                EventType::StateUpdate,
+                id,
+                MutationOp::Set(vec![]),
+            )
+        };
+        let now = StateTime::from_millis(0);
+        field.quarantine(event(3), vec![(owner, 2)], now, now);
+        field.quarantine(event(9), vec![(owner, 8)], now, StateTime::from_millis(500));
+        assert_eq!(field.oldest_quarantined(), Some(now));
+
+        assert!(field.release_quarantine().is_empty());
+        field.get_mut(id).unwrap().version.set(owner, 2);
+        let ready = field.release_quarantine();
+        assert_eq!(ready.len(), 1);
+        assert_eq!(ready[0].event.id.seq, 3);
+
+        let later = StateTime::from_millis(1000);
+        assert!(field
+            .expire_quarantine(later, Duration::from_millis(600))
+            .is_empty());
+        let expired = field.expire_quarantine(later, Duration::from_millis(400));
+        assert_eq!(expired.len(), 1);
+        assert_eq!(field.quarantine_size(), 0);
+    }
 }
@@ -0,0 +1,586 @@
+//! Event history - append-only log of applied events and field snapshots
+//!
+//! Events are the truth and the state field is their projection. With an
+//! `EventStore` attached, the reconciliation engine records every event it
+//! applies, with its authority proof, in order. Periodic `FieldSnapshot`s
+//! mark a position in the log, so a restarted node loads the latest snapshot
+//! and replays only what follows.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use elara_core::{
+    AuthorityProof, AuthorityScope, DelegationLink, EntropyHint, Event, EventType, MutationOp,
+    NodeId, RateLimit, StateAtom, StateBounds, StateId, StateTime, TimeIntent, VersionVector,
+};
+
+use crate::repair::{encode_bytes, encode_version, Reader};
+use crate::AtomSnapshot;
+
+/// File the event log is appended to inside a `FileEventStore` directory
+pub const EVENT_LOG_FILE: &str = "events.log";
+
+/// File holding the latest field snapshot
+pub const SNAPSHOT_FILE: &str = "snapshot.bin";
+
+/// One entry in the event log
+#[derive(Clone, Debug)]
+pub enum LogRecord {
+    /// An event applied at absolute state time `at`
+    Applied { event: Event, at: StateTime },
+}
+
+impl LogRecord {
+    /// The atom this record touched
+    pub fn state(&self) -> StateId {
+        match self {
+            LogRecord::Applied { event, .. } => event.target_state,
+        }
+    }
+
+    /// State time the record was applied at
+    pub fn at(&self) -> StateTime {
+        match self {
+            LogRecord::Applied { at, .. } => *at,
+        }
+    }
+
+    /// Encode for storage
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        match self {
+            LogRecord::Applied { event, at } => {
+                buf.push(0);
+                buf.extend_from_slice(&at.as_micros().to_le_bytes());
+                encode_event(&mut buf, event);
+            }
+        }
+        buf
+    }
+
+    /// Decode a stored record
+    pub fn decode(buf: &[u8]) -> Option<Self> {
+        let mut reader = Reader::new(buf);
+        let tag = reader.u8()?;
+        let at = StateTime::from_micros(i64::from_le_bytes(reader.array()?));
+        let record = match tag {
+            0 => LogRecord::Applied {
+                event: decode_event(&mut reader)?,
+                at,
+            },
+            _ => return None,
+        };
+        reader.finish()?;
+        Some(record)
+    }
+}
+
+/// An atom as stored in a field snapshot
+///
+/// Carries the authority and bounds an `AtomSnapshot` leaves to the
+/// receiving replica, so a rebuilt atom enforces what the original did.
+#[derive(Clone, Debug)]
+pub struct StoredAtom {
+    pub snapshot: AtomSnapshot,
+    pub delegates: Vec<(NodeId, AuthorityScope)>,
+    pub revoked: Vec<NodeId>,
+    pub bounds: StateBounds,
+    pub last_modified: StateTime,
+}
+
+impl StoredAtom {
+    /// Capture an atom
+    pub fn of(atom: &StateAtom) -> Self {
+        let mut delegates: Vec<(NodeId, AuthorityScope)> = atom
+            .authority
+            .delegates
+            .iter()
+            .map(|(node, scope)| (*node, scope.clone()))
+            .collect();
+        delegates.sort_by_key(|(node, _)| *node);
+        let mut revoked: Vec<NodeId> = atom.authority.revoked.iter().copied().collect();
+        revoked.sort();
+        StoredAtom {
+            snapshot: AtomSnapshot::of(atom),
+            delegates,
+            revoked,
+            bounds: atom.bounds.clone(),
+            last_modified: atom.last_modified,
+        }
+    }
+
+    /// Rebuild the atom
+    pub fn to_atom(&self) -> Option<StateAtom> {
+        let mut atom = self.snapshot.to_atom()?;
+        for (node, scope) in &self.delegates {
+            atom.authority.add_delegate(*node, scope.clone());
+        }
+        for node in &self.revoked {
+            atom.authority.revoke(*node);
+        }
+        atom.bounds = self.bounds.clone();
+        atom.last_modified = self.last_modified;
+        Some(atom)
+    }
+
+    fn encode(&self, buf: &mut Vec<u8>) {
+        encode_bytes(buf, &self.snapshot.encode());
+        buf.extend_from_slice(&(self.delegates.len() as u16).to_le_bytes());
+        for (node, scope) in &self.delegates {
+            buf.extend_from_slice(&node.to_bytes());
+            buf.extend_from_slice(&scope.encode());
+        }
+        buf.extend_from_slice(&(self.revoked.len() as u16).to_le_bytes());
+        for node in &self.revoked {
+            buf.extend_from_slice(&node.to_bytes());
+        }
+        buf.extend_from_slice(&(self.bounds.max_size as u64).to_le_bytes());
+        match &self.bounds.rate_limit {
+            Some(limit) => {
+                buf.push(1);
+                buf.extend_from_slice(&limit.max_events.to_le_bytes());
+                buf.extend_from_slice(&limit.window_ms.to_le_bytes());
+            }
+            None => buf.push(0),
+        }
+        buf.extend_from_slice(&self.bounds.max_entropy.to_le_bytes());
+        buf.extend_from_slice(&self.last_modified.as_micros().to_le_bytes());
+    }
+
+    fn decode(reader: &mut Reader) -> Option<Self> {
+        let snapshot = AtomSnapshot::decode(&reader.bytes()?)?;
+        let delegates = (0..reader.u16()?)
+            .map(|_| Some((reader.node()?, reader.decoded(AuthorityScope::decode)?)))
+            .collect::<Option<Vec<_>>>()?;
+        let revoked = (0..reader.u16()?)
+            .map(|_| reader.node())
+            .collect::<Option<Vec<_>>>()?;
+        let max_size = reader.u64()? as usize;
+        let rate_limit = match reader.u8()? {
+            0 => None,
+            1 => Some(RateLimit::new(reader.u32()?, reader.u32()?)),
+            _ => return None,
+        };
+        let max_entropy = f64::from_le_bytes(reader.array()?);
+        let last_modified = StateTime::from_micros(i64::from_le_bytes(reader.array()?));
+        Some(StoredAtom {
+            snapshot,
+            delegates,
+            revoked,
+            bounds: StateBounds {
+                max_size,
+                rate_limit,
+                max_entropy,
+            },
+            last_modified,
+        })
+    }
+}
+
+/// The whole state field as of the first `log_len` log records
+#[derive(Clone, Debug)]
+pub struct FieldSnapshot {
+    pub log_len: u64,
+    pub taken_at: StateTime,
+    pub atoms: Vec<StoredAtom>,
+}
+
+impl FieldSnapshot {
+    /// Encode for storage
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&self.log_len.to_le_bytes());
+        buf.extend_from_slice(&self.taken_at.as_micros().to_le_bytes());
+        buf.extend_from_slice(&(self.atoms.len() as u32).to_le_bytes());
+        for atom in &self.atoms {
+            atom.encode(&mut buf);
+        }
+        buf
+    }
+
+    /// Decode a stored snapshot
+    pub fn decode(buf: &[u8]) -> Option<Self> {
+        let mut reader = Reader::new(buf);
+        let log_len = reader.u64()?;
+        let taken_at = StateTime::from_micros(i64::from_le_bytes(reader.array()?));
+        let atoms = (0..reader.u32()?)
+            .map(|_| StoredAtom::decode(&mut reader))
+            .collect::<Option<Vec<_>>>()?;
+        reader.finish()?;
+        Some(FieldSnapshot {
+            log_len,
+            taken_at,
+            atoms,
+        })
+    }
+}
+
+/// An atom as it stood at a past state time, rebuilt from the log
+#[derive(Clone, Debug)]
+pub struct AtomProjection {
+    pub state: StateId,
+    pub at: StateTime,
+    /// The atom, `None` if it did not exist at `at`
+    pub atom: Option<StateAtom>,
+    /// Version frontier of the history the projection covers
+    pub frontier: VersionVector,
+}
+
+/// What happened to an atom between two state times
+#[derive(Clone, Debug)]
+pub struct AtomChanges {
+    pub before: AtomProjection,
+    pub after: AtomProjection,
+    /// The atom's log records in `(before.at, after.at]`, in log order
+    pub records: Vec<LogRecord>,
+}
+
+/// Storage for the event log and the latest field snapshot
+pub trait EventStore: Send + Sync {
+    /// Append a record to the log
+    fn append(&mut self, record: &LogRecord) -> io::Result<()>;
+
+    /// Number of records in the log
+    fn len(&self) -> u64;
+
+    /// Check if the log is empty
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Every record from position `start` on, in append order
+    fn read_from(&self, start: u64) -> io::Result<Vec<LogRecord>>;
+
+    /// Replace the stored snapshot
+    fn save_snapshot(&mut self, snapshot: &FieldSnapshot) -> io::Result<()>;
+
+    /// The latest stored snapshot, if any
+    fn load_snapshot(&self) -> io::Result<Option<FieldSnapshot>>;
+}
+
+/// Event store kept in memory, for tests and nodes without a disk
+#[derive(Debug, Default)]
+pub struct MemoryEventStore {
+    records: Vec<LogRecord>,
+    snapshot: Option<FieldSnapshot>,
+}
+
+impl MemoryEventStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl EventStore for MemoryEventStore {
+    fn append(&mut self, record: &LogRecord) -> io::Result<()> {
+        self.records.push(record.clone());
+        Ok(())
+    }
+
+    fn len(&self) -> u64 {
+        self.records.len() as u64
+    }
+
+    fn read_from(&self, start: u64) -> io::Result<Vec<LogRecord>> {
+        Ok(self.records.iter().skip(start as usize).cloned().collect())
+    }
+
+    fn save_snapshot(&mut self, snapshot: &FieldSnapshot) -> io::Result<()> {
+        self.snapshot = Some(snapshot.clone());
+        Ok(())
+    }
+
+    fn load_snapshot(&self) -> io::Result<Option<FieldSnapshot>> {
+        Ok(self.snapshot.clone())
+    }
+}
+
+/// Event store in a local directory
+///
+/// The log is a sequence of length-prefixed records appended to
+/// `EVENT_LOG_FILE`. A record torn by a crash is cut off when the store is
+/// reopened. Snapshots are written beside it and renamed into place, so a
+/// crash mid-write leaves the previous snapshot intact.
+#[derive(Debug)]
+pub struct FileEventStore {
+    dir: PathBuf,
+    log: File,
+    len: u64,
+}
+
+impl FileEventStore {
+    /// Open or create a store in `dir`
+    pub fn open(dir: impl AsRef<Path>) -> io::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+        let log = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(dir.join(EVENT_LOG_FILE))?;
+
+        let (records, valid) = Self::scan(&fs::read(dir.join(EVENT_LOG_FILE))?);
+        if valid < log.metadata()?.len() {
+            tracing::warn!(
+                dir = %dir.display(),
+                kept = records.len(),
+                "Truncating torn event log tail"
+            );
+            log.set_len(valid)?;
+        }
+
+        Ok(FileEventStore {
+            dir,
+            log,
+            len: records.len() as u64,
+        })
+    }
+
+    /// Directory the store lives in
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Decode every complete record, returning them and the bytes they span
+    fn scan(data: &[u8]) -> (Vec<LogRecord>, u64) {
+        let mut records = Vec::new();
+        let mut offset = 0;
+        while let Some(header) = data.get(offset..offset + 4) {
+            let len = u32::from_le_bytes(header.try_into().unwrap()) as usize;
+            let Some(record) = data
+                .get(offset + 4..offset + 4 + len)
+                .and_then(LogRecord::decode)
+            else {
+                break;
+            };
+            records.push(record);
+            offset += 4 + len;
+        }
+        (records, offset as u64)
+    }
+}
+
+impl EventStore for FileEventStore {
+    fn append(&mut self, record: &LogRecord) -> io::Result<()> {
+        let encoded = record.encode();
+        let mut buf = Vec::with_capacity(4 + encoded.len());
+        buf.extend_from_slice(&(encoded.len() as u32).to_le_bytes());
+        buf.extend_from_slice(&encoded);
+        self.log.write_all(&buf)?;
+        self.len += 1;
+        Ok(())
+    }
+
+    fn len(&self) -> u64 {
+        self.len
+    }
+
+    fn read_from(&self, start: u64) -> io::Result<Vec<LogRecord>> {
+        let mut data = Vec::new();
+        File::open(self.dir.join(EVENT_LOG_FILE))?.read_to_end(&mut data)?;
+        let (records, _) = Self::scan(&data);
+        Ok(records.into_iter().skip(start as usize).collect())
+    }
+
+    fn save_snapshot(&mut self, snapshot: &FieldSnapshot) -> io::Result<()> {
+        // The snapshot must not cover records that are not on disk yet
+        self.log.sync_data()?;
+        let tmp = self.dir.join(format!("{SNAPSHOT_FILE}.tmp"));
+        let mut file = File::create(&tmp)?;
+        file.write_all(&snapshot.encode())?;
+        file.sync_all()?;
+        fs::rename(tmp, self.dir.join(SNAPSHOT_FILE))
+    }
+
+    fn load_snapshot(&self) -> io::Result<Option<FieldSnapshot>> {
+        let data = match fs::read(self.dir.join(SNAPSHOT_FILE)) {
+            Ok(data) => data,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        FieldSnapshot::decode(&data)
+            .map(Some)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "corrupt field snapshot"))
+    }
+}
+
+pub(crate) fn encode_event(buf: &mut Vec<u8>, event: &Event) {
+    buf.push(event.event_type.to_byte());
+    buf.extend_from_slice(&event.source.to_bytes());
+    buf.extend_from_slice(&event.id.seq.to_le_bytes());
+    buf.extend_from_slice(&event.target_state.to_bytes());
+    encode_version(buf, &event.version_ref);
+    encode_bytes(buf, &event.mutation.encode());
+
+    buf.extend_from_slice(&event.time_intent.ts_offset().to_le_bytes());
+    match event.time_intent.deadline {
+        Some(deadline) => {
+            buf.push(1);
+            buf.extend_from_slice(&deadline.to_le_bytes());
+        }
+        None => buf.push(0),
+    }
+
+    buf.extend_from_slice(&event.authority_proof.signature);
+    let chain = event.authority_proof.delegation_chain.as_deref();
+    match chain {
+        Some(chain) => {
+            buf.push(1);
+            buf.push(chain.len() as u8);
+            for link in chain {
+                buf.extend_from_slice(&link.encode());
+            }
+        }
+        None => buf.push(0),
+    }
+
+    buf.extend_from_slice(&event.entropy_hint.entropy.to_le_bytes());
+    buf.extend_from_slice(&event.entropy_hint.confidence.to_le_bytes());
+}
+
+pub(crate) fn decode_event(reader: &mut Reader) -> Option<Event> {
+    let event_type = EventType::from_byte(reader.u8()?)?;
+    let source = reader.node()?;
+    let seq = reader.u64()?;
+    let target_state = StateId::from_bytes(reader.array()?);
+    let version_ref = reader.version()?;
+    let delta = reader.bytes()?;
+    let (mutation, used) = MutationOp::decode(&delta)?;
+    if used != delta.len() {
+        return None;
+    }
+
+    let mut time_intent = TimeIntent::new(i32::from_le_bytes(reader.array()?));
+    time_intent.deadline = match reader.u8()? {
+        0 => None,
+        1 => Some(i32::from_le_bytes(reader.array()?)),
+        _ => return None,
+    };
+
+    let mut proof = AuthorityProof::new(reader.array()?);
+    match reader.u8()? {
+        0 => {}
+        1 => {
+            let chain = (0..reader.u8()?)
+                .map(|_| reader.decoded(DelegationLink::decode))
+                .collect::<Option<Vec<_>>>()?;
+            proof = proof.with_delegation(chain);
+        }
+        _ => return None,
+    }
+
+    let entropy = f32::from_le_bytes(reader.array()?);
+    let confidence = f32::from_le_bytes(reader.array()?);
+
+    let mut event = Event::new(source, seq, event_type, target_state, mutation)
+        .with_version(version_ref)
+        .with_time_intent(time_intent)
+        .with_authority_proof(proof);
+    event.entropy_hint = EntropyHint::new(entropy, confidence);
+    Some(event)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use elara_core::{SessionId, StateType, VersionVector};
+
+    fn sample_event() -> Event {
+        let mut version = VersionVector::new();
+        version.increment(NodeId::new(2));
+        let link = DelegationLink::new(
+            NodeId::new(1),
+            NodeId::new(3),
+            SessionId::new(1),
+            StateId::new(9),
+            AuthorityScope::Append,
+            Some(StateTime::from_millis(500)),
+        );
+        Event::new(
+            NodeId::new(3),
+            4,
+            EventType::TextAppend,
+            StateId::new(9),
+            MutationOp::Append(b"hello".to_vec()),
+        )
+        .with_version(version)
+        .with_time_intent(TimeIntent::new(-25).with_deadline(40))
+        .with_authority_proof(AuthorityProof::new([7u8; 64]).with_delegation(vec![link]))
+    }
+
+    fn records() -> Vec<LogRecord> {
+        let mut later = sample_event();
+        later.mutation = MutationOp::Append(b" world".to_vec());
+        vec![
+            LogRecord::Applied {
+                event: sample_event(),
+                at: StateTime::from_millis(120),
+            },
+            LogRecord::Applied {
+                event: later,
+                at: StateTime::from_millis(130),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_log_record_roundtrip() {
+        let record = &records()[0];
+        let LogRecord::Applied { event, at } = LogRecord::decode(&record.encode()).unwrap();
+        let original = sample_event();
+        assert_eq!(at, StateTime::from_millis(120));
+        assert_eq!(event.id, original.id);
+        assert_eq!(
+            event.signing_bytes(SessionId::ZERO),
+            original.signing_bytes(SessionId::ZERO)
+        );
+        assert_eq!(event.time_intent.deadline, Some(40));
+        assert_eq!(
+            event.authority_proof.delegation_chain.unwrap()[0].encode(),
+            original.authority_proof.delegation_chain.unwrap()[0].encode()
+        );
+    }
+
+    #[test]
+    fn test_file_store_reopens_and_cuts_torn_tail() {
+        let dir = std::env::temp_dir().join(format!("elara-history-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        let mut store = FileEventStore::open(&dir).unwrap();
+        for record in records() {
+            store.append(&record).unwrap();
+        }
+        let mut atom = StateAtom::new(StateId::new(9), StateType::Core, NodeId::new(1));
+        atom.authority.revoke(NodeId::new(5));
+        atom.bounds.rate_limit = Some(RateLimit::new(10, 1000));
+        store
+            .save_snapshot(&FieldSnapshot {
+                log_len: 1,
+                taken_at: StateTime::from_millis(125),
+                atoms: vec![StoredAtom::of(&atom)],
+            })
+            .unwrap();
+        drop(store);
+
+        // A crash mid-append leaves a partial record behind
+        let mut log = OpenOptions::new()
+            .append(true)
+            .open(dir.join(EVENT_LOG_FILE))
+            .unwrap();
+        log.write_all(&[200, 0, 0, 0, 1, 2]).unwrap();
+
+        let store = FileEventStore::open(&dir).unwrap();
+        assert_eq!(store.len(), 2);
+        let tail = store.read_from(1).unwrap();
+        assert_eq!(tail.len(), 1);
+        assert_eq!(tail[0].at(), StateTime::from_millis(130));
+        let snapshot = store.load_snapshot().unwrap().unwrap();
+        assert_eq!(snapshot.log_len, 1);
+        let restored = snapshot.atoms[0].to_atom().unwrap();
+        assert!(restored.authority.is_revoked(&NodeId::new(5)));
+        assert_eq!(restored.bounds.rate_limit.unwrap().max_events, 10);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
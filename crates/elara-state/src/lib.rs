@@ -3,14 +3,36 @@
 //!
 //! This crate implements the State Reconciliation Engine:
 //! - State field management
-//! - Authority checking
-//! - Causality validation
+//! - Authority checking and pluggable reconciliation policies
+//! - State bounds and rate limiting
+//! - Event signature verification
+//! - Causality validation and quarantine
+//! - Early event buffering
 //! - Delta merge operations
+//! - Event history and field snapshots
 //! - Divergence control
 //! - Partition handling
 
+pub mod authority;
+pub mod bounds;
+pub mod divergence;
+pub mod early;
 pub mod field;
+pub mod history;
+pub mod merge;
+pub mod partition;
+pub mod policy;
 pub mod reconcile;
+pub mod repair;
 
+pub use authority::*;
+pub use bounds::*;
+pub use divergence::*;
+pub use early::*;
 pub use field::*;
+pub use history::*;
+pub use merge::*;
+pub use partition::*;
+pub use policy::*;
 pub use reconcile::*;
+pub use repair::*;
@@ -0,0 +1,692 @@
+//! Delta merge - per-law merge semantics for state atoms
+//!
+//! Every law other than last-writer-wins is convergent: replicas that
+//! apply the same events in any order render the same value.
+
+use std::collections::BTreeMap;
+
+use elara_core::{
+    BlendSample, CounterMerge, CounterSlot, DeltaLaw, Event, InterpolationType, MergeState,
+    MutationOp, NodeId, RejectReason, Sibling, StateAtom,
+};
+
+use crate::AtomSnapshot;
+
+/// Merge an event's mutation into an atom according to its delta law
+///
+/// The mutation is validated before anything is touched, so a rejected
+/// event leaves the atom unchanged. On success the value is re-rendered
+/// and the atom's entropy reset to reflect fresh data.
+///
+/// Accepted mutations per law:
+/// - `LastWriterWins`: `Set` replaces, `Append` extends
+/// - `AppendOnly`: `Append` and `Merge` add an entry; the oldest entries
+///   by `(seq, source)` are dropped beyond `max_size`
+/// - `Counter`: `Increment` adds to the source's slot, `Merge` carries an
+///   absolute `i64` report for it. A source should use one or the other.
+/// - `MultiValueRegister`: `Set` and `Merge` write a sibling that
+///   supersedes every sibling its `version_ref` has seen
+/// - `ContinuousBlend`: `Blend` replaces the source's weighted `f32`
+///   sample; `Set` is a blend at full weight
+pub fn merge_mutation(atom: &mut StateAtom, event: &Event) -> Result<(), RejectReason> {
+    let law = atom.delta_law.clone();
+    let spread = match law {
+        DeltaLaw::LastWriterWins => merge_plain(atom, &event.mutation).map(|_| 0.0)?,
+        DeltaLaw::AppendOnly { max_size } => merge_log(atom, event, max_size).map(|_| 0.0)?,
+        DeltaLaw::Counter { merge } => merge_counter(atom, event, merge).map(|_| 0.0)?,
+        DeltaLaw::MultiValueRegister => merge_register(atom, event).map(|_| 0.0)?,
+        DeltaLaw::ContinuousBlend { interpolation, .. } => merge_blend(atom, event, interpolation)?,
+    };
+
+    settle_entropy(atom, &law, spread);
+    Ok(())
+}
+
+/// Merge a peer's snapshot of the same atom into it
+///
+/// Uses the local atom's delta law. Convergent laws take the union of
+/// both sides' bookkeeping; last-writer-wins takes the newer value, and
+/// between concurrent values the greater by `(length, bytes)` so every
+/// replica picks the same one. Returns `false` if the snapshot holds
+/// nothing the atom has not already seen.
+pub fn merge_snapshot(atom: &mut StateAtom, snapshot: &AtomSnapshot) -> bool {
+    if snapshot.version == atom.version || snapshot.version.happens_before(&atom.version) {
+        return false;
+    }
+
+    let law = atom.delta_law.clone();
+    let mut spread = 0.0;
+    match law {
+        DeltaLaw::LastWriterWins => {
+            let newer = atom.version.happens_before(&snapshot.version);
+            if newer || (snapshot.value.len(), &snapshot.value) > (atom.value.len(), &atom.value) {
+                atom.value = snapshot.value.clone();
+            }
+        }
+        DeltaLaw::AppendOnly { max_size } => {
+            let entries = log_entries(&mut atom.merge);
+            if let MergeState::Log(remote) = &snapshot.merge {
+                for (key, data) in remote {
+                    entries.entry(*key).or_insert_with(|| data.clone());
+                }
+            }
+            atom.value = render_log(entries, max_size);
+        }
+        DeltaLaw::Counter { merge } => {
+            let slots = counter_slots(&mut atom.merge);
+            if let MergeState::Counter(remote) = &snapshot.merge {
+                for (source, theirs) in remote {
+                    let ours = slots.entry(*source).or_insert(*theirs);
+                    if (theirs.seq, theirs.total) > (ours.seq, ours.total) {
+                        *ours = *theirs;
+                    }
+                }
+            }
+            atom.value = render_counter(slots, merge);
+        }
+        DeltaLaw::MultiValueRegister => {
+            let siblings = register_siblings(&mut atom.merge);
+            if let MergeState::Siblings(remote) = &snapshot.merge {
+                for sibling in remote {
+                    insert_sibling(siblings, sibling.clone());
+                }
+            }
+            if let Some(last) = siblings.last() {
+                atom.value = last.value.clone();
+            }
+        }
+        DeltaLaw::ContinuousBlend { interpolation, .. } => {
+            let samples = blend_samples(&mut atom.merge);
+            if let MergeState::Blend(remote) = &snapshot.merge {
+                for (source, sample) in remote {
+                    match samples.get(source) {
+                        Some(existing) if existing.seq >= sample.seq => {}
+                        _ => {
+                            samples.insert(*source, sample.clone());
+                        }
+                    }
+                }
+            }
+            let (value, blend_spread) = render_blend(samples, interpolation);
+            atom.value = value;
+            spread = blend_spread;
+        }
+    }
+
+    atom.version = atom.version.merge(&snapshot.version);
+    settle_entropy(atom, &law, spread);
+    true
+}
+
+/// Reset entropy after fresh data, unless a blend's sources disagree
+fn settle_entropy(atom: &mut StateAtom, law: &DeltaLaw, spread: f32) {
+    atom.entropy.reset();
+    if let DeltaLaw::ContinuousBlend { max_deviation, .. } = *law {
+        // Sources disagreeing beyond the allowed deviation make the blend uncertain
+        let spread = spread as f64;
+        if spread > max_deviation {
+            atom.entropy.increase(1.0 - max_deviation.max(0.0) / spread);
+        }
+    }
+}
+
+fn merge_plain(atom: &mut StateAtom, mutation: &MutationOp) -> Result<(), RejectReason> {
+    match mutation {
+        MutationOp::Set(data) => atom.value = data.clone(),
+        MutationOp::Append(data) => atom.value.extend_from_slice(data),
+        _ => return Err(RejectReason::InvalidMutation),
+    }
+    Ok(())
+}
+
+fn merge_log(atom: &mut StateAtom, event: &Event, max_size: usize) -> Result<(), RejectReason> {
+    let data = match &event.mutation {
+        MutationOp::Append(data) | MutationOp::Merge(data) => data,
+        _ => return Err(RejectReason::InvalidMutation),
+    };
+
+    let entries = log_entries(&mut atom.merge);
+    entries
+        .entry((event.id.seq, event.source))
+        .or_insert_with(|| data.clone());
+    atom.value = render_log(entries, max_size);
+    Ok(())
+}
+
+fn log_entries(merge: &mut MergeState) -> &mut BTreeMap<(u64, NodeId), Vec<u8>> {
+    if !matches!(merge, MergeState::Log(_)) {
+        *merge = MergeState::Log(BTreeMap::new());
+    }
+    let MergeState::Log(entries) = merge else {
+        unreachable!()
+    };
+    entries
+}
+
+fn render_log(entries: &mut BTreeMap<(u64, NodeId), Vec<u8>>, max_size: usize) -> Vec<u8> {
+    // Truncating by key keeps the newest entries no matter the arrival order
+    while entries.len() > max_size {
+        entries.pop_first();
+    }
+    entries.values().flatten().copied().collect()
+}
+
+fn merge_counter(
+    atom: &mut StateAtom,
+    event: &Event,
+    merge: CounterMerge,
+) -> Result<(), RejectReason> {
+    let seq = event.id.seq;
+    let (delta, report) = match &event.mutation {
+        MutationOp::Increment(delta) => (*delta, None),
+        MutationOp::Merge(data) => {
+            let bytes = data
+                .as_slice()
+                .try_into()
+                .map_err(|_| RejectReason::OutOfBounds)?;
+            (0, Some(i64::from_le_bytes(bytes)))
+        }
+        _ => return Err(RejectReason::InvalidMutation),
+    };
+
+    let slots = counter_slots(&mut atom.merge);
+    match report {
+        None => {
+            let slot = slots.entry(event.source).or_default();
+            slot.total = slot.total.saturating_add(delta);
+            slot.seq = slot.seq.max(seq);
+        }
+        Some(total) => {
+            let slot = slots
+                .entry(event.source)
+                .or_insert(CounterSlot { total, seq });
+            if seq > slot.seq {
+                *slot = CounterSlot { total, seq };
+            }
+        }
+    }
+
+    atom.value = render_counter(slots, merge);
+    Ok(())
+}
+
+fn counter_slots(merge: &mut MergeState) -> &mut BTreeMap<NodeId, CounterSlot> {
+    if !matches!(merge, MergeState::Counter(_)) {
+        *merge = MergeState::Counter(BTreeMap::new());
+    }
+    let MergeState::Counter(slots) = merge else {
+        unreachable!()
+    };
+    slots
+}
+
+fn render_counter(slots: &BTreeMap<NodeId, CounterSlot>, merge: CounterMerge) -> Vec<u8> {
+    let totals = slots.values().map(|slot| slot.total as i128);
+    let value = match merge {
+        CounterMerge::Sum => totals.sum::<i128>(),
+        CounterMerge::Max => totals.max().unwrap_or(0),
+        CounterMerge::Average => totals.sum::<i128>() / slots.len().max(1) as i128,
+    };
+    let value = value.clamp(i64::MIN as i128, i64::MAX as i128) as i64;
+    value.to_le_bytes().to_vec()
+}
+
+fn merge_register(atom: &mut StateAtom, event: &Event) -> Result<(), RejectReason> {
+    let data = match &event.mutation {
+        MutationOp::Set(data) | MutationOp::Merge(data) => data,
+        _ => return Err(RejectReason::InvalidMutation),
+    };
+
+    // The write's own version: what it had seen, plus itself
+    let mut version = event.version_ref.clone();
+    version.set(event.source, event.id.seq);
+
+    let siblings = register_siblings(&mut atom.merge);
+    insert_sibling(
+        siblings,
+        Sibling {
+            source: event.source,
+            version,
+            value: data.clone(),
+        },
+    );
+
+    // Readers that ignore siblings still see one deterministic value
+    if let Some(last) = siblings.last() {
+        atom.value = last.value.clone();
+    }
+    Ok(())
+}
+
+fn register_siblings(merge: &mut MergeState) -> &mut Vec<Sibling> {
+    if !matches!(merge, MergeState::Siblings(_)) {
+        *merge = MergeState::Siblings(Vec::new());
+    }
+    let MergeState::Siblings(siblings) = merge else {
+        unreachable!()
+    };
+    siblings
+}
+
+/// Add a write unless it is known or superseded, dropping what it supersedes
+fn insert_sibling(siblings: &mut Vec<Sibling>, sibling: Sibling) {
+    let clock = sibling.version.get(sibling.source);
+    let superseded = siblings.iter().any(|s| {
+        (s.source == sibling.source && s.version.get(s.source) == clock)
+            || sibling.version.happens_before(&s.version)
+    });
+    if superseded {
+        return;
+    }
+    siblings.retain(|s| !s.version.happens_before(&sibling.version));
+    siblings.push(sibling);
+    siblings.sort_by_key(|s| (s.source, s.version.get(s.source)));
+}
+
+/// Merge a blend sample, returning how far the furthest source strays
+/// from the blended value
+fn merge_blend(
+    atom: &mut StateAtom,
+    event: &Event,
+    interpolation: InterpolationType,
+) -> Result<f32, RejectReason> {
+    let (data, weight) = match &event.mutation {
+        MutationOp::Blend { value, weight } => (value, *weight),
+        MutationOp::Set(value) => (value, 1.0),
+        _ => return Err(RejectReason::InvalidMutation),
+    };
+    if !weight.is_finite() || data.len() % 4 != 0 {
+        return Err(RejectReason::OutOfBounds);
+    }
+    let components: Vec<f32> = data
+        .chunks_exact(4)
+        .map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+        .collect();
+    if components.iter().any(|c| !c.is_finite()) {
+        return Err(RejectReason::OutOfBounds);
+    }
+
+    let samples = blend_samples(&mut atom.merge);
+    let sample = BlendSample {
+        seq: event.id.seq,
+        components,
+        weight: weight.clamp(0.0, 1.0),
+    };
+    match samples.get(&event.source) {
+        Some(existing) if existing.seq >= sample.seq => {}
+        _ => {
+            samples.insert(event.source, sample);
+        }
+    }
+
+    let (value, spread) = render_blend(samples, interpolation);
+    atom.value = value;
+    Ok(spread)
+}
+
+fn blend_samples(merge: &mut MergeState) -> &mut BTreeMap<NodeId, BlendSample> {
+    if !matches!(merge, MergeState::Blend(_)) {
+        *merge = MergeState::Blend(BTreeMap::new());
+    }
+    let MergeState::Blend(samples) = merge else {
+        unreachable!()
+    };
+    samples
+}
+
+/// Render the blended value and how far the furthest source strays from it
+fn render_blend(
+    samples: &BTreeMap<NodeId, BlendSample>,
+    interpolation: InterpolationType,
+) -> (Vec<u8>, f32) {
+    let blended = blend(samples, interpolation);
+    let spread = samples
+        .values()
+        .flat_map(|s| s.components.iter().zip(&blended))
+        .map(|(x, b)| (x - b).abs())
+        .fold(0.0, f32::max);
+    let bytes = blended.iter().flat_map(|c| c.to_le_bytes()).collect();
+    (bytes, spread)
+}
+
+/// Weighted mean of every source's sample, component by component
+///
+/// Sources are visited in `NodeId` order so the float sums are identical
+/// on every replica.
+fn blend(samples: &BTreeMap<NodeId, BlendSample>, interpolation: InterpolationType) -> Vec<f32> {
+    let len = samples
+        .values()
+        .map(|s| s.components.len())
+        .max()
+        .unwrap_or(0);
+    (0..len)
+        .map(|i| {
+            let mut weighted = 0.0f64;
+            let mut total_weight = 0.0f64;
+            let mut plain = 0.0f64;
+            let mut count = 0u32;
+            for sample in samples.values() {
+                let Some(&x) = sample.components.get(i) else {
+                    continue;
+                };
+                let w = ease(interpolation, sample.weight) as f64;
+                weighted += w * x as f64;
+                total_weight += w;
+                plain += x as f64;
+                count += 1;
+            }
+            if total_weight > 0.0 {
+                (weighted / total_weight) as f32
+            } else {
+                (plain / count as f64) as f32
+            }
+        })
+        .collect()
+}
+
+/// Shape a blend weight by the law's interpolation curve
+fn ease(interpolation: InterpolationType, w: f32) -> f32 {
+    match interpolation {
+        InterpolationType::Linear => w,
+        // Smoothstep
+        InterpolationType::Cubic => w * w * (3.0 - 2.0 * w),
+        // Catmull-Rom segment through control points 0, 0, 1, 1
+        InterpolationType::Catmull => 0.5 * (w + 3.0 * w * w - 2.0 * w * w * w),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use elara_core::{EventType, StateId, StateType, VersionVector};
+    use proptest::prelude::*;
+
+    fn atom(law: DeltaLaw) -> StateAtom {
+        let mut atom = StateAtom::new(StateId::new(1), StateType::Core, NodeId::new(1));
+        atom.delta_law = law;
+        atom
+    }
+
+    fn event(source: u64, seq: u64, mutation: MutationOp) -> Event {
+        Event::new(
+            NodeId::new(source),
+            seq,
+            EventType::StateUpdate,
+            StateId::new(1),
+            mutation,
+        )
+    }
+
+    fn blend_bytes(components: &[f32]) -> Vec<u8> {
+        components.iter().flat_map(|c| c.to_le_bytes()).collect()
+    }
+
+    /// Apply events in order, ignoring rejections
+    fn replay(law: &DeltaLaw, events: &[Event]) -> StateAtom {
+        let mut atom = atom(law.clone());
+        for event in events {
+            let _ = merge_mutation(&mut atom, event);
+        }
+        atom
+    }
+
+    #[test]
+    fn test_counter_laws() {
+        let events = [
+            event(1, 1, MutationOp::Increment(4)),
+            event(2, 1, MutationOp::Increment(10)),
+            event(1, 2, MutationOp::Increment(2)),
+        ];
+        let value = |merge| {
+            let atom = replay(&DeltaLaw::Counter { merge }, &events);
+            i64::from_le_bytes(atom.value.try_into().unwrap())
+        };
+        assert_eq!(value(CounterMerge::Sum), 16);
+        assert_eq!(value(CounterMerge::Max), 10);
+        assert_eq!(value(CounterMerge::Average), 8);
+
+        // Absolute reports resolve by sequence per source
+        let reports = [
+            event(1, 2, MutationOp::Merge(20i64.to_le_bytes().to_vec())),
+            event(1, 1, MutationOp::Merge(5i64.to_le_bytes().to_vec())),
+        ];
+        let atom = replay(
+            &DeltaLaw::Counter {
+                merge: CounterMerge::Max,
+            },
+            &reports,
+        );
+        assert_eq!(atom.value, 20i64.to_le_bytes().to_vec());
+    }
+
+    #[test]
+    fn test_append_only_truncation() {
+        let law = DeltaLaw::AppendOnly { max_size: 2 };
+        let events = [
+            event(1, 3, MutationOp::Append(vec![3])),
+            event(1, 1, MutationOp::Append(vec![1])),
+            event(1, 2, MutationOp::Append(vec![2])),
+        ];
+        let atom = replay(&law, &events);
+        assert_eq!(atom.value, vec![2, 3]);
+
+        let mut atom = atom;
+        assert_eq!(
+            merge_mutation(&mut atom, &event(1, 4, MutationOp::Set(vec![9]))),
+            Err(RejectReason::InvalidMutation)
+        );
+        assert_eq!(atom.value, vec![2, 3]);
+    }
+
+    #[test]
+    fn test_register_siblings() {
+        let law = DeltaLaw::MultiValueRegister;
+        let mut atom = replay(
+            &law,
+            &[
+                event(1, 1, MutationOp::Set(b"a".to_vec())),
+                event(2, 1, MutationOp::Set(b"b".to_vec())),
+            ],
+        );
+        // Concurrent writes are both kept
+        assert_eq!(atom.siblings().len(), 2);
+
+        // A write that saw both supersedes them
+        let mut seen = VersionVector::new();
+        seen.set(NodeId::new(1), 1);
+        seen.set(NodeId::new(2), 1);
+        let resolve = event(1, 2, MutationOp::Set(b"c".to_vec())).with_version(seen);
+        merge_mutation(&mut atom, &resolve).unwrap();
+        assert_eq!(atom.siblings().len(), 1);
+        assert_eq!(atom.value, b"c".to_vec());
+    }
+
+    #[test]
+    fn test_blend_weights_and_interpolation() {
+        let events = [
+            event(
+                1,
+                1,
+                MutationOp::Blend {
+                    value: blend_bytes(&[0.0, 1.0]),
+                    weight: 1.0,
+                },
+            ),
+            event(
+                2,
+                1,
+                MutationOp::Blend {
+                    value: blend_bytes(&[1.0]),
+                    weight: 0.25,
+                },
+            ),
+        ];
+        let blended = |interpolation| {
+            let law = DeltaLaw::ContinuousBlend {
+                interpolation,
+                max_deviation: 1.0,
+            };
+            replay(&law, &events).value
+        };
+        // The second source's 0.25 weight eases to 0.15625 (smoothstep)
+        // and 0.203125 (Catmull-Rom)
+        assert_eq!(
+            blended(InterpolationType::Linear),
+            blend_bytes(&[(0.25f64 / 1.25) as f32, 1.0])
+        );
+        assert_eq!(
+            blended(InterpolationType::Cubic),
+            blend_bytes(&[(0.15625f64 / 1.15625) as f32, 1.0])
+        );
+        assert_eq!(
+            blended(InterpolationType::Catmull),
+            blend_bytes(&[(0.203125f64 / 1.203125) as f32, 1.0])
+        );
+
+        // Disagreement beyond max_deviation raises entropy
+        let law = DeltaLaw::ContinuousBlend {
+            interpolation: InterpolationType::Linear,
+            max_deviation: 0.1,
+        };
+        assert!(replay(&law, &events).entropy.level > 0.0);
+
+        let mut atom = atom(law);
+        assert_eq!(
+            merge_mutation(&mut atom, &event(1, 1, MutationOp::Set(vec![0, 0, 0]))),
+            Err(RejectReason::OutOfBounds)
+        );
+    }
+
+    #[test]
+    fn test_snapshot_merge_last_writer_wins() {
+        let mut a = atom(DeltaLaw::LastWriterWins);
+        a.value = b"left".to_vec();
+        a.version.set(NodeId::new(1), 1);
+        let mut b = atom(DeltaLaw::LastWriterWins);
+        b.value = b"right".to_vec();
+        b.version.set(NodeId::new(2), 1);
+
+        // Concurrent values: both replicas settle on the same one
+        let (snap_a, snap_b) = (AtomSnapshot::of(&a), AtomSnapshot::of(&b));
+        assert!(merge_snapshot(&mut a, &snap_b));
+        assert!(merge_snapshot(&mut b, &snap_a));
+        assert_eq!(a.value, b"right".to_vec());
+        assert_eq!(a.value, b.value);
+        assert_eq!(a.version, b.version);
+
+        // Nothing new the second time round
+        assert!(!merge_snapshot(&mut a, &AtomSnapshot::of(&b)));
+
+        // A strictly newer snapshot wins even with a smaller value
+        let mut newer = b.clone();
+        newer.value = b"x".to_vec();
+        newer.version.increment(NodeId::new(1));
+        assert!(merge_snapshot(&mut a, &AtomSnapshot::of(&newer)));
+        assert_eq!(a.value, b"x".to_vec());
+    }
+
+    #[test]
+    fn test_last_writer_wins_rejects_unknown_ops() {
+        let mut atom = atom(DeltaLaw::LastWriterWins);
+        assert_eq!(
+            merge_mutation(&mut atom, &event(1, 1, MutationOp::Increment(1))),
+            Err(RejectReason::InvalidMutation)
+        );
+    }
+
+    fn arb_law() -> impl Strategy<Value = DeltaLaw> {
+        prop_oneof![
+            (1usize..8).prop_map(|max_size| DeltaLaw::AppendOnly { max_size }),
+            prop_oneof![
+                Just(CounterMerge::Sum),
+                Just(CounterMerge::Max),
+                Just(CounterMerge::Average),
+            ]
+            .prop_map(|merge| DeltaLaw::Counter { merge }),
+            Just(DeltaLaw::MultiValueRegister),
+            prop_oneof![
+                Just(InterpolationType::Linear),
+                Just(InterpolationType::Cubic),
+                Just(InterpolationType::Catmull),
+            ]
+            .prop_map(|interpolation| DeltaLaw::ContinuousBlend {
+                interpolation,
+                max_deviation: 0.5,
+            }),
+        ]
+    }
+
+    /// Events from up to four sources, numbered from one shared counter
+    /// the way a node stamps the signed sequence it puts on the wire
+    fn arb_events() -> impl Strategy<Value = Vec<Event>> {
+        let op = prop_oneof![
+            (-50i64..50).prop_map(MutationOp::Increment),
+            prop::collection::vec(any::<u8>(), 0..4).prop_map(MutationOp::Append),
+            prop::collection::vec(any::<u8>(), 0..4).prop_map(MutationOp::Set),
+            (prop::collection::vec(-10.0f32..10.0, 0..3), 0.0f32..1.0).prop_map(
+                |(components, weight)| MutationOp::Blend {
+                    value: blend_bytes(&components),
+                    weight,
+                }
+            ),
+        ];
+        let seen = prop::collection::vec((1u64..5, 0u64..4), 0..3);
+        prop::collection::vec((1u64..5, op, seen), 1..24).prop_map(|ops| {
+            let mut next_seq = 0u64;
+            ops.into_iter()
+                .map(|(source, op, seen)| {
+                    next_seq += 1;
+                    let mut version = VersionVector::new();
+                    for (node, clock) in seen {
+                        version.set(NodeId::new(node), clock);
+                    }
+                    event(source, next_seq, op).with_version(version)
+                })
+                .collect()
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn prop_snapshot_exchange_matches_full_replay(
+            law in arb_law(),
+            events in arb_events(),
+        ) {
+            // Each replica hears every event of its own half of the sources
+            let (odd, even): (Vec<Event>, Vec<Event>) =
+                events.iter().cloned().partition(|e| e.source.0 % 2 == 1);
+            let full = replay(&law, &events);
+            let replica = |events: &[Event]| {
+                let mut atom = atom(law.clone());
+                for event in events {
+                    if merge_mutation(&mut atom, event).is_ok() {
+                        atom.version.increment(event.source);
+                    }
+                }
+                atom
+            };
+            let (mut a, mut b) = (replica(&odd), replica(&even));
+
+            let (snap_a, snap_b) = (AtomSnapshot::of(&a), AtomSnapshot::of(&b));
+            merge_snapshot(&mut a, &snap_b);
+            merge_snapshot(&mut b, &snap_a);
+            prop_assert_eq!(&a.value, &full.value);
+            prop_assert_eq!(&b.value, &full.value);
+            prop_assert_eq!(a.siblings(), full.siblings());
+            prop_assert_eq!(b.entropy.level, full.entropy.level);
+        }
+
+        #[test]
+        fn prop_replicas_converge_under_any_delivery_order(
+            law in arb_law(),
+            (events, shuffled) in arb_events()
+                .prop_flat_map(|events| (Just(events.clone()), Just(events).prop_shuffle())),
+        ) {
+            let a = replay(&law, &events);
+            let b = replay(&law, &shuffled);
+            prop_assert_eq!(&a.value, &b.value);
+            prop_assert_eq!(a.siblings(), b.siblings());
+            prop_assert_eq!(a.entropy.level, b.entropy.level);
+        }
+    }
+}
@@ -0,0 +1,385 @@
+//! Partition handling - detecting cut-off peers and reporting merges on heal
+
+use std::collections::{BTreeSet, HashMap};
+use std::time::Duration;
+
+use elara_core::{DeltaLaw, NodeId, StateId, StateTime, VersionVector};
+use elara_time::NetworkModel;
+
+use crate::StateField;
+
+/// Default time a peer must be silent and stalled to count as partitioned
+pub const DEFAULT_PARTITION_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Default time a healed partition gets to exchange history before reporting
+pub const DEFAULT_MERGE_WINDOW: Duration = Duration::from_secs(2);
+
+/// How a partition merge reconciled one atom
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MergeOutcome {
+    /// Only the other side changed the atom
+    FastForward,
+    /// Both sides changed it and its law merged them without loss
+    ConcurrentMerged,
+    /// Both sides changed it and its law kept one side, or both values
+    Conflicted,
+}
+
+impl MergeOutcome {
+    /// Outcome for an atom both sides changed
+    fn concurrent(law: &DeltaLaw) -> Self {
+        match law {
+            DeltaLaw::LastWriterWins | DeltaLaw::MultiValueRegister => MergeOutcome::Conflicted,
+            DeltaLaw::AppendOnly { .. }
+            | DeltaLaw::Counter { .. }
+            | DeltaLaw::ContinuousBlend { .. } => MergeOutcome::ConcurrentMerged,
+        }
+    }
+}
+
+/// How the histories of a healed partition were reconciled
+#[derive(Clone, Debug)]
+pub struct PartitionMergeReport {
+    /// Peers that were cut off and came back
+    pub peers: Vec<NodeId>,
+    pub partitioned_at: StateTime,
+    pub healed_at: StateTime,
+    /// Atoms the merge changed, ordered by id
+    pub atoms: Vec<(StateId, MergeOutcome)>,
+}
+
+impl PartitionMergeReport {
+    /// Number of atoms merged with `outcome`
+    pub fn count(&self, outcome: MergeOutcome) -> usize {
+        self.atoms.iter().filter(|(_, o)| *o == outcome).count()
+    }
+}
+
+/// Peers currently cut off and the field versions from when they went
+#[derive(Debug)]
+struct Partition {
+    peers: BTreeSet<NodeId>,
+    since: StateTime,
+    base: HashMap<StateId, VersionVector>,
+}
+
+/// A healed partition waiting for anti-entropy to settle
+#[derive(Debug)]
+struct PendingMerge {
+    peers: Vec<NodeId>,
+    partitioned_at: StateTime,
+    healed_at: StateTime,
+    base: HashMap<StateId, VersionVector>,
+    local: HashMap<StateId, VersionVector>,
+}
+
+impl PendingMerge {
+    /// Compare every atom against its versions at the partition and heal
+    fn report(self, field: &StateField) -> PartitionMergeReport {
+        let empty = VersionVector::new();
+        let mut atoms: Vec<(StateId, MergeOutcome)> = field
+            .atoms
+            .values()
+            .filter_map(|atom| {
+                let local = self.local.get(&atom.id).unwrap_or(&empty);
+                if atom.version == *local {
+                    return None;
+                }
+                let base = self.base.get(&atom.id).unwrap_or(&empty);
+                let outcome = if local == base {
+                    MergeOutcome::FastForward
+                } else {
+                    MergeOutcome::concurrent(&atom.delta_law)
+                };
+                Some((atom.id, outcome))
+            })
+            .collect();
+        atoms.sort_by_key(|(id, _)| id.0);
+
+        PartitionMergeReport {
+            peers: self.peers,
+            partitioned_at: self.partitioned_at,
+            healed_at: self.healed_at,
+            atoms,
+        }
+    }
+}
+
+/// Detects partitions from peer liveness and version stalls
+///
+/// A peer is partitioned once the network model has not heard from it for
+/// `timeout` and its clock in the field's version frontier has not moved
+/// for as long, so a peer whose state still reaches us through others is
+/// not cut off. When a partitioned peer is heard again the detector waits
+/// `merge_window` for anti-entropy to exchange the missed history, then
+/// classifies every atom the merge changed.
+#[derive(Debug)]
+pub struct PartitionDetector {
+    timeout: Duration,
+    merge_window: Duration,
+    /// Each peer's frontier clock and when it last advanced
+    progress: HashMap<NodeId, (u64, StateTime)>,
+    partition: Option<Partition>,
+    pending: Vec<PendingMerge>,
+}
+
+impl PartitionDetector {
+    pub fn new(timeout: Duration, merge_window: Duration) -> Self {
+        PartitionDetector {
+            timeout,
+            merge_window,
+            progress: HashMap::new(),
+            partition: None,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Peers currently partitioned away
+    pub fn partitioned(&self) -> Vec<NodeId> {
+        self.partition
+            .as_ref()
+            .map(|partition| partition.peers.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Check if any peer is partitioned away
+    pub fn is_partitioned(&self) -> bool {
+        self.partition.is_some()
+    }
+
+    /// Stop watching a peer that left
+    pub fn forget(&mut self, peer: NodeId) {
+        self.progress.remove(&peer);
+        if let Some(partition) = self.partition.as_mut() {
+            partition.peers.remove(&peer);
+            if partition.peers.is_empty() {
+                self.partition = None;
+            }
+        }
+    }
+
+    /// Update partitions at state time `now` and report settled merges
+    pub fn check(
+        &mut self,
+        network: &NetworkModel,
+        field: &StateField,
+        now: StateTime,
+    ) -> Vec<PartitionMergeReport> {
+        let silent: BTreeSet<NodeId> = network
+            .silent_peers(now.as_secs_f64(), self.timeout.as_secs_f64())
+            .into_iter()
+            .collect();
+
+        let frontier = field
+            .atoms
+            .values()
+            .fold(VersionVector::new(), |frontier, atom| {
+                frontier.merge(&atom.version)
+            });
+        for &peer in network.peers.keys() {
+            let clock = frontier.get(peer);
+            let progress = self.progress.entry(peer).or_insert((clock, now));
+            if clock > progress.0 {
+                *progress = (clock, now);
+            }
+        }
+
+        self.detect(&silent, field, now);
+        self.heal(&silent, field, now);
+
+        let (ready, waiting) = std::mem::take(&mut self.pending)
+            .into_iter()
+            .partition(|merge: &PendingMerge| now - merge.healed_at >= self.merge_window);
+        self.pending = waiting;
+        ready.into_iter().map(|merge| merge.report(field)).collect()
+    }
+
+    /// Start or widen the partition with peers that went silent and stalled
+    fn detect(&mut self, silent: &BTreeSet<NodeId>, field: &StateField, now: StateTime) {
+        let cut_off: Vec<NodeId> = silent
+            .iter()
+            .filter(|peer| {
+                let stalled = self
+                    .progress
+                    .get(peer)
+                    .is_some_and(|&(_, advanced)| now - advanced > self.timeout);
+                let known = self
+                    .partition
+                    .as_ref()
+                    .is_some_and(|partition| partition.peers.contains(peer));
+                stalled && !known
+            })
+            .copied()
+            .collect();
+        if cut_off.is_empty() {
+            return;
+        }
+
+        let partition = self.partition.get_or_insert_with(|| Partition {
+            peers: BTreeSet::new(),
+            since: now,
+            base: versions(field),
+        });
+        for peer in cut_off {
+            tracing::warn!(peer_id = peer.0, "Peer partitioned away");
+            partition.peers.insert(peer);
+        }
+    }
+
+    /// Queue a merge for partitioned peers that are heard again
+    fn heal(&mut self, silent: &BTreeSet<NodeId>, field: &StateField, now: StateTime) {
+        let Some(partition) = self.partition.as_mut() else {
+            return;
+        };
+        let healed: Vec<NodeId> = partition
+            .peers
+            .iter()
+            .filter(|peer| !silent.contains(peer))
+            .copied()
+            .collect();
+        if healed.is_empty() {
+            return;
+        }
+        for peer in &healed {
+            partition.peers.remove(peer);
+        }
+        tracing::info!(peers = healed.len(), "Partition healed");
+
+        let base = if partition.peers.is_empty() {
+            let partition = self.partition.take().expect("partition is active");
+            (partition.since, partition.base)
+        } else {
+            (partition.since, partition.base.clone())
+        };
+        self.pending.push(PendingMerge {
+            peers: healed,
+            partitioned_at: base.0,
+            healed_at: now,
+            base: base.1,
+            local: versions(field),
+        });
+    }
+}
+
+impl Default for PartitionDetector {
+    fn default() -> Self {
+        Self::new(DEFAULT_PARTITION_TIMEOUT, DEFAULT_MERGE_WINDOW)
+    }
+}
+
+fn versions(field: &StateField) -> HashMap<StateId, VersionVector> {
+    field
+        .atoms
+        .iter()
+        .map(|(&id, atom)| (id, atom.version.clone()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use elara_core::{CounterMerge, StateType};
+
+    const SECOND: Duration = Duration::from_secs(1);
+
+    fn at(secs: u64) -> StateTime {
+        StateTime::ZERO + Duration::from_secs(secs)
+    }
+
+    /// Bump `state` in `field` as a write from `writer`
+    fn write(field: &mut StateField, state: StateId, law: DeltaLaw, writer: NodeId) {
+        if !field.contains(state) {
+            field.create_atom(state, StateType::Core, writer).delta_law = law;
+        }
+        field.get_mut(state).unwrap().version.increment(writer);
+    }
+
+    #[test]
+    fn test_partition_detected_and_merge_classified() {
+        let (us, ally, cut) = (NodeId::new(1), NodeId::new(2), NodeId::new(3));
+        let (counter, title, doc, later) = (
+            StateId::new(1),
+            StateId::new(2),
+            StateId::new(3),
+            StateId::new(4),
+        );
+        let sum = DeltaLaw::Counter {
+            merge: CounterMerge::Sum,
+        };
+        let mut detector = PartitionDetector::new(2 * SECOND, SECOND);
+        let mut network = NetworkModel::new();
+        let mut field = StateField::new();
+        for atom in [counter, title, doc] {
+            write(&mut field, atom, DeltaLaw::LastWriterWins, us);
+        }
+        field.get_mut(counter).unwrap().delta_law = sum.clone();
+
+        network.update_from_packet(ally, 0.0, 0.0, 0);
+        network.update_from_packet(cut, 0.0, 0.0, 0);
+        assert!(detector.check(&network, &field, at(0)).is_empty());
+        assert!(!detector.is_partitioned());
+
+        // Only `cut` goes quiet; our side keeps writing
+        network.update_from_packet(ally, 3.0, 3.0, 1);
+        detector.check(&network, &field, at(3));
+        assert_eq!(detector.partitioned(), vec![cut]);
+        write(&mut field, counter, sum.clone(), ally);
+        write(&mut field, title, DeltaLaw::LastWriterWins, ally);
+
+        // `cut` returns with what it wrote meanwhile
+        network.update_from_packet(ally, 5.0, 5.0, 2);
+        network.update_from_packet(cut, 5.0, 5.0, 1);
+        assert!(detector.check(&network, &field, at(5)).is_empty());
+        assert!(!detector.is_partitioned());
+        write(&mut field, counter, sum, cut);
+        write(&mut field, title, DeltaLaw::LastWriterWins, cut);
+        write(&mut field, doc, DeltaLaw::LastWriterWins, cut);
+        write(&mut field, later, DeltaLaw::LastWriterWins, cut);
+
+        network.update_from_packet(ally, 6.0, 6.0, 3);
+        network.update_from_packet(cut, 6.0, 6.0, 2);
+        let reports = detector.check(&network, &field, at(6));
+        assert_eq!(reports.len(), 1);
+        let report = &reports[0];
+        assert_eq!(report.peers, vec![cut]);
+        assert_eq!((report.partitioned_at, report.healed_at), (at(3), at(5)));
+        assert_eq!(
+            report.atoms,
+            vec![
+                (counter, MergeOutcome::ConcurrentMerged),
+                (title, MergeOutcome::Conflicted),
+                (doc, MergeOutcome::FastForward),
+                (later, MergeOutcome::FastForward),
+            ]
+        );
+        assert_eq!(report.count(MergeOutcome::FastForward), 2);
+    }
+
+    #[test]
+    fn test_relayed_progress_is_not_a_partition() {
+        let (us, relayed) = (NodeId::new(1), NodeId::new(2));
+        let mut detector = PartitionDetector::new(2 * SECOND, SECOND);
+        let mut network = NetworkModel::new();
+        let mut field = StateField::new();
+        network.update_from_packet(relayed, 0.0, 0.0, 0);
+        detector.check(&network, &field, at(0));
+
+        // Never heard directly again, but its writes keep arriving
+        for secs in 1..=6 {
+            write(
+                &mut field,
+                StateId::new(1),
+                DeltaLaw::LastWriterWins,
+                relayed,
+            );
+            detector.check(&network, &field, at(secs));
+        }
+        assert!(!detector.is_partitioned());
+
+        write(&mut field, StateId::new(1), DeltaLaw::LastWriterWins, us);
+        detector.check(&network, &field, at(9));
+        assert_eq!(detector.partitioned(), vec![relayed]);
+        detector.forget(relayed);
+        assert!(!detector.is_partitioned());
+    }
+}
@@ -0,0 +1,261 @@
+//! Reconciliation policies - who may create atoms and how they merge
+//!
+//! The engine asks an [`AuthorityPolicy`] whether an event may create or
+//! mutate its target, and the [`MergePolicy`] registered for the target's
+//! `StateId::state_type()` how a new atom is shaped and how events merge
+//! into it. Profiles bring their own policies instead of patching the
+//! engine.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+use elara_core::{
+    AuthorityScope, DeltaLaw, Event, EventType, MutationOp, NodeId, RejectReason, StateAtom,
+    StateBounds, StateId, StateType,
+};
+
+use crate::merge_mutation;
+
+/// Decides which events may create and mutate atoms
+pub trait AuthorityPolicy: Send + Sync {
+    /// Check whether `event` may create its target atom, which does not exist yet
+    fn authorize_create(&self, event: &Event) -> Result<(), RejectReason>;
+
+    /// Scope `event` needs over its existing target atom
+    fn required_scope(&self, event: &Event) -> AuthorityScope {
+        match event.mutation {
+            MutationOp::Append(_) => AuthorityScope::Append,
+            _ => AuthorityScope::Full,
+        }
+    }
+}
+
+/// Any source may create an atom, and becomes its owner
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SourceOwnsPolicy;
+
+impl AuthorityPolicy for SourceOwnsPolicy {
+    fn authorize_create(&self, _event: &Event) -> Result<(), RejectReason> {
+        Ok(())
+    }
+}
+
+/// Only session admins may create atoms
+///
+/// Existing atoms are still governed by their own authority sets, so an
+/// admin can create state on behalf of a peer and delegate it.
+#[derive(Clone, Debug, Default)]
+pub struct AdminCreatePolicy {
+    admins: HashSet<NodeId>,
+}
+
+impl AdminCreatePolicy {
+    pub fn new(admins: impl IntoIterator<Item = NodeId>) -> Self {
+        AdminCreatePolicy {
+            admins: admins.into_iter().collect(),
+        }
+    }
+
+    /// Grant a node session-admin authority
+    pub fn add_admin(&mut self, admin: NodeId) {
+        self.admins.insert(admin);
+    }
+
+    /// Check if a node is a session admin
+    pub fn is_admin(&self, node: NodeId) -> bool {
+        self.admins.contains(&node)
+    }
+}
+
+impl AuthorityPolicy for AdminCreatePolicy {
+    fn authorize_create(&self, event: &Event) -> Result<(), RejectReason> {
+        if self.is_admin(event.source) {
+            Ok(())
+        } else {
+            Err(RejectReason::Unauthorized)
+        }
+    }
+}
+
+/// How atoms of some state types are created and merged
+pub trait MergePolicy: Send + Sync {
+    /// Shape a new atom for `event`: its state type, delta law and bounds
+    fn init_atom(&self, atom: &mut StateAtom, event: &Event);
+
+    /// Merge `event` into `atom`
+    fn merge(&self, atom: &mut StateAtom, event: &Event) -> Result<(), RejectReason> {
+        merge_mutation(atom, event)
+    }
+}
+
+/// Fallback policy: state type from the event type, last writer wins
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EventTypePolicy;
+
+impl MergePolicy for EventTypePolicy {
+    fn init_atom(&self, atom: &mut StateAtom, event: &Event) {
+        atom.state_type = match event.event_type {
+            EventType::VoiceFrame
+            | EventType::VoiceMute
+            | EventType::PresenceUpdate
+            | EventType::TypingStart
+            | EventType::TypingStop
+            | EventType::VisualKeyframe
+            | EventType::VisualDelta => StateType::Perceptual,
+            _ => StateType::Core,
+        };
+    }
+}
+
+/// Same state type, delta law and bounds for every atom
+#[derive(Clone, Debug)]
+pub struct TemplatePolicy {
+    pub state_type: StateType,
+    pub delta_law: DeltaLaw,
+    pub bounds: StateBounds,
+}
+
+impl TemplatePolicy {
+    pub fn new(state_type: StateType, delta_law: DeltaLaw) -> Self {
+        TemplatePolicy {
+            state_type,
+            delta_law,
+            bounds: StateBounds::default(),
+        }
+    }
+
+    pub fn with_bounds(mut self, bounds: StateBounds) -> Self {
+        self.bounds = bounds;
+        self
+    }
+
+    /// Give `atom` this template's type, law and bounds
+    pub fn apply(&self, atom: &mut StateAtom) {
+        atom.state_type = self.state_type;
+        atom.delta_law = self.delta_law.clone();
+        atom.bounds = self.bounds.clone();
+    }
+}
+
+impl MergePolicy for TemplatePolicy {
+    fn init_atom(&self, atom: &mut StateAtom, _event: &Event) {
+        self.apply(atom);
+    }
+}
+
+/// Merge policies registered by `StateId::state_type()` range
+///
+/// Where ranges overlap, the policy registered last wins. State types no
+/// range covers fall back to [`EventTypePolicy`].
+#[derive(Clone, Default)]
+pub struct MergePolicies {
+    ranges: Vec<(RangeInclusive<u16>, Arc<dyn MergePolicy>)>,
+}
+
+impl MergePolicies {
+    pub fn new() -> Self {
+        MergePolicies::default()
+    }
+
+    /// Use `policy` for every state type in `types`
+    pub fn register(&mut self, types: RangeInclusive<u16>, policy: impl MergePolicy + 'static) {
+        self.ranges.push((types, Arc::new(policy)));
+    }
+
+    /// Policy governing atom `state`
+    pub fn for_state(&self, state: StateId) -> &dyn MergePolicy {
+        let state_type = state.state_type();
+        self.ranges
+            .iter()
+            .rev()
+            .find(|(types, _)| types.contains(&state_type))
+            .map_or(&EventTypePolicy as &dyn MergePolicy, |(_, policy)| {
+                policy.as_ref()
+            })
+    }
+
+    /// Number of registered ranges
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    /// Check if no range is registered
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+}
+
+impl fmt::Debug for MergePolicies {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.ranges.iter().map(|(types, _)| types))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(source: NodeId, state: StateId, event_type: EventType) -> Event {
+        Event::new(source, 1, event_type, state, MutationOp::Set(vec![1]))
+    }
+
+    #[test]
+    fn test_admin_create_policy() {
+        let admin = NodeId::new(1);
+        let policy = AdminCreatePolicy::new([admin]);
+        let state = StateId::new(5);
+
+        assert!(policy
+            .authorize_create(&event(admin, state, EventType::StateCreate))
+            .is_ok());
+        assert_eq!(
+            policy.authorize_create(&event(NodeId::new(2), state, EventType::StateCreate)),
+            Err(RejectReason::Unauthorized)
+        );
+    }
+
+    #[test]
+    fn test_merge_policies_by_state_type_range() {
+        let mut policies = MergePolicies::new();
+        policies.register(
+            0x0100..=0x01FF,
+            TemplatePolicy::new(StateType::Core, DeltaLaw::AppendOnly { max_size: 4 }),
+        );
+        policies.register(
+            0x0180..=0x0180,
+            TemplatePolicy::new(StateType::Cosmetic, DeltaLaw::LastWriterWins),
+        );
+
+        let shaped = |state: StateId, event_type| {
+            let mut atom = StateAtom::new(state, StateType::Core, NodeId::new(1));
+            policies
+                .for_state(state)
+                .init_atom(&mut atom, &event(NodeId::new(1), state, event_type));
+            atom
+        };
+
+        let atom = shaped(
+            StateId::from_type_instance(0x0101, 1),
+            EventType::StateCreate,
+        );
+        assert!(matches!(
+            atom.delta_law,
+            DeltaLaw::AppendOnly { max_size: 4 }
+        ));
+        let atom = shaped(
+            StateId::from_type_instance(0x0180, 1),
+            EventType::StateCreate,
+        );
+        assert_eq!(atom.state_type, StateType::Cosmetic);
+        let atom = shaped(
+            StateId::from_type_instance(0x0200, 1),
+            EventType::VoiceFrame,
+        );
+        assert_eq!(atom.state_type, StateType::Perceptual);
+        assert!(matches!(atom.delta_law, DeltaLaw::LastWriterWins));
+    }
+}
@@ -1,12 +1,32 @@
 //! State reconciliation pipeline
 
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::fmt;
+use std::io;
+use std::sync::Arc;
+use std::time::Duration;
+
 use elara_core::{
-    AuthorityScope, Event, EventResult, EventType, RejectReason, StateAtom, StateTime, StateType,
-    TimePosition,
+    DegradationLevel, DeltaLaw, EntropyModel, Event, EventId, EventResult, EventType, MergeState,
+    MutationOp, NodeId, RejectReason, RepresentationProfile, SessionId, StateAtom, StateId,
+    StateTime, StateType, TimePosition, VersionVector,
 };
 use elara_time::TimeEngine;
 
-use crate::StateField;
+use crate::{
+    AtomChanges, AtomDigest, AtomEvents, AtomProjection, AuthorityPolicy, DegradationChange,
+    DivergenceController, EarlyEventBuffer, EventStore, FieldSnapshot, InfluenceLimiter,
+    KeyDirectory, LogRecord, MergeOutcome, MergePolicies, MergePolicy, MisbehaviourScores,
+    MissingRange, PartitionDetector, PartitionMergeReport, SourceOwnsPolicy, StateDigest,
+    StateField, StateRequest, StoredAtom, DEFAULT_DEGRADATION_LADDER, DEFAULT_MERGE_WINDOW,
+    DEFAULT_PARTITION_TIMEOUT,
+};
+
+/// Default time an event waits in quarantine before its gap is requested
+pub const DEFAULT_QUARANTINE_TTL: Duration = Duration::from_millis(600);
+
+/// Most applied events kept per atom to answer peers' state requests
+pub const MAX_RETAINED_EVENTS: usize = 256;
 
 /// Reconciliation result for a batch of events
 #[derive(Debug, Default)]
@@ -16,24 +36,194 @@ pub struct ReconciliationResult {
     pub late_corrected: u32,
     pub buffered: u32,
     pub rejected: u32,
+    /// Rejections for exceeding an atom's rate limit
+    pub rate_limited: u32,
+    /// Rejections for growing an atom past its `max_size`
+    pub oversized: u32,
+}
+
+impl ReconciliationResult {
+    /// Count one event's outcome
+    pub fn record(&mut self, outcome: &EventResult) {
+        match outcome {
+            EventResult::Applied => self.applied += 1,
+            EventResult::Merged => self.merged += 1,
+            EventResult::LateCorrected => self.late_corrected += 1,
+            EventResult::Buffered => self.buffered += 1,
+            EventResult::Duplicate => {}
+            EventResult::Rejected(reason) => {
+                self.rejected += 1;
+                match reason {
+                    RejectReason::RateLimitExceeded => self.rate_limited += 1,
+                    RejectReason::StateBoundsExceeded => self.oversized += 1,
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    /// Fold another batch's counts into this one
+    pub fn absorb(&mut self, other: ReconciliationResult) {
+        self.applied += other.applied;
+        self.merged += other.merged;
+        self.late_corrected += other.late_corrected;
+        self.buffered += other.buffered;
+        self.rejected += other.rejected;
+        self.rate_limited += other.rate_limited;
+        self.oversized += other.oversized;
+    }
+}
+
+/// Tunables and policies a `ReconciliationEngine` is built with
+#[derive(Clone)]
+pub struct ReconciliationConfig {
+    /// How long quarantined events wait for their dependencies
+    pub quarantine_ttl: Duration,
+    /// Entropy at which each degradation step from L1 to L5 begins
+    pub divergence_ladder: [f64; 5],
+    /// Silence after which a peer is taken for partitioned
+    pub partition_timeout: Duration,
+    /// How long a healed partition's merges are attributed to it
+    pub partition_merge_window: Duration,
+    /// Who may create and mutate atoms
+    pub authority: Arc<dyn AuthorityPolicy>,
+    /// How atoms are shaped and merged, by state type range
+    pub merge_policies: MergePolicies,
+}
+
+impl ReconciliationConfig {
+    /// Replace the authority policy
+    pub fn with_authority(mut self, policy: impl AuthorityPolicy + 'static) -> Self {
+        self.authority = Arc::new(policy);
+        self
+    }
+
+    /// Register a merge policy for a range of state types
+    pub fn with_merge_policy(
+        mut self,
+        types: std::ops::RangeInclusive<u16>,
+        policy: impl MergePolicy + 'static,
+    ) -> Self {
+        self.merge_policies.register(types, policy);
+        self
+    }
+}
+
+impl Default for ReconciliationConfig {
+    fn default() -> Self {
+        ReconciliationConfig {
+            quarantine_ttl: DEFAULT_QUARANTINE_TTL,
+            divergence_ladder: DEFAULT_DEGRADATION_LADDER,
+            partition_timeout: DEFAULT_PARTITION_TIMEOUT,
+            partition_merge_window: DEFAULT_MERGE_WINDOW,
+            authority: Arc::new(SourceOwnsPolicy),
+            merge_policies: MergePolicies::new(),
+        }
+    }
+}
+
+impl fmt::Debug for ReconciliationConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReconciliationConfig")
+            .field("quarantine_ttl", &self.quarantine_ttl)
+            .field("divergence_ladder", &self.divergence_ladder)
+            .field("partition_timeout", &self.partition_timeout)
+            .field("partition_merge_window", &self.partition_merge_window)
+            .field("merge_policies", &self.merge_policies)
+            .finish_non_exhaustive()
+    }
 }
 
 /// State reconciliation engine
 pub struct ReconciliationEngine {
     /// State field
     field: StateField,
-    /// Divergence threshold
-    divergence_threshold: f64,
+    /// Entropy-driven degradation levels
+    divergence: DivergenceController,
+    /// Public keys for event signature verification
+    keys: KeyDirectory,
+    /// Session that delegation links must name, if any
+    session: Option<SessionId>,
+    /// Events waiting for τs to reach them
+    early: EarlyEventBuffer,
+    /// How long quarantined events wait for their dependencies
+    quarantine_ttl: Duration,
+    /// Per-(source, atom) rate limit buckets
+    limiter: InfluenceLimiter,
+    /// Bounds violations per source
+    misbehaviour: MisbehaviourScores,
+    /// Log of applied events, if history is kept
+    history: Option<Box<dyn EventStore>>,
+    /// Latest applied events per atom, replayed to peers that lag behind
+    retained: HashMap<StateId, VecDeque<Event>>,
+    /// Sequence numbers already merged, per atom and source
+    seen: HashMap<(StateId, NodeId), SeenEvents>,
+    /// Partition detection and merge reporting
+    partitions: PartitionDetector,
+    /// Who may create and mutate atoms
+    authority: Arc<dyn AuthorityPolicy>,
+    /// How atoms are shaped and merged
+    merge_policies: MergePolicies,
 }
 
 impl ReconciliationEngine {
     pub fn new() -> Self {
+        Self::with_config(ReconciliationConfig::default())
+    }
+
+    pub fn with_config(config: ReconciliationConfig) -> Self {
         ReconciliationEngine {
             field: StateField::new(),
-            divergence_threshold: 0.5,
+            divergence: DivergenceController::new(config.divergence_ladder),
+            keys: KeyDirectory::new(),
+            session: None,
+            early: EarlyEventBuffer::new(),
+            quarantine_ttl: config.quarantine_ttl,
+            limiter: InfluenceLimiter::new(),
+            misbehaviour: MisbehaviourScores::new(),
+            history: None,
+            retained: HashMap::new(),
+            seen: HashMap::new(),
+            partitions: PartitionDetector::new(
+                config.partition_timeout,
+                config.partition_merge_window,
+            ),
+            authority: config.authority,
+            merge_policies: config.merge_policies,
         }
     }
 
+    /// Get reference to the merge policies
+    pub fn merge_policies(&self) -> &MergePolicies {
+        &self.merge_policies
+    }
+
+    /// Get reference to the peer key directory
+    pub fn keys(&self) -> &KeyDirectory {
+        &self.keys
+    }
+
+    /// Get mutable reference to the peer key directory
+    pub fn keys_mut(&mut self) -> &mut KeyDirectory {
+        &mut self.keys
+    }
+
+    /// Set the session delegation links must be granted in
+    ///
+    /// Outside a session no delegation chain is accepted.
+    pub fn set_session(&mut self, session: Option<SessionId>) {
+        self.session = session;
+    }
+
+    /// Check an event's signature without processing it
+    ///
+    /// Events must be signed for the current session, or for
+    /// `SessionId::ZERO` outside one.
+    pub fn verify_signature(&self, event: &Event) -> Result<(), RejectReason> {
+        let session = self.session.unwrap_or(SessionId::ZERO);
+        self.keys.verify_event(event, session)
+    }
+
     /// Get reference to state field
     pub fn field(&self) -> &StateField {
         &self.field
@@ -44,19 +234,528 @@ impl ReconciliationEngine {
         &mut self.field
     }
 
-    /// Get the number of pending events that haven't been fully reconciled.
+    /// Get reference to the early event buffer
+    pub fn early_buffer(&self) -> &EarlyEventBuffer {
+        &self.early
+    }
+
+    /// Replace the early event buffer, e.g. to change its capacity
+    pub fn set_early_buffer(&mut self, buffer: EarlyEventBuffer) {
+        self.early = buffer;
+    }
+
+    /// Set how long quarantined events wait before their gap is requested
+    pub fn set_quarantine_ttl(&mut self, ttl: Duration) {
+        self.quarantine_ttl = ttl;
+    }
+
+    /// Get the partition detector
+    pub fn partitions(&self) -> &PartitionDetector {
+        &self.partitions
+    }
+
+    /// Get reference to the divergence controller
+    pub fn divergence(&self) -> &DivergenceController {
+        &self.divergence
+    }
+
+    /// Replace the divergence controller, e.g. to change its ladder
+    pub fn set_divergence_controller(&mut self, controller: DivergenceController) {
+        self.divergence = controller;
+    }
+
+    /// Replace the partition detector, e.g. to change its timeouts
+    pub fn set_partition_detector(&mut self, detector: PartitionDetector) {
+        self.partitions = detector;
+    }
+
+    /// Stop tracking a peer that left, so it is not taken for partitioned
+    pub fn forget_peer(&mut self, peer: NodeId) {
+        self.partitions.forget(peer);
+    }
+
+    /// Detect partitions from the time engine's network model
     ///
-    /// This returns a count of events that are buffered or waiting for
-    /// reconciliation. A high pending count may indicate state divergence
-    /// or convergence issues.
+    /// Returns a report for each healed partition whose merge window has
+    /// passed.
+    pub fn check_partitions(&mut self, time_engine: &TimeEngine) -> Vec<PartitionMergeReport> {
+        let now = time_engine.tau_s();
+        let reports = self
+            .partitions
+            .check(time_engine.network(), &self.field, now);
+        for report in &reports {
+            tracing::info!(
+                peers = report.peers.len(),
+                fast_forward = report.count(MergeOutcome::FastForward),
+                concurrent_merged = report.count(MergeOutcome::ConcurrentMerged),
+                conflicted = report.count(MergeOutcome::Conflicted),
+                "Partition merge complete"
+            );
+        }
+        reports
+    }
+
+    /// Get the per-peer misbehaviour scores
+    pub fn misbehaviour(&self) -> &MisbehaviourScores {
+        &self.misbehaviour
+    }
+
+    /// Keep history in `store`, recording everything applied from now on
+    ///
+    /// Call `restore` afterwards to rebuild the field from what the store
+    /// already holds.
+    pub fn set_event_store(&mut self, store: Box<dyn EventStore>) {
+        self.history = Some(store);
+    }
+
+    /// Get the event store, if history is kept
+    pub fn event_store(&self) -> Option<&dyn EventStore> {
+        self.history.as_deref()
+    }
+
+    /// Store a snapshot of the field covering the log so far
+    pub fn save_snapshot(&mut self, now: StateTime) -> io::Result<()> {
+        let Some(store) = self.history.as_mut() else {
+            return Ok(());
+        };
+        let mut atoms: Vec<StoredAtom> = self.field.atoms.values().map(StoredAtom::of).collect();
+        atoms.sort_by_key(|atom| atom.snapshot.state.0);
+        let snapshot = FieldSnapshot {
+            log_len: store.len(),
+            taken_at: now,
+            atoms,
+        };
+        store.save_snapshot(&snapshot)?;
+        tracing::debug!(
+            log_len = snapshot.log_len,
+            atoms = snapshot.atoms.len(),
+            "Saved field snapshot"
+        );
+        Ok(())
+    }
+
+    /// Rebuild the field from the event store's snapshot and log
+    ///
+    /// Logged events were verified and bounded when first applied, so they
+    /// are replayed straight onto the field. Returns the number of log
+    /// records replayed after the snapshot.
+    pub fn restore(&mut self) -> io::Result<usize> {
+        let Some(store) = self.history.take() else {
+            return Ok(0);
+        };
+        let result = self.replay_history(store.as_ref());
+        self.history = Some(store);
+        result
+    }
+
+    fn replay_history(&mut self, store: &dyn EventStore) -> io::Result<usize> {
+        let snapshot = store.load_snapshot()?;
+        let start = snapshot.as_ref().map_or(0, |snapshot| snapshot.log_len);
+        if let Some(snapshot) = snapshot {
+            self.field.atoms = snapshot
+                .atoms
+                .iter()
+                .filter_map(StoredAtom::to_atom)
+                .map(|atom| (atom.id, atom))
+                .collect();
+        }
+
+        let records = store.read_from(start)?;
+        for record in &records {
+            match record {
+                LogRecord::Applied { event, at } => {
+                    if event.event_type == EventType::AuthorityRevoke {
+                        self.apply_revocation(event, *at);
+                    } else if let Err(reason) = self.commit_event(event, *at) {
+                        tracing::warn!(
+                            target_state = event.target_state.0,
+                            reason = ?reason,
+                            "Logged event no longer applies"
+                        );
+                        continue;
+                    }
+                    self.retain(event);
+                }
+            }
+        }
+
+        tracing::info!(
+            atoms = self.field.len(),
+            replayed = records.len(),
+            "State field restored from history"
+        );
+        Ok(records.len())
+    }
+
+    /// Append to the event log, if history is kept
+    fn record(&mut self, record: LogRecord) {
+        let Some(store) = self.history.as_mut() else {
+            return;
+        };
+        if let Err(err) = store.append(&record) {
+            tracing::warn!(error = %err, "Failed to append to event log");
+        }
+    }
+
+    /// Rebuild atom `state` as it stood at state time `at`
+    ///
+    /// Replays the atom's logged history onto a scratch field, starting
+    /// from the stored snapshot when it predates `at`. The live field is
+    /// left untouched.
+    pub fn atom_at(&self, state: StateId, at: StateTime) -> io::Result<AtomProjection> {
+        let (replay, _) = self.replay_atom(state, at)?;
+        Ok(replay.projection(at))
+    }
+
+    /// What happened to atom `state` between state times `from` and `to`
+    pub fn changes_between(
+        &self,
+        state: StateId,
+        from: StateTime,
+        to: StateTime,
+    ) -> io::Result<AtomChanges> {
+        if to < from {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "change range ends before it starts",
+            ));
+        }
+        let (mut replay, later) = self.replay_atom(state, from)?;
+        let before = replay.projection(from);
+        let records: Vec<LogRecord> = later
+            .into_iter()
+            .filter(|record| record.at() <= to)
+            .collect();
+        for record in &records {
+            replay.apply(record);
+        }
+        Ok(AtomChanges {
+            before,
+            after: replay.projection(to),
+            records,
+        })
+    }
+
+    /// Replay atom `state`'s history up to `at`, returning the records after it
+    fn replay_atom(
+        &self,
+        state: StateId,
+        at: StateTime,
+    ) -> io::Result<(AtomReplay, Vec<LogRecord>)> {
+        let Some(store) = self.history.as_deref() else {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "no event history is kept",
+            ));
+        };
+
+        let mut replay = AtomReplay::new(state, self.field.get(state), &self.merge_policies);
+        let mut start = 0;
+        if let Some(snapshot) = store.load_snapshot()? {
+            if snapshot.taken_at <= at {
+                start = snapshot.log_len;
+                let stored = snapshot
+                    .atoms
+                    .iter()
+                    .find(|atom| atom.snapshot.state == state);
+                if let Some(atom) = stored.and_then(StoredAtom::to_atom) {
+                    replay.frontier = atom.version.clone();
+                    replay.engine.field.insert(atom);
+                }
+            }
+        }
+
+        let mut later = Vec::new();
+        for record in store.read_from(start)? {
+            if record.state() != state {
+                continue;
+            }
+            if record.at() <= at {
+                replay.apply(&record);
+            } else {
+                later.push(record);
+            }
+        }
+        Ok((replay, later))
+    }
+
+    /// Get the number of pending events that haven't been fully reconciled.
     ///
-    /// For now, this returns 0 as a placeholder. In a full implementation,
-    /// this would track buffered events, unmerged states, and other
-    /// pending reconciliation work.
+    /// This counts events buffered ahead of the reality window and events
+    /// quarantined on missing dependencies. A high pending count may
+    /// indicate clock skew, loss or convergence issues.
     pub fn pending_count(&self) -> usize {
-        // TODO: Implement actual pending event tracking
-        // This would count buffered events, unmerged atoms, etc.
-        0
+        self.early.len() + self.field.quarantine_size()
+    }
+
+    /// How long the longest-waiting quarantined event has waited
+    pub fn quarantine_age(&self, now: StateTime) -> Option<Duration> {
+        let oldest = self.field.oldest_quarantined()?;
+        Some(Duration::from_micros(
+            now.0.saturating_sub(oldest.0).max(0) as u64
+        ))
+    }
+
+    /// Drop quarantined events that outlived the TTL
+    ///
+    /// Returns one request per affected atom for the clock ranges it is
+    /// still missing, to be sent to peers.
+    pub fn expire_quarantine(&mut self, now: StateTime) -> Vec<StateRequest> {
+        let expired = self.field.expire_quarantine(now, self.quarantine_ttl);
+        if expired.is_empty() {
+            return Vec::new();
+        }
+        tracing::info!(
+            expired = expired.len(),
+            "Quarantined events expired, requesting missing state"
+        );
+
+        let mut ranges: HashMap<StateId, BTreeMap<NodeId, (u64, u64)>> = HashMap::new();
+        for quarantined in expired {
+            let state = quarantined.event.target_state;
+            let atom = self.field.get(state);
+            for (node, clock) in quarantined.missing_deps {
+                let have = atom.map_or(0, |a| a.version.get(node));
+                if have >= clock {
+                    continue;
+                }
+                let range = ranges
+                    .entry(state)
+                    .or_default()
+                    .entry(node)
+                    .or_insert((have + 1, clock));
+                range.1 = range.1.max(clock);
+            }
+        }
+
+        ranges
+            .into_iter()
+            .map(|(state, nodes)| StateRequest {
+                state,
+                missing: nodes
+                    .into_iter()
+                    .map(|(node, (from, to))| MissingRange { node, from, to })
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// Version summary of every Core atom, for anti-entropy digests
+    ///
+    /// Perceptual and lighter state is superseded too quickly to be worth
+    /// repairing.
+    pub fn digest(&self) -> Vec<AtomDigest> {
+        let mut atoms: Vec<AtomDigest> = self
+            .field
+            .iter_by_type(StateType::Core)
+            .map(|atom| AtomDigest {
+                state: atom.id,
+                version: atom.version.clone(),
+            })
+            .collect();
+        atoms.sort_by_key(|atom| atom.state.0);
+        atoms
+    }
+
+    /// Requests for the atoms a peer's digest shows it is ahead on
+    pub fn requests_for(&self, digest: &StateDigest) -> Vec<StateRequest> {
+        digest
+            .atoms
+            .iter()
+            .filter_map(|remote| {
+                let local = self.field.get(remote.state).map(|atom| &atom.version);
+                let mut missing: Vec<MissingRange> = remote
+                    .version
+                    .to_compact()
+                    .into_iter()
+                    .filter_map(|(node, clock)| {
+                        let have = local.map_or(0, |v| v.get(node));
+                        (have < clock).then_some(MissingRange {
+                            node,
+                            from: have + 1,
+                            to: clock,
+                        })
+                    })
+                    .collect();
+                if missing.is_empty() {
+                    return None;
+                }
+                missing.sort_by_key(|range| range.node);
+                Some(StateRequest {
+                    state: remote.state,
+                    missing,
+                })
+            })
+            .collect()
+    }
+
+    /// Retained events answering a request, if this replica holds any
+    ///
+    /// Returns every retained event from a requested node at or past the
+    /// start of its missing range, in the order they were applied here.
+    pub fn answer(&self, request: &StateRequest) -> Option<AtomEvents> {
+        let events: Vec<Event> = self
+            .retained
+            .get(&request.state)?
+            .iter()
+            .filter(|event| {
+                request
+                    .missing
+                    .iter()
+                    .any(|range| range.node == event.source && atom_clock(event) >= range.from)
+            })
+            .cloned()
+            .collect();
+        (!events.is_empty()).then_some(AtomEvents {
+            state: request.state,
+            events,
+        })
+    }
+
+    /// Apply events a peer replayed in answer to our request, then replay
+    /// what they unblock
+    ///
+    /// Each event keeps its author's signature and goes through the same
+    /// signature, authority and causality checks as live events, so a relay
+    /// cannot forge history or grant itself authority. They bypass temporal
+    /// placement because they carry settled history, and the atom's rate
+    /// limit was charged when they first arrived; size bounds still apply.
+    /// Events already seen count as duplicates, and ones whose dependencies
+    /// are still missing are rejected until a later round brings them.
+    pub fn apply_repair(
+        &mut self,
+        repair: AtomEvents,
+        time_engine: &TimeEngine,
+    ) -> ReconciliationResult {
+        let mut result = ReconciliationResult::default();
+        let now = time_engine.tau_s();
+        for event in repair.events {
+            let outcome = self.repair_event(event, now);
+            if let EventResult::Rejected(reason) = &outcome {
+                tracing::warn!(
+                    target_state = repair.state.0,
+                    reason = ?reason,
+                    "Repair event rejected"
+                );
+            }
+            result.record(&outcome);
+        }
+        if result.merged > 0 {
+            tracing::debug!(
+                target_state = repair.state.0,
+                merged = result.merged,
+                "Applied repair events"
+            );
+            self.replay_quarantine(time_engine, &mut result);
+        }
+        result
+    }
+
+    /// Check and apply one replayed event
+    fn repair_event(&mut self, event: Event, now: StateTime) -> EventResult {
+        if self.is_duplicate(&event) {
+            return EventResult::Duplicate;
+        }
+        if let Err(reason) = self.verify_signature(&event) {
+            return EventResult::Rejected(reason);
+        }
+        if let Err(reason) = self.check_authority(&event, now) {
+            return EventResult::Rejected(reason);
+        }
+        if event.event_type == EventType::AuthorityRevoke {
+            return match self.apply_revocation(&event, now) {
+                EventResult::Applied => {
+                    self.retain(&event);
+                    self.record(LogRecord::Applied { event, at: now });
+                    EventResult::Merged
+                }
+                outcome => outcome,
+            };
+        }
+        if let Some(&(node, clock)) = self.missing_dependencies(&event).first() {
+            let missing = EventId::new(node, clock);
+            return EventResult::Rejected(RejectReason::MissingDependency(missing));
+        }
+        if !self.check_causality(&event) {
+            return EventResult::Rejected(RejectReason::CausalityViolation);
+        }
+        match self.commit_event(&event, now) {
+            Ok(()) => {
+                self.retain(&event);
+                self.record(LogRecord::Applied { event, at: now });
+                EventResult::Merged
+            }
+            Err(reason) => {
+                self.misbehaviour.record(event.source, &reason, now);
+                EventResult::Rejected(reason)
+            }
+        }
+    }
+
+    /// Keep an applied event for answering peers' requests
+    ///
+    /// Only the latest `MAX_RETAINED_EVENTS` per atom are kept; a deletion
+    /// drops the atom's history along with it.
+    fn retain(&mut self, event: &Event) {
+        if matches!(event.mutation, MutationOp::Delete) {
+            self.retained.remove(&event.target_state);
+            return;
+        }
+        let events = self.retained.entry(event.target_state).or_default();
+        if events.len() >= MAX_RETAINED_EVENTS {
+            events.pop_front();
+        }
+        events.push_back(event.clone());
+    }
+
+    /// Whether an event with the same source and sequence was merged already
+    fn is_duplicate(&self, event: &Event) -> bool {
+        self.seen
+            .get(&(event.target_state, event.source))
+            .is_some_and(|seen| seen.contains(event.id.seq))
+    }
+
+    /// Remember a merged event, so redelivered copies are dropped
+    fn mark_seen(&mut self, event: &Event) {
+        self.seen
+            .entry((event.target_state, event.source))
+            .or_default()
+            .insert(event.id.seq);
+    }
+
+    /// Rerun quarantined events whose dependencies have arrived
+    fn replay_quarantine(&mut self, time_engine: &TimeEngine, result: &mut ReconciliationResult) {
+        loop {
+            let ready = self.field.release_quarantine();
+            if ready.is_empty() {
+                return;
+            }
+            tracing::debug!(released = ready.len(), "Releasing quarantined events");
+            for quarantined in ready {
+                let outcome =
+                    self.process_at(quarantined.event, quarantined.intended_at, time_engine);
+                result.record(&outcome);
+            }
+        }
+    }
+
+    /// Reprocess buffered events that τs has brought into the reality window
+    ///
+    /// Must be called every tick, before new events are processed.
+    pub fn drain_early(&mut self, time_engine: &TimeEngine) -> ReconciliationResult {
+        let mut result = ReconciliationResult::default();
+        let due = self.early.drain_until(time_engine.reality_window().right());
+        if due.is_empty() {
+            return result;
+        }
+
+        tracing::debug!(released = due.len(), "Releasing buffered early events");
+        for (event, τ_event) in due {
+            let outcome = self.process_at(event, τ_event, time_engine);
+            result.record(&outcome);
+        }
+        self.replay_quarantine(time_engine, &mut result);
+        result
     }
 
     /// Process a batch of events
@@ -74,15 +773,10 @@ impl ReconciliationEngine {
         let mut result = ReconciliationResult::default();
 
         for event in events {
-            match self.process_single_event(event, time_engine) {
-                EventResult::Applied => result.applied += 1,
-                EventResult::Merged => result.merged += 1,
-                EventResult::LateCorrected => result.late_corrected += 1,
-                EventResult::Buffered => result.buffered += 1,
-                EventResult::Duplicate => {}
-                EventResult::Rejected(_) => result.rejected += 1,
-            }
+            let outcome = self.process_single_event(event, time_engine);
+            result.record(&outcome);
         }
+        self.replay_quarantine(time_engine, &mut result);
 
         tracing::info!(
             applied = result.applied,
@@ -98,6 +792,17 @@ impl ReconciliationEngine {
 
     /// Process a single event through the reconciliation pipeline
     fn process_single_event(&mut self, event: Event, time_engine: &TimeEngine) -> EventResult {
+        let τ_event = event.absolute_time(time_engine.tau_s());
+        self.process_at(event, τ_event, time_engine)
+    }
+
+    /// Run the pipeline for an event intended for absolute time `τ_event`
+    fn process_at(
+        &mut self,
+        event: Event,
+        τ_event: StateTime,
+        time_engine: &TimeEngine,
+    ) -> EventResult {
         tracing::debug!(
             source = event.source.0,
             target_state = event.target_state.0,
@@ -105,29 +810,69 @@ impl ReconciliationEngine {
             "Processing event"
         );
 
+        if self.is_duplicate(&event) {
+            tracing::debug!(
+                source = event.source.0,
+                target_state = event.target_state.0,
+                seq = event.id.seq,
+                "Event dropped: already merged"
+            );
+            return EventResult::Duplicate;
+        }
+
+        // Stage 0: Signature Check
+        if let Err(reason) = self.verify_signature(&event) {
+            tracing::warn!(
+                source = event.source.0,
+                target_state = event.target_state.0,
+                reason = ?reason,
+                "Event rejected: signature verification failed"
+            );
+            return EventResult::Rejected(reason);
+        }
+
         // Stage 1: Authority Check
-        if !self.check_authority(&event) {
+        if let Err(reason) = self.check_authority(&event, time_engine.tau_s()) {
             tracing::warn!(
                 source = event.source.0,
                 target_state = event.target_state.0,
+                reason = ?reason,
                 "Event rejected: unauthorized"
             );
-            return EventResult::Rejected(RejectReason::Unauthorized);
+            return EventResult::Rejected(reason);
+        }
+
+        // Revocations change authority, not value, and must not be lost to a
+        // stale version reference
+        if event.event_type == EventType::AuthorityRevoke {
+            let now = time_engine.tau_s();
+            let outcome = self.apply_revocation(&event, now);
+            if matches!(outcome, EventResult::Applied) {
+                self.retain(&event);
+                self.record(LogRecord::Applied { event, at: now });
+            }
+            return outcome;
         }
 
         // Stage 2: Causality Check
+        let missing_deps = self.missing_dependencies(&event);
+        if !missing_deps.is_empty() {
+            tracing::debug!(
+                source = event.source.0,
+                target_state = event.target_state.0,
+                missing = missing_deps.len(),
+                "Event quarantined: missing dependencies"
+            );
+            self.field
+                .quarantine(event, missing_deps, τ_event, time_engine.tau_s());
+            return EventResult::Buffered;
+        }
         if !self.check_causality(&event) {
             tracing::warn!(
                 source = event.source.0,
                 target_state = event.target_state.0,
                 "Event rejected: causality violation"
             );
-            // Quarantine for later
-            self.field.quarantine(
-                vec![], // Would serialize event here
-                vec![event.target_state],
-                time_engine.tau_s(),
-            );
             return EventResult::Rejected(RejectReason::CausalityViolation);
         }
 
@@ -137,13 +882,11 @@ impl ReconciliationEngine {
                 target_state = event.target_state.0,
                 "Applying deletion event"
             );
-            self.apply_event(&event, time_engine.tau_s());
-            return EventResult::Applied;
+            return self.applied(&event, time_engine.tau_s(), EventResult::Applied);
         }
 
-        // Stage 3: Temporal Placement
-        let τ_event = event.absolute_time(time_engine.tau_s());
-        let position = time_engine.classify_time(τ_event);
+        // Stage 3: Temporal Placement, in the source peer's reality window
+        let position = time_engine.classify_time_for(event.source, τ_event);
 
         // Stage 4: Handle based on temporal position
         match position {
@@ -159,50 +902,131 @@ impl ReconciliationEngine {
                     target_state = event.target_state.0,
                     "Applying late correction"
                 );
-                self.apply_late_correction(&event, time_engine);
-                EventResult::LateCorrected
+                self.apply_late_correction(&event, τ_event, time_engine)
             }
             TimePosition::Current => {
                 tracing::debug!(
                     target_state = event.target_state.0,
                     "Applying current event"
                 );
-                self.apply_event(&event, time_engine.tau_s());
-                EventResult::Applied
+                self.applied(&event, time_engine.tau_s(), EventResult::Applied)
             }
             TimePosition::Predictable => {
                 tracing::debug!(
                     target_state = event.target_state.0,
                     "Merging predictable event"
                 );
-                self.replace_prediction(&event, time_engine.tau_s());
-                EventResult::Merged
+                self.replace_prediction(&event, time_engine.tau_s())
             }
             TimePosition::TooEarly => {
                 tracing::debug!(
                     target_state = event.target_state.0,
                     "Buffering early event"
                 );
-                // Buffer for future
-                EventResult::Buffered
+                // Buffer until τs catches up
+                let class = event.event_type.packet_class();
+                if self.early.insert(event, class, τ_event) {
+                    EventResult::Buffered
+                } else {
+                    EventResult::Rejected(RejectReason::OutOfBounds)
+                }
             }
         }
     }
 
     /// Check if event source has authority over target state
-    fn check_authority(&self, event: &Event) -> bool {
-        if let Some(atom) = self.field.get(event.target_state) {
-            atom.authority
-                .has_authority(event.source, &AuthorityScope::Full)
+    ///
+    /// Sources outside the atom's authority set may present a delegation
+    /// chain leading to them from one of its authorities, granted for this
+    /// atom in the current session.
+    fn check_authority(&self, event: &Event, now: StateTime) -> Result<(), RejectReason> {
+        let Some(atom) = self.field.get(event.target_state) else {
+            return self.authority.authorize_create(event);
+        };
+        let required = self.authority.required_scope(event);
+        if atom.authority.has_authority(event.source, &required) {
+            return Ok(());
+        }
+        if atom.authority.is_revoked(&event.source) {
+            return Err(RejectReason::AuthorityRevoked);
+        }
+
+        let Some(chain) = &event.authority_proof.delegation_chain else {
+            return Err(RejectReason::Unauthorized);
+        };
+        let session = self.session.ok_or(RejectReason::Unauthorized)?;
+        let scope = self.keys.verify_delegation_chain(
+            chain,
+            &atom.authority,
+            event.source,
+            event.target_state,
+            session,
+            now,
+        )?;
+        if scope.allows(&required) {
+            Ok(())
         } else {
-            // New state - source becomes owner
-            true
+            Err(RejectReason::Unauthorized)
+        }
+    }
+
+    /// Revoke the node named by an `AuthorityRevoke` event
+    ///
+    /// The mutation carries the revoked `NodeId`. Only owners may revoke
+    /// other owners.
+    fn apply_revocation(&mut self, event: &Event, now: StateTime) -> EventResult {
+        let revoked = match &event.mutation {
+            elara_core::MutationOp::Set(data) => data.as_slice().try_into().ok(),
+            _ => None,
+        };
+        let Some(revoked) = revoked.map(NodeId::from_bytes) else {
+            return EventResult::Rejected(RejectReason::OutOfBounds);
+        };
+        let Some(atom) = self.field.get_mut(event.target_state) else {
+            return EventResult::Rejected(RejectReason::OutOfBounds);
+        };
+        if atom.authority.owners.contains(&revoked)
+            && !atom.authority.owners.contains(&event.source)
+        {
+            return EventResult::Rejected(RejectReason::Unauthorized);
         }
+
+        tracing::info!(
+            source = event.source.0,
+            target_state = event.target_state.0,
+            revoked = revoked.0,
+            "Authority revoked"
+        );
+        atom.authority.revoke(revoked);
+        atom.version = atom.version.merge(&event.version_ref);
+        atom.version.increment(event.source);
+        atom.last_modified = now;
+        self.mark_seen(event);
+        EventResult::Applied
+    }
+
+    /// Version-vector entries the event has seen that the target has not
+    fn missing_dependencies(&self, event: &Event) -> Vec<(NodeId, u64)> {
+        let current = self.field.get(event.target_state).map(|atom| &atom.version);
+        let mut missing: Vec<(NodeId, u64)> = event
+            .version_ref
+            .to_compact()
+            .into_iter()
+            .filter(|&(node, clock)| current.map_or(0, |v| v.get(node)) < clock)
+            .collect();
+        missing.sort();
+        missing
     }
 
     /// Check causality using version vectors
+    ///
+    /// Only last-writer-wins atoms care; the other delta laws merge stale
+    /// and concurrent deltas convergently.
     fn check_causality(&self, event: &Event) -> bool {
         if let Some(atom) = self.field.get(event.target_state) {
+            if !matches!(atom.delta_law, DeltaLaw::LastWriterWins) {
+                return true;
+            }
             // Event's version ref should not be ahead of current version
             !event.version_ref.happens_before(&atom.version)
                 || event.version_ref == atom.version
@@ -213,129 +1037,247 @@ impl ReconciliationEngine {
         }
     }
 
+    /// Apply an event, reporting `ok` on success
+    fn applied(&mut self, event: &Event, now: StateTime, ok: EventResult) -> EventResult {
+        match self.apply_event(event, now) {
+            Ok(()) => {
+                self.retain(event);
+                self.record(LogRecord::Applied {
+                    event: event.clone(),
+                    at: now,
+                });
+                ok
+            }
+            Err(reason) => {
+                tracing::debug!(
+                    target_state = event.target_state.0,
+                    reason = ?reason,
+                    "Event rejected on apply"
+                );
+                self.misbehaviour.record(event.source, &reason, now);
+                EventResult::Rejected(reason)
+            }
+        }
+    }
+
     /// Apply event directly to state
-    fn apply_event(&mut self, event: &Event, now: StateTime) {
+    ///
+    /// The atom's rate limit is charged to the event's source, and the
+    /// mutation is tried on a copy so one that would outgrow `max_size`
+    /// leaves the atom untouched.
+    fn apply_event(&mut self, event: &Event, now: StateTime) -> Result<(), RejectReason> {
+        let limit = self
+            .field
+            .get(event.target_state)
+            .and_then(|atom| atom.bounds.rate_limit.as_ref());
+        if let Some(limit) = limit {
+            if !self
+                .limiter
+                .allow(event.source, event.target_state, limit, now)
+            {
+                return Err(RejectReason::RateLimitExceeded);
+            }
+        }
+        self.commit_event(event, now)
+    }
+
+    /// Merge an event into the field without charging its rate limit
+    fn commit_event(&mut self, event: &Event, now: StateTime) -> Result<(), RejectReason> {
         if matches!(event.mutation, elara_core::MutationOp::Delete) {
             self.field.remove(event.target_state);
-            return;
+            self.limiter.forget(event.target_state);
+            self.seen
+                .retain(|(state, _), _| *state != event.target_state);
+            return Ok(());
         }
 
+        let policy = self.merge_policies.for_state(event.target_state);
         if let Some(atom) = self.field.get_mut(event.target_state) {
+            let mut next = atom.clone();
+            policy.merge(&mut next, event)?;
+            Self::check_size(&next)?;
+            *atom = next;
             atom.version = atom.version.merge(&event.version_ref);
             atom.version.increment(event.source);
             atom.last_modified = now;
-
-            match &event.mutation {
-                elara_core::MutationOp::Set(data) => {
-                    atom.value = data.clone();
-                }
-                elara_core::MutationOp::Append(data) => {
-                    atom.value.extend_from_slice(data);
-                }
-                _ => {}
-            }
-
-            atom.entropy.reset();
         } else {
-            let state_type = Self::state_type_for_event(event.event_type);
-            let mut atom = StateAtom::new(event.target_state, state_type, event.source);
+            // New state - source becomes owner
+            let mut atom = StateAtom::new(event.target_state, StateType::Core, event.source);
+            policy.init_atom(&mut atom, event);
+            policy.merge(&mut atom, event)?;
+            Self::check_size(&atom)?;
             atom.version.increment(event.source);
             atom.last_modified = now;
 
-            match &event.mutation {
-                elara_core::MutationOp::Set(data) => {
-                    atom.value = data.clone();
-                }
-                elara_core::MutationOp::Append(data) => {
-                    atom.value.extend_from_slice(data);
-                }
-                _ => {}
-            }
-
             self.field.insert(atom);
         }
+        self.mark_seen(event);
+        Ok(())
     }
 
-    fn state_type_for_event(event_type: EventType) -> StateType {
-        match event_type {
-            EventType::VoiceFrame
-            | EventType::VoiceMute
-            | EventType::PresenceUpdate
-            | EventType::TypingStart
-            | EventType::TypingStop
-            | EventType::VisualKeyframe
-            | EventType::VisualDelta => StateType::Perceptual,
-            EventType::TextAppend
-            | EventType::TextEdit
-            | EventType::TextDelete
-            | EventType::TextReact
-            | EventType::FeedAppend
-            | EventType::FeedDelete
-            | EventType::StreamStart
-            | EventType::StreamEnd => StateType::Core,
-            _ => StateType::Core,
+    /// Reject an atom whose value has outgrown its bounds
+    fn check_size(atom: &StateAtom) -> Result<(), RejectReason> {
+        if atom.value.len() > atom.bounds.max_size {
+            return Err(RejectReason::StateBoundsExceeded);
         }
+        Ok(())
     }
 
     /// Apply late correction with blending
-    fn apply_late_correction(&mut self, event: &Event, time_engine: &TimeEngine) {
-        let τ_event = event.absolute_time(time_engine.tau_s());
+    fn apply_late_correction(
+        &mut self,
+        event: &Event,
+        τ_event: StateTime,
+        time_engine: &TimeEngine,
+    ) -> EventResult {
         let delay = time_engine.tau_s() - τ_event;
-        let weight = time_engine.correction_weight(delay);
+        let weight = time_engine.correction_weight_for(event.source, delay);
 
         if weight > 0.1 {
             // Apply with reduced weight
-            self.apply_event(event, time_engine.tau_s());
+            return self.applied(event, time_engine.tau_s(), EventResult::LateCorrected);
         }
+        EventResult::LateCorrected
     }
 
     /// Replace prediction with actual data
-    fn replace_prediction(&mut self, event: &Event, now: StateTime) {
-        self.apply_event(event, now);
+    fn replace_prediction(&mut self, event: &Event, now: StateTime) -> EventResult {
+        self.applied(event, now, EventResult::Merged)
     }
 
     /// Control divergence across all atoms
-    pub fn control_divergence(&mut self) {
+    ///
+    /// Reassesses the degradation level of every (peer, profile) group and
+    /// drops enhancement and cosmetic detail from groups at L2 or worse.
+    /// Returns the groups whose level moved, so the caller can degrade the
+    /// media state it owns.
+    pub fn control_divergence(
+        &mut self,
+        profile_of: impl Fn(&StateAtom) -> RepresentationProfile,
+    ) -> Vec<DegradationChange> {
         let atom_count = self.field.atoms.len();
         let mut reduced_count = 0;
 
-        tracing::debug!(
-            atom_count = atom_count,
-            threshold = self.divergence_threshold,
-            "Controlling divergence"
-        );
+        tracing::debug!(atom_count = atom_count, "Controlling divergence");
 
-        for (_, atom) in self.field.atoms.iter_mut() {
-            if atom.entropy.level > self.divergence_threshold {
+        let changes = self.divergence.assess(&self.field, &profile_of);
+        for atom in self.field.atoms.values_mut() {
+            let Some(&owner) = atom.authority.owners.iter().min() else {
+                continue;
+            };
+            let level = self.divergence.level(owner, profile_of(atom));
+            if level.is_worse_than(DegradationLevel::L1_DistortedPerception)
+                && matches!(
+                    atom.state_type,
+                    StateType::Enhancement | StateType::Cosmetic
+                )
+                && !atom.value.is_empty()
+            {
+                // Can drop these entirely
+                atom.value.clear();
                 reduced_count += 1;
-                // Reduce detail based on state type
-                match atom.state_type {
-                    StateType::Enhancement | StateType::Cosmetic => {
-                        // Can drop these entirely
-                        atom.value.clear();
-                    }
-                    StateType::Perceptual => {
-                        // Reduce precision
-                        atom.entropy.increase(0.1);
-                    }
-                    StateType::Core => {
-                        // Keep core, just mark uncertainty
-                        atom.entropy.increase(0.05);
-                    }
-                }
             }
         }
 
-        if reduced_count > 0 {
+        if reduced_count > 0 || !changes.is_empty() {
             tracing::info!(
                 reduced_count = reduced_count,
                 total_atoms = atom_count,
+                level_changes = changes.len(),
+                overall = ?self.divergence.overall(),
                 "Divergence control applied"
             );
         }
+        changes
     }
 }
 
+/// Scratch replay of a single atom's logged history
+struct AtomReplay {
+    engine: ReconciliationEngine,
+    state: StateId,
+    /// Blank copy of the live atom, so replayed merges follow its laws
+    template: Option<StateAtom>,
+    frontier: VersionVector,
+}
+
+impl AtomReplay {
+    fn new(state: StateId, live: Option<&StateAtom>, policies: &MergePolicies) -> Self {
+        let template = live.map(|atom| StateAtom {
+            version: VersionVector::new(),
+            entropy: EntropyModel::new(),
+            value: Vec::new(),
+            merge: MergeState::Plain,
+            ..atom.clone()
+        });
+        let config = ReconciliationConfig {
+            merge_policies: policies.clone(),
+            ..Default::default()
+        };
+        AtomReplay {
+            engine: ReconciliationEngine::with_config(config),
+            state,
+            template,
+            frontier: VersionVector::new(),
+        }
+    }
+
+    fn apply(&mut self, record: &LogRecord) {
+        let LogRecord::Applied { event, at } = record;
+        if !self.engine.field.contains(self.state) {
+            if let Some(template) = &self.template {
+                self.engine.field.insert(template.clone());
+            }
+        }
+        if event.event_type == EventType::AuthorityRevoke {
+            self.engine.apply_revocation(event, *at);
+        } else {
+            let _ = self.engine.commit_event(event, *at);
+        }
+        self.frontier = self.frontier.merge(&event.version_ref);
+        self.frontier.increment(event.source);
+    }
+
+    fn projection(&self, at: StateTime) -> AtomProjection {
+        AtomProjection {
+            state: self.state,
+            at,
+            atom: self.engine.field.get(self.state).cloned(),
+            frontier: self.frontier.clone(),
+        }
+    }
+}
+
+/// Sequence numbers of the events merged from one source into one atom
+#[derive(Debug, Default)]
+struct SeenEvents {
+    /// Every sequence number below this one counts as seen
+    floor: u64,
+    recent: BTreeSet<u64>,
+}
+
+impl SeenEvents {
+    fn contains(&self, seq: u64) -> bool {
+        seq < self.floor || self.recent.contains(&seq)
+    }
+
+    /// Remember `seq`, folding the oldest beyond `MAX_RETAINED_EVENTS`
+    /// into the floor
+    fn insert(&mut self, seq: u64) {
+        self.recent.insert(seq);
+        if self.recent.len() > MAX_RETAINED_EVENTS {
+            if let Some(oldest) = self.recent.pop_first() {
+                self.floor = self.floor.max(oldest.saturating_add(1));
+            }
+        }
+    }
+}
+
+/// The clock value an event gives its source on the target atom
+fn atom_clock(event: &Event) -> u64 {
+    event.version_ref.get(event.source).saturating_add(1)
+}
+
 impl Default for ReconciliationEngine {
     fn default() -> Self {
         Self::new()
@@ -345,20 +1287,44 @@ impl Default for ReconciliationEngine {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use elara_core::{Event, EventType, MutationOp, NodeId, StateId};
+    use elara_core::{
+        AuthorityProof, AuthorityScope, CounterMerge, DelegationLink, Event, EventType, MutationOp,
+        StateId, TimeIntent, VersionVector,
+    };
+    use elara_crypto::Identity;
+
+    use crate::{AdminCreatePolicy, ResponseAssembler, StateResponse, TemplatePolicy};
+
+    /// Engine that trusts `identity`'s key
+    fn engine_trusting(identity: &Identity) -> ReconciliationEngine {
+        let mut engine = ReconciliationEngine::new();
+        engine.keys_mut().insert(identity.public_identity());
+        engine
+    }
+
+    fn signed(identity: &Identity, event: Event) -> Event {
+        signed_in(identity, SessionId::ZERO, event)
+    }
+
+    fn signed_in(identity: &Identity, session: SessionId, mut event: Event) -> Event {
+        event.authority_proof.signature = identity.sign(&event.signing_bytes(session));
+        event
+    }
 
     #[test]
     fn test_reconciliation_basic() {
-        let mut engine = ReconciliationEngine::new();
+        let identity = Identity::generate();
+        let mut engine = engine_trusting(&identity);
         let time_engine = TimeEngine::new();
 
         let event = Event::new(
-            NodeId::new(1),
+            identity.node_id(),
             1,
             EventType::StateCreate,
             StateId::new(100),
             MutationOp::Set(vec![1, 2, 3]),
         );
+        let event = signed(&identity, event);
 
         let result = engine.process_events(vec![event], &time_engine);
         assert_eq!(result.applied, 1);
@@ -367,11 +1333,12 @@ mod tests {
 
     #[test]
     fn test_stream_start_end_creates_and_deletes_state() {
-        let mut engine = ReconciliationEngine::new();
+        let identity = Identity::generate();
+        let mut engine = engine_trusting(&identity);
         let time_engine = TimeEngine::new();
 
         let state_id = StateId::new(200);
-        let source = NodeId::new(7);
+        let source = identity.node_id();
 
         let start_event = Event::new(
             source,
@@ -380,6 +1347,7 @@ mod tests {
             state_id,
             MutationOp::Set(vec![9, 9, 9]),
         );
+        let start_event = signed(&identity, start_event);
 
         let _ = engine.process_events(vec![start_event], &time_engine);
         assert!(engine.field().contains(state_id));
@@ -398,17 +1366,19 @@ mod tests {
             MutationOp::Delete,
         )
         .with_version(current_version);
+        let end_event = signed(&identity, end_event);
         let _ = engine.process_events(vec![end_event], &time_engine);
         assert!(!engine.field().contains(state_id));
     }
 
     #[test]
     fn test_visual_keyframe_creates_perceptual_atom() {
-        let mut engine = ReconciliationEngine::new();
+        let identity = Identity::generate();
+        let mut engine = engine_trusting(&identity);
         let time_engine = TimeEngine::new();
 
         let state_id = StateId::new(300);
-        let source = NodeId::new(8);
+        let source = identity.node_id();
 
         let visual_event = Event::new(
             source,
@@ -417,10 +1387,780 @@ mod tests {
             state_id,
             MutationOp::Set(vec![1, 2, 3, 4]),
         );
+        let visual_event = signed(&identity, visual_event);
 
         let result = engine.process_events(vec![visual_event], &time_engine);
         assert_eq!(result.applied, 1);
         let atom = engine.field().get(state_id).expect("atom exists");
         assert_eq!(atom.state_type, StateType::Perceptual);
     }
+
+    #[test]
+    fn test_unverified_events_rejected() {
+        let identity = Identity::generate();
+        let mut engine = engine_trusting(&identity);
+        let time_engine = TimeEngine::new();
+
+        // Unknown signer
+        let stranger = Identity::generate();
+        let event = Event::new(
+            stranger.node_id(),
+            1,
+            EventType::StateCreate,
+            StateId::new(400),
+            MutationOp::Set(vec![1]),
+        );
+        let result = engine.process_events(vec![signed(&stranger, event)], &time_engine);
+        assert_eq!(result.rejected, 1);
+
+        // Known source, retargeted after signing
+        let event = Event::new(
+            identity.node_id(),
+            1,
+            EventType::StateCreate,
+            StateId::new(401),
+            MutationOp::Set(vec![1]),
+        );
+        let mut event = signed(&identity, event);
+        event.target_state = StateId::new(402);
+        assert!(matches!(
+            engine.verify_signature(&event),
+            Err(RejectReason::InvalidSignature)
+        ));
+        let result = engine.process_events(vec![event], &time_engine);
+        assert_eq!(result.rejected, 1);
+        assert!(engine.field().is_empty());
+    }
+
+    #[test]
+    fn test_events_bound_to_their_session() {
+        let identity = Identity::generate();
+        let time_engine = TimeEngine::new();
+        let event = Event::new(
+            identity.node_id(),
+            1,
+            EventType::StateCreate,
+            StateId::new(403),
+            MutationOp::Set(vec![1]),
+        );
+        let event = signed_in(&identity, SessionId::new(1), event);
+
+        let mut first = engine_trusting(&identity);
+        first.set_session(Some(SessionId::new(1)));
+        let result = first.process_events(vec![event.clone()], &time_engine);
+        assert_eq!(result.applied, 1);
+
+        // The same signed event relayed into another session
+        let mut second = engine_trusting(&identity);
+        second.set_session(Some(SessionId::new(2)));
+        assert!(matches!(
+            second.verify_signature(&event),
+            Err(RejectReason::InvalidSignature)
+        ));
+        let result = second.process_events(vec![event], &time_engine);
+        assert_eq!(result.rejected, 1);
+        assert!(second.field().is_empty());
+    }
+
+    #[test]
+    fn test_configured_authority_and_merge_policies() {
+        let admin = Identity::generate();
+        let member = Identity::generate();
+        let config = ReconciliationConfig::default()
+            .with_authority(AdminCreatePolicy::new([admin.node_id()]))
+            .with_merge_policy(
+                0x0001..=0x0001,
+                TemplatePolicy::new(StateType::Core, DeltaLaw::AppendOnly { max_size: 2 }),
+            );
+        let mut engine = ReconciliationEngine::with_config(config);
+        engine.keys_mut().insert(admin.public_identity());
+        engine.keys_mut().insert(member.public_identity());
+        let time_engine = TimeEngine::new();
+        let log = StateId::from_type_instance(0x0001, 1);
+
+        let event = Event::new(
+            member.node_id(),
+            1,
+            EventType::TextAppend,
+            log,
+            MutationOp::Append(b"hi".to_vec()),
+        );
+        let result = engine.process_events(vec![signed(&member, event)], &time_engine);
+        assert_eq!(result.rejected, 1);
+        assert!(!engine.field().contains(log));
+
+        let event = Event::new(
+            admin.node_id(),
+            1,
+            EventType::TextAppend,
+            log,
+            MutationOp::Append(b"hi".to_vec()),
+        );
+        let result = engine.process_events(vec![signed(&admin, event)], &time_engine);
+        assert_eq!(result.applied, 1);
+        let atom = engine.field().get(log).unwrap();
+        assert!(matches!(
+            atom.delta_law,
+            DeltaLaw::AppendOnly { max_size: 2 }
+        ));
+    }
+
+    #[test]
+    fn test_delegated_authority_and_revocation() {
+        let owner = Identity::generate();
+        let moderator = Identity::generate();
+        let mut engine = engine_trusting(&owner);
+        engine.keys_mut().insert(moderator.public_identity());
+        let session = SessionId::new(5);
+        engine.set_session(Some(session));
+        let time_engine = TimeEngine::new();
+        let (state_id, other_state) = (StateId::new(500), StateId::new(501));
+
+        for state in [state_id, other_state] {
+            let create = Event::new(
+                owner.node_id(),
+                1,
+                EventType::StreamStart,
+                state,
+                MutationOp::Set(vec![1]),
+            );
+            engine.process_events(vec![signed_in(&owner, session, create)], &time_engine);
+        }
+
+        // Without a chain the moderator has no authority
+        let append = |seq, version| {
+            Event::new(
+                moderator.node_id(),
+                seq,
+                EventType::TextAppend,
+                state_id,
+                MutationOp::Append(vec![seq as u8]),
+            )
+            .with_version(version)
+        };
+        let version = engine.field().get(state_id).unwrap().version.clone();
+        let event = signed_in(&moderator, session, append(1, version.clone()));
+        assert_eq!(engine.process_events(vec![event], &time_engine).rejected, 1);
+
+        // Owner grants Append; the moderator presents the link
+        let mut link = DelegationLink::new(
+            owner.node_id(),
+            moderator.node_id(),
+            session,
+            state_id,
+            AuthorityScope::Append,
+            None,
+        );
+        link.signature = owner.sign(&link.signing_bytes());
+        let proof = AuthorityProof::new([0u8; 64]).with_delegation(vec![link]);
+        let event = append(2, version.clone()).with_authority_proof(proof.clone());
+        let result =
+            engine.process_events(vec![signed_in(&moderator, session, event)], &time_engine);
+        assert_eq!(result.applied, 1);
+        assert_eq!(engine.field().get(state_id).unwrap().value, vec![1, 2]);
+
+        // The link grants nothing on the owner's other atoms
+        let mut elsewhere = append(2, version).with_authority_proof(proof.clone());
+        elsewhere.target_state = other_state;
+        let elsewhere = signed_in(&moderator, session, elsewhere);
+        assert_eq!(
+            engine.check_authority(&elsewhere, time_engine.tau_s()),
+            Err(RejectReason::Unauthorized)
+        );
+
+        // Append scope does not cover overwriting the value
+        let version = engine.field().get(state_id).unwrap().version.clone();
+        let overwrite = Event::new(
+            moderator.node_id(),
+            3,
+            EventType::StateUpdate,
+            state_id,
+            MutationOp::Set(vec![9]),
+        )
+        .with_version(version.clone())
+        .with_authority_proof(proof.clone());
+        let result = engine.process_events(
+            vec![signed_in(&moderator, session, overwrite)],
+            &time_engine,
+        );
+        assert_eq!(result.rejected, 1);
+
+        // The owner revokes the moderator
+        let revoke = Event::new(
+            owner.node_id(),
+            2,
+            EventType::AuthorityRevoke,
+            state_id,
+            MutationOp::Set(moderator.node_id().to_bytes().to_vec()),
+        )
+        .with_version(version);
+        let result = engine.process_events(vec![signed_in(&owner, session, revoke)], &time_engine);
+        assert_eq!(result.applied, 1);
+
+        let version = engine.field().get(state_id).unwrap().version.clone();
+        let event = append(4, version).with_authority_proof(proof);
+        let event = signed_in(&moderator, session, event);
+        assert!(matches!(
+            engine.check_authority(&event, time_engine.tau_s()),
+            Err(RejectReason::AuthorityRevoked)
+        ));
+        assert_eq!(engine.process_events(vec![event], &time_engine).rejected, 1);
+    }
+
+    #[test]
+    fn test_counter_converges_across_delivery_orders() {
+        let alice = Identity::generate();
+        let bob = Identity::generate();
+        let time_engine = TimeEngine::new();
+        let state_id = StateId::new(600);
+
+        let replica = || {
+            let mut engine = engine_trusting(&alice);
+            engine.keys_mut().insert(bob.public_identity());
+            let atom = engine
+                .field_mut()
+                .create_atom(state_id, StateType::Core, alice.node_id());
+            atom.authority.add_owner(bob.node_id());
+            atom.delta_law = DeltaLaw::Counter {
+                merge: CounterMerge::Sum,
+            };
+            engine
+        };
+        // Counters need no version reference; each like is told apart by
+        // its source and signed sequence number
+        let like = |identity: &Identity, seq| {
+            let event = Event::new(
+                identity.node_id(),
+                seq,
+                EventType::StateUpdate,
+                state_id,
+                MutationOp::Increment(1),
+            );
+            signed(identity, event)
+        };
+        let events = vec![like(&alice, 1), like(&bob, 1), like(&alice, 2)];
+
+        let mut forward = replica();
+        let result = forward.process_events(events.clone(), &time_engine);
+        assert_eq!(result.applied, 3);
+        let mut backward = replica();
+        backward.process_events(events.iter().cloned().rev().collect(), &time_engine);
+
+        let value =
+            |engine: &ReconciliationEngine| engine.field().get(state_id).unwrap().value.clone();
+        assert_eq!(value(&forward), 3i64.to_le_bytes().to_vec());
+        assert_eq!(value(&forward), value(&backward));
+
+        // Redelivered likes are dropped, not counted again
+        let result = forward.process_events(events, &time_engine);
+        assert_eq!(result.applied, 0);
+        assert_eq!(value(&forward), 3i64.to_le_bytes().to_vec());
+
+        // Mutations the law does not define are rejected, not ignored
+        let set = signed(
+            &alice,
+            Event::new(
+                alice.node_id(),
+                3,
+                EventType::StateUpdate,
+                state_id,
+                MutationOp::Set(vec![0]),
+            ),
+        );
+        assert_eq!(forward.process_events(vec![set], &time_engine).rejected, 1);
+        assert_eq!(value(&forward), 3i64.to_le_bytes().to_vec());
+    }
+
+    #[test]
+    fn test_restart_rebuilds_field_from_history() {
+        let alice = Identity::generate();
+        let bob = Identity::generate();
+        let time_engine = TimeEngine::new();
+        let state_id = StateId::new(605);
+        let dir = std::env::temp_dir().join(format!("elara-restore-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let mut engine = engine_trusting(&alice);
+        engine.keys_mut().insert(bob.public_identity());
+        engine.set_event_store(Box::new(crate::FileEventStore::open(&dir).unwrap()));
+        let atom = engine
+            .field_mut()
+            .create_atom(state_id, StateType::Core, alice.node_id());
+        atom.authority.add_owner(bob.node_id());
+        atom.delta_law = DeltaLaw::Counter {
+            merge: CounterMerge::Sum,
+        };
+        engine.save_snapshot(time_engine.tau_s()).unwrap();
+
+        let like = |identity: &Identity, seq| {
+            let event = Event::new(
+                identity.node_id(),
+                seq,
+                EventType::StateUpdate,
+                state_id,
+                MutationOp::Increment(1),
+            );
+            signed(identity, event)
+        };
+        engine.process_events(vec![like(&alice, 1), like(&bob, 1)], &time_engine);
+        engine.save_snapshot(time_engine.tau_s()).unwrap();
+
+        // Only what follows the latest snapshot is replayed
+        let revoke = Event::new(
+            alice.node_id(),
+            2,
+            EventType::AuthorityRevoke,
+            state_id,
+            MutationOp::Set(bob.node_id().to_bytes().to_vec()),
+        );
+        let events = vec![like(&alice, 3), signed(&alice, revoke)];
+        assert_eq!(engine.process_events(events, &time_engine).applied, 2);
+        let expected = engine.field().get(state_id).unwrap().clone();
+        drop(engine);
+
+        let mut restarted = ReconciliationEngine::new();
+        restarted.set_event_store(Box::new(crate::FileEventStore::open(&dir).unwrap()));
+        assert_eq!(restarted.restore().unwrap(), 2);
+        let atom = restarted.field().get(state_id).unwrap();
+        assert_eq!(atom.value, expected.value);
+        assert_eq!(atom.version, expected.version);
+        assert!(atom.authority.is_revoked(&bob.node_id()));
+
+        // The rebuilt projection answers peers' requests
+        let request = StateRequest {
+            state: state_id,
+            missing: vec![MissingRange {
+                node: alice.node_id(),
+                from: 1,
+                to: 2,
+            }],
+        };
+        assert!(restarted.answer(&request).is_some());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_atom_projection_over_history() {
+        let alice = Identity::generate();
+        let mut time_engine = TimeEngine::new();
+        let state_id = StateId::new(607);
+
+        let mut engine = engine_trusting(&alice);
+        engine.set_event_store(Box::new(crate::MemoryEventStore::new()));
+        engine
+            .field_mut()
+            .create_atom(state_id, StateType::Core, alice.node_id())
+            .delta_law = DeltaLaw::Counter {
+            merge: CounterMerge::Sum,
+        };
+        let mut times = vec![time_engine.tau_s()];
+        let mut version = VersionVector::new();
+        for seq in 1..=3 {
+            let event = Event::new(
+                alice.node_id(),
+                seq,
+                EventType::StateUpdate,
+                state_id,
+                MutationOp::Increment(10),
+            )
+            .with_version(version.clone());
+            engine.process_events(vec![signed(&alice, event)], &time_engine);
+            version.increment(alice.node_id());
+            if seq == 2 {
+                engine.save_snapshot(time_engine.tau_s()).unwrap();
+            }
+            time_engine.tick();
+            times.push(time_engine.tau_s());
+        }
+
+        let at_start = engine.atom_at(state_id, times[0] - Duration::from_millis(1));
+        assert!(at_start.unwrap().atom.is_none());
+
+        // Before the snapshot the log is replayed from the start
+        let first = engine.atom_at(state_id, times[0]).unwrap();
+        assert_eq!(first.atom.unwrap().value, 10i64.to_le_bytes());
+        assert_eq!(first.frontier.get(alice.node_id()), 1);
+
+        let changes = engine
+            .changes_between(state_id, times[0], times[2])
+            .unwrap();
+        assert_eq!(changes.records.len(), 2);
+        assert_eq!(changes.before.frontier.get(alice.node_id()), 1);
+        let after = changes.after.atom.unwrap();
+        assert_eq!(after.value, 30i64.to_le_bytes());
+        assert_eq!(changes.after.frontier, after.version);
+        assert_eq!(
+            after.value,
+            engine.field().get(state_id).unwrap().value,
+            "projection at the latest time matches the live field"
+        );
+
+        // From the snapshot on, only the records after it are replayed
+        let second = engine.atom_at(state_id, times[1]).unwrap();
+        assert_eq!(second.atom.unwrap().value, 20i64.to_le_bytes());
+        assert_eq!(second.frontier.get(alice.node_id()), 2);
+
+        assert!(engine
+            .changes_between(state_id, times[2], times[0])
+            .is_err());
+        assert!(ReconciliationEngine::new()
+            .atom_at(state_id, times[0])
+            .is_err());
+    }
+
+    #[test]
+    fn test_state_bounds_enforced_per_source() {
+        let alice = Identity::generate();
+        let bob = Identity::generate();
+        let time_engine = TimeEngine::new();
+        let (likes, name) = (StateId::new(610), StateId::new(611));
+
+        let mut engine = engine_trusting(&alice);
+        engine.keys_mut().insert(bob.public_identity());
+        let atom = engine
+            .field_mut()
+            .create_atom(likes, StateType::Core, alice.node_id());
+        atom.authority.add_owner(bob.node_id());
+        atom.delta_law = DeltaLaw::Counter {
+            merge: CounterMerge::Sum,
+        };
+        atom.bounds.rate_limit = Some(elara_core::RateLimit::new(2, 1000));
+        engine
+            .field_mut()
+            .create_atom(name, StateType::Core, alice.node_id())
+            .bounds
+            .max_size = 4;
+
+        let event = |identity: &Identity, seq, state, mutation| {
+            let event = Event::new(
+                identity.node_id(),
+                seq,
+                EventType::StateUpdate,
+                state,
+                mutation,
+            );
+            signed(identity, event)
+        };
+
+        // Alice exhausts her bucket; Bob's is separate
+        let events = (1..=3)
+            .map(|seq| event(&alice, seq, likes, MutationOp::Increment(1)))
+            .chain([event(&bob, 1, likes, MutationOp::Increment(1))])
+            .collect();
+        let result = engine.process_events(events, &time_engine);
+        assert_eq!(result.applied, 3);
+        assert_eq!(result.rate_limited, 1);
+        let value = engine.field().get(likes).unwrap().value.clone();
+        assert_eq!(value, 3i64.to_le_bytes().to_vec());
+
+        // An oversized value is rejected and leaves the atom as it was
+        let big = event(&alice, 4, name, MutationOp::Set(b"too long".to_vec()));
+        let result = engine.process_events(vec![big], &time_engine);
+        assert_eq!((result.rejected, result.oversized), (1, 1));
+        assert!(engine.field().get(name).unwrap().value.is_empty());
+
+        let now = time_engine.tau_s();
+        assert_eq!(engine.misbehaviour().score(alice.node_id(), now), 5.0);
+        assert_eq!(engine.misbehaviour().score(bob.node_id(), now), 0.0);
+    }
+
+    #[test]
+    fn test_divergence_drops_cosmetic_detail() {
+        let mut engine = ReconciliationEngine::new();
+        let (peer, other) = (NodeId::new(1), NodeId::new(2));
+        for (id, owner, state_type) in [
+            (1, peer, StateType::Core),
+            (2, peer, StateType::Cosmetic),
+            (3, other, StateType::Cosmetic),
+        ] {
+            let atom = engine
+                .field_mut()
+                .create_atom(StateId::new(id), state_type, owner);
+            atom.value = vec![1, 2, 3];
+            atom.entropy.level = if owner == peer { 0.5 } else { 0.0 };
+        }
+
+        let changes = engine.control_divergence(|_| RepresentationProfile::Textual);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].peer, peer);
+        assert_eq!(changes[0].to, DegradationLevel::L2_FragmentedPerception);
+
+        let field = engine.field();
+        assert!(!field.get(StateId::new(1)).unwrap().value.is_empty());
+        assert!(field.get(StateId::new(2)).unwrap().value.is_empty());
+        assert!(!field.get(StateId::new(3)).unwrap().value.is_empty());
+    }
+
+    #[test]
+    fn test_early_events_buffered_until_due() {
+        let identity = Identity::generate();
+        let mut engine = engine_trusting(&identity);
+        let mut time_engine = TimeEngine::new();
+        let state_id = StateId::new(700);
+
+        // 100ms ahead, beyond the 40ms prediction horizon
+        let event = Event::new(
+            identity.node_id(),
+            1,
+            EventType::StateCreate,
+            state_id,
+            MutationOp::Set(vec![7]),
+        )
+        .with_time_intent(TimeIntent::new(1000));
+        let result = engine.process_events(vec![signed(&identity, event)], &time_engine);
+        assert_eq!(result.buffered, 1);
+        assert_eq!(engine.pending_count(), 1);
+        assert!(!engine.field().contains(state_id));
+
+        for _ in 0..5 {
+            time_engine.tick();
+        }
+        let result = engine.drain_early(&time_engine);
+        assert_eq!(result.applied + result.merged, 0);
+        assert_eq!(engine.pending_count(), 1);
+
+        for _ in 0..5 {
+            time_engine.tick();
+        }
+        let result = engine.drain_early(&time_engine);
+        assert_eq!(result.applied + result.merged, 1);
+        assert_eq!(engine.pending_count(), 0);
+        assert_eq!(engine.field().get(state_id).unwrap().value, vec![7]);
+    }
+
+    #[test]
+    fn test_late_events_classified_in_source_window() {
+        let steady = Identity::generate();
+        let mobile = Identity::generate();
+        let mut engine = engine_trusting(&steady);
+        engine.keys_mut().insert(mobile.public_identity());
+
+        // The mobile peer's timestamps wander by 120ms, the steady one's don't
+        let mut time_engine = TimeEngine::new();
+        for i in 0..10 {
+            time_engine.tick();
+            let sent = time_engine.tau_s() - Duration::from_millis(10);
+            let spike = Duration::from_millis(if i % 2 == 0 { 120 } else { 0 });
+            time_engine.update_from_packet(steady.node_id(), sent, 0);
+            time_engine.update_from_packet(mobile.node_id(), sent - spike, 0);
+        }
+
+        // 150ms late: beyond the steady peer's correction horizon only
+        let late = |identity: &Identity, state: u64| {
+            let event = Event::new(
+                identity.node_id(),
+                1,
+                EventType::StateCreate,
+                StateId::new(state),
+                MutationOp::Set(vec![1]),
+            )
+            .with_time_intent(TimeIntent::new(-1500));
+            signed(identity, event)
+        };
+        let result = engine.process_events(vec![late(&steady, 750)], &time_engine);
+        assert_eq!(result.rejected, 1);
+        let result = engine.process_events(vec![late(&mobile, 751)], &time_engine);
+        assert_eq!(result.late_corrected, 1);
+        assert!(engine.field().contains(StateId::new(751)));
+    }
+
+    #[test]
+    fn test_quarantined_events_replay_when_dependencies_arrive() {
+        let identity = Identity::generate();
+        let mut engine = engine_trusting(&identity);
+        let time_engine = TimeEngine::new();
+        let state_id = StateId::new(800);
+        let source = identity.node_id();
+
+        let create = Event::new(
+            source,
+            1,
+            EventType::StateCreate,
+            state_id,
+            MutationOp::Set(vec![1]),
+        );
+        let mut seen = VersionVector::new();
+        seen.set(source, 1);
+        let update = Event::new(
+            source,
+            2,
+            EventType::StateUpdate,
+            state_id,
+            MutationOp::Set(vec![2]),
+        )
+        .with_version(seen);
+
+        // The update overtakes the creation it depends on
+        let result = engine.process_events(vec![signed(&identity, update)], &time_engine);
+        assert_eq!(result.buffered, 1);
+        assert_eq!(engine.field().quarantine_size(), 1);
+        assert_eq!(engine.pending_count(), 1);
+        assert!(!engine.field().contains(state_id));
+
+        let result = engine.process_events(vec![signed(&identity, create)], &time_engine);
+        assert_eq!(result.applied, 2);
+        assert_eq!(engine.field().quarantine_size(), 0);
+        assert_eq!(engine.field().get(state_id).unwrap().value, vec![2]);
+    }
+
+    #[test]
+    fn test_expired_quarantine_requests_missing_range() {
+        let identity = Identity::generate();
+        let mut engine = engine_trusting(&identity);
+        engine.set_quarantine_ttl(Duration::from_millis(50));
+        let mut time_engine = TimeEngine::new();
+        let state_id = StateId::new(801);
+        let source = identity.node_id();
+
+        let mut seen = VersionVector::new();
+        seen.set(source, 4);
+        let update = Event::new(
+            source,
+            5,
+            EventType::StateUpdate,
+            state_id,
+            MutationOp::Set(vec![5]),
+        )
+        .with_version(seen);
+        engine.process_events(vec![signed(&identity, update)], &time_engine);
+        assert!(engine.expire_quarantine(time_engine.tau_s()).is_empty());
+
+        for _ in 0..10 {
+            time_engine.tick();
+        }
+        assert!(engine.quarantine_age(time_engine.tau_s()).unwrap() >= Duration::from_millis(50));
+        let requests = engine.expire_quarantine(time_engine.tau_s());
+        assert_eq!(
+            requests,
+            vec![StateRequest {
+                state: state_id,
+                missing: vec![MissingRange {
+                    node: source,
+                    from: 1,
+                    to: 4,
+                }],
+            }]
+        );
+        assert_eq!(engine.pending_count(), 0);
+    }
+
+    #[test]
+    fn test_repair_catches_up_lagging_replica() {
+        let identity = Identity::generate();
+        let mut ahead = engine_trusting(&identity);
+        let mut behind = engine_trusting(&identity);
+        let time_engine = TimeEngine::new();
+        let state_id = StateId::new(802);
+        let source = identity.node_id();
+
+        let append = |seq: u64, data: &[u8]| {
+            let mut seen = VersionVector::new();
+            seen.set(source, seq - 1);
+            let event = Event::new(
+                source,
+                seq,
+                EventType::TextAppend,
+                state_id,
+                MutationOp::Append(data.to_vec()),
+            )
+            .with_version(seen);
+            signed(&identity, event)
+        };
+        ahead.process_events(vec![append(1, b"a"), append(2, b"b")], &time_engine);
+        // The lagging replica only sees the third append, which waits on the rest
+        let result = behind.process_events(vec![append(3, b"c")], &time_engine);
+        assert_eq!(result.buffered, 1);
+        let digest = StateDigest {
+            atoms: behind.digest(),
+        };
+        assert!(ahead.requests_for(&digest).is_empty());
+
+        let requests = behind.requests_for(&StateDigest {
+            atoms: ahead.digest(),
+        });
+        assert_eq!(requests.len(), 1);
+        // `ahead` relays history it did not write
+        let repair = ahead.answer(&requests[0]).unwrap();
+        assert_eq!(repair.events.len(), 2);
+
+        let mut assembler = ResponseAssembler::new();
+        let mut assembled = None;
+        for chunk in StateResponse::split(&repair, 16) {
+            assembled = assembler.insert(NodeId::new(99), chunk);
+        }
+        let result = behind.apply_repair(assembled.unwrap(), &time_engine);
+        assert_eq!(result.merged, 2);
+        assert_eq!(result.applied, 1);
+        assert_eq!(behind.field().get(state_id).unwrap().value, b"abc".to_vec());
+        assert_eq!(behind.pending_count(), 0);
+
+        // Replaying the same events again changes nothing, live or repaired
+        let result = behind.apply_repair(repair, &time_engine);
+        assert_eq!(result.merged, 0);
+        assert_eq!(result.rejected, 0);
+        let result = behind.process_events(vec![append(1, b"a")], &time_engine);
+        assert_eq!(result.applied, 0);
+        assert_eq!(result.rejected, 0);
+        assert_eq!(behind.field().get(state_id).unwrap().value, b"abc".to_vec());
+    }
+
+    #[test]
+    fn test_repair_events_checked_like_live_ones() {
+        let owner = Identity::generate();
+        let stranger = Identity::generate();
+        let mut engine = engine_trusting(&owner);
+        engine.keys_mut().insert(stranger.public_identity());
+        let time_engine = TimeEngine::new();
+        let state_id = StateId::new(803);
+
+        let create = Event::new(
+            owner.node_id(),
+            1,
+            EventType::StateCreate,
+            state_id,
+            MutationOp::Set(vec![1]),
+        );
+        engine.process_events(vec![signed(&owner, create)], &time_engine);
+        let mut seen = VersionVector::new();
+        seen.set(owner.node_id(), 1);
+        let set = |identity: &Identity, value: Vec<u8>| {
+            let event = Event::new(
+                identity.node_id(),
+                2,
+                EventType::StateUpdate,
+                state_id,
+                MutationOp::Set(value),
+            )
+            .with_version(seen.clone());
+            signed(identity, event)
+        };
+        let repair = |events| AtomEvents {
+            state: state_id,
+            events,
+        };
+
+        // A relay cannot rewrite a signed event
+        let mut forged = set(&owner, vec![2]);
+        forged.mutation = MutationOp::Set(vec![9]);
+        let result = engine.apply_repair(repair(vec![forged]), &time_engine);
+        assert_eq!(result.rejected, 1);
+        // Nor pass off a stranger's event as authorized
+        let result = engine.apply_repair(repair(vec![set(&stranger, vec![9])]), &time_engine);
+        assert_eq!(result.rejected, 1);
+        assert_eq!(engine.field().get(state_id).unwrap().value, vec![1]);
+
+        // Even the owner cannot grow an atom past its bounds
+        let max_size = engine.field().get(state_id).unwrap().bounds.max_size;
+        let result = engine.apply_repair(
+            repair(vec![set(&owner, vec![0; max_size + 1])]),
+            &time_engine,
+        );
+        assert_eq!(result.oversized, 1);
+        assert_eq!(engine.field().get(state_id).unwrap().value, vec![1]);
+
+        let result = engine.apply_repair(repair(vec![set(&owner, vec![2])]), &time_engine);
+        assert_eq!(result.merged, 1);
+        assert_eq!(engine.field().get(state_id).unwrap().value, vec![2]);
+    }
 }
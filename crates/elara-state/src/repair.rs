@@ -0,0 +1,824 @@
+//! Repair messages - anti-entropy between replicas
+//!
+//! Replicas periodically exchange digests of their atoms' version vectors
+//! (`SessionSync`). A replica that finds a peer ahead on an atom requests
+//! the missing clock ranges (`StateRequest`), and peers holding them answer
+//! with the signed events that produced them, split into chunks
+//! (`StateResponse`). The events are checked like any other, so a peer can
+//! relay history it did not write but cannot forge it.
+
+use std::collections::{BTreeMap, HashMap};
+
+use elara_core::{
+    BlendSample, CounterMerge, CounterSlot, DeltaLaw, Event, EventType, InterpolationType,
+    MergeState, MutationOp, NodeId, Sibling, StateAtom, StateId, StateType, VersionVector,
+};
+
+use crate::history::{decode_event, encode_event};
+
+/// Target state of `SessionSync` events, which describe the whole field
+pub const SYNC_STATE: StateId = StateId::ZERO;
+
+/// Most partially received responses kept at once
+pub const MAX_PENDING_RESPONSES: usize = 64;
+
+/// A run of one node's clock values on a state atom, inclusive
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MissingRange {
+    pub node: NodeId,
+    pub from: u64,
+    pub to: u64,
+}
+
+/// Request for the history a replica is missing on one atom
+///
+/// Travels as the `Set` payload of an `EventType::StateRequest` event
+/// targeting the atom.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StateRequest {
+    pub state: StateId,
+    pub missing: Vec<MissingRange>,
+}
+
+impl StateRequest {
+    /// Size of one encoded range: node, from, to
+    const RANGE_SIZE: usize = 8 + 8 + 8;
+
+    /// Encode the ranges for an event payload
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(2 + self.missing.len() * Self::RANGE_SIZE);
+        buf.extend_from_slice(&(self.missing.len() as u16).to_le_bytes());
+        for range in &self.missing {
+            buf.extend_from_slice(&range.node.to_bytes());
+            buf.extend_from_slice(&range.from.to_le_bytes());
+            buf.extend_from_slice(&range.to.to_le_bytes());
+        }
+        buf
+    }
+
+    /// Decode the ranges of a request for `state`
+    pub fn decode(state: StateId, buf: &[u8]) -> Option<Self> {
+        let count = u16::from_le_bytes(buf.get(0..2)?.try_into().ok()?) as usize;
+        if buf.len() != 2 + count * Self::RANGE_SIZE {
+            return None;
+        }
+        let missing = buf[2..]
+            .chunks_exact(Self::RANGE_SIZE)
+            .map(|chunk| MissingRange {
+                node: NodeId::from_bytes(chunk[0..8].try_into().unwrap()),
+                from: u64::from_le_bytes(chunk[8..16].try_into().unwrap()),
+                to: u64::from_le_bytes(chunk[16..24].try_into().unwrap()),
+            })
+            .collect();
+        Some(StateRequest { state, missing })
+    }
+
+    /// Wrap the request in an unsigned event from `source`
+    pub fn to_event(&self, source: NodeId, seq: u64) -> Event {
+        Event::new(
+            source,
+            seq,
+            EventType::StateRequest,
+            self.state,
+            MutationOp::Set(self.encode()),
+        )
+    }
+
+    /// Extract a request from a `StateRequest` event
+    pub fn from_event(event: &Event) -> Option<Self> {
+        if event.event_type != EventType::StateRequest {
+            return None;
+        }
+        match &event.mutation {
+            MutationOp::Set(data) => Self::decode(event.target_state, data),
+            _ => None,
+        }
+    }
+}
+
+/// Version vector summary of one atom
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AtomDigest {
+    pub state: StateId,
+    pub version: VersionVector,
+}
+
+/// Summary of (part of) a replica's field, carried by `SessionSync`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StateDigest {
+    pub atoms: Vec<AtomDigest>,
+}
+
+impl StateDigest {
+    /// Split atom summaries into digests whose encoding fits `max_bytes`
+    ///
+    /// An atom too large for `max_bytes` still gets a digest of its own.
+    pub fn split(atoms: Vec<AtomDigest>, max_bytes: usize) -> Vec<StateDigest> {
+        let mut digests = Vec::new();
+        let mut current = StateDigest::default();
+        let mut size = 2;
+        for atom in atoms {
+            let atom_size = 8 + version_size(&atom.version);
+            if !current.atoms.is_empty() && size + atom_size > max_bytes {
+                digests.push(std::mem::take(&mut current));
+                size = 2;
+            }
+            size += atom_size;
+            current.atoms.push(atom);
+        }
+        if !current.atoms.is_empty() {
+            digests.push(current);
+        }
+        digests
+    }
+
+    /// Encode for an event payload
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&(self.atoms.len() as u16).to_le_bytes());
+        for atom in &self.atoms {
+            buf.extend_from_slice(&atom.state.to_bytes());
+            encode_version(&mut buf, &atom.version);
+        }
+        buf
+    }
+
+    /// Decode a digest payload
+    pub fn decode(buf: &[u8]) -> Option<Self> {
+        let mut reader = Reader::new(buf);
+        let count = reader.u16()?;
+        let atoms = (0..count)
+            .map(|_| {
+                Some(AtomDigest {
+                    state: StateId::from_bytes(reader.array()?),
+                    version: reader.version()?,
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+        reader.finish()?;
+        Some(StateDigest { atoms })
+    }
+
+    /// Wrap the digest in an unsigned `SessionSync` event from `source`
+    pub fn to_event(&self, source: NodeId, seq: u64) -> Event {
+        Event::new(
+            source,
+            seq,
+            EventType::SessionSync,
+            SYNC_STATE,
+            MutationOp::Set(self.encode()),
+        )
+    }
+
+    /// Extract a digest from a `SessionSync` event
+    pub fn from_event(event: &Event) -> Option<Self> {
+        if event.event_type != EventType::SessionSync {
+            return None;
+        }
+        match &event.mutation {
+            MutationOp::Set(data) => Self::decode(data),
+            _ => None,
+        }
+    }
+}
+
+/// Everything needed to rebuild an atom, such as from a field snapshot
+#[derive(Clone, Debug)]
+pub struct AtomSnapshot {
+    pub state: StateId,
+    pub state_type: StateType,
+    pub delta_law: DeltaLaw,
+    pub owners: Vec<NodeId>,
+    pub version: VersionVector,
+    pub value: Vec<u8>,
+    pub merge: MergeState,
+}
+
+impl AtomSnapshot {
+    /// Snapshot an atom
+    pub fn of(atom: &StateAtom) -> Self {
+        let mut owners: Vec<NodeId> = atom.authority.owners.iter().copied().collect();
+        owners.sort();
+        AtomSnapshot {
+            state: atom.id,
+            state_type: atom.state_type,
+            delta_law: atom.delta_law.clone(),
+            owners,
+            version: atom.version.clone(),
+            value: atom.value.clone(),
+            merge: atom.merge.clone(),
+        }
+    }
+
+    /// Build a new atom from the snapshot
+    pub fn to_atom(&self) -> Option<StateAtom> {
+        let (first, rest) = self.owners.split_first()?;
+        let mut atom = StateAtom::new(self.state, self.state_type, *first);
+        for owner in rest {
+            atom.authority.add_owner(*owner);
+        }
+        atom.delta_law = self.delta_law.clone();
+        atom.version = self.version.clone();
+        atom.value = self.value.clone();
+        atom.merge = self.merge.clone();
+        Some(atom)
+    }
+
+    /// Encode for transfer
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&self.state.to_bytes());
+        buf.push(state_type_to_byte(self.state_type));
+        encode_law(&mut buf, &self.delta_law);
+        buf.extend_from_slice(&(self.owners.len() as u16).to_le_bytes());
+        for owner in &self.owners {
+            buf.extend_from_slice(&owner.to_bytes());
+        }
+        encode_version(&mut buf, &self.version);
+        encode_bytes(&mut buf, &self.value);
+        encode_merge(&mut buf, &self.merge);
+        buf
+    }
+
+    /// Decode a snapshot
+    pub fn decode(buf: &[u8]) -> Option<Self> {
+        let mut reader = Reader::new(buf);
+        let state = StateId::from_bytes(reader.array()?);
+        let state_type = state_type_from_byte(reader.u8()?)?;
+        let delta_law = reader.law()?;
+        let owners = (0..reader.u16()?)
+            .map(|_| reader.array().map(NodeId::from_bytes))
+            .collect::<Option<Vec<_>>>()?;
+        let version = reader.version()?;
+        let value = reader.bytes()?;
+        let merge = reader.merge()?;
+        reader.finish()?;
+        Some(AtomSnapshot {
+            state,
+            state_type,
+            delta_law,
+            owners,
+            version,
+            value,
+            merge,
+        })
+    }
+}
+
+/// Signed events from an atom's history, in the order they were applied
+#[derive(Clone, Debug)]
+pub struct AtomEvents {
+    pub state: StateId,
+    pub events: Vec<Event>,
+}
+
+impl AtomEvents {
+    /// Encode for transfer, keeping each event's source and signature
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&self.state.to_bytes());
+        buf.extend_from_slice(&(self.events.len() as u32).to_le_bytes());
+        for event in &self.events {
+            encode_event(&mut buf, event);
+        }
+        buf
+    }
+
+    /// Decode events, all of which must target the same atom
+    pub fn decode(buf: &[u8]) -> Option<Self> {
+        let mut reader = Reader::new(buf);
+        let state = StateId::from_bytes(reader.array()?);
+        let events = (0..reader.u32()?)
+            .map(|_| decode_event(&mut reader).filter(|event| event.target_state == state))
+            .collect::<Option<Vec<_>>>()?;
+        reader.finish()?;
+        Some(AtomEvents { state, events })
+    }
+}
+
+/// One chunk of encoded events answering a `StateRequest`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StateResponse {
+    pub state: StateId,
+    pub index: u16,
+    pub count: u16,
+    pub data: Vec<u8>,
+}
+
+impl StateResponse {
+    /// Split events into chunks of at most `max_chunk` bytes
+    pub fn split(events: &AtomEvents, max_chunk: usize) -> Vec<StateResponse> {
+        let encoded = events.encode();
+        let chunks: Vec<&[u8]> = encoded.chunks(max_chunk.max(1)).collect();
+        let count = chunks.len() as u16;
+        chunks
+            .into_iter()
+            .enumerate()
+            .map(|(index, data)| StateResponse {
+                state: events.state,
+                index: index as u16,
+                count,
+                data: data.to_vec(),
+            })
+            .collect()
+    }
+
+    /// Wrap the chunk in an unsigned event from `source`
+    pub fn to_event(&self, source: NodeId, seq: u64) -> Event {
+        let mut buf = Vec::with_capacity(4 + self.data.len());
+        buf.extend_from_slice(&self.index.to_le_bytes());
+        buf.extend_from_slice(&self.count.to_le_bytes());
+        buf.extend_from_slice(&self.data);
+        Event::new(
+            source,
+            seq,
+            EventType::StateResponse,
+            self.state,
+            MutationOp::Set(buf),
+        )
+    }
+
+    /// Extract a chunk from a `StateResponse` event
+    pub fn from_event(event: &Event) -> Option<Self> {
+        if event.event_type != EventType::StateResponse {
+            return None;
+        }
+        let MutationOp::Set(data) = &event.mutation else {
+            return None;
+        };
+        let mut reader = Reader::new(data);
+        let index = reader.u16()?;
+        let count = reader.u16()?;
+        if index >= count {
+            return None;
+        }
+        Some(StateResponse {
+            state: event.target_state,
+            index,
+            count,
+            data: data[4..].to_vec(),
+        })
+    }
+}
+
+/// Chunks of one response received so far
+#[derive(Debug)]
+struct PartialResponse {
+    chunks: Vec<Option<Vec<u8>>>,
+    received: usize,
+    /// Order the first chunk arrived in, for eviction
+    started: u64,
+}
+
+/// Reassembles chunked responses per sender and atom
+#[derive(Debug, Default)]
+pub struct ResponseAssembler {
+    pending: HashMap<(NodeId, StateId), PartialResponse>,
+    started: u64,
+}
+
+impl ResponseAssembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a chunk from `source`, returning the events once complete
+    ///
+    /// A chunk whose count disagrees with earlier ones restarts the
+    /// response. Beyond `MAX_PENDING_RESPONSES` the oldest partial
+    /// response is dropped.
+    pub fn insert(&mut self, source: NodeId, response: StateResponse) -> Option<AtomEvents> {
+        if response.index >= response.count {
+            return None;
+        }
+        let key = (source, response.state);
+        let count = response.count as usize;
+        if self
+            .pending
+            .get(&key)
+            .is_some_and(|partial| partial.chunks.len() != count)
+        {
+            self.pending.remove(&key);
+        }
+        if !self.pending.contains_key(&key) && self.pending.len() >= MAX_PENDING_RESPONSES {
+            let oldest = self
+                .pending
+                .iter()
+                .min_by_key(|(_, partial)| partial.started)
+                .map(|(key, _)| *key);
+            if let Some(oldest) = oldest {
+                self.pending.remove(&oldest);
+            }
+        }
+
+        self.started += 1;
+        let started = self.started;
+        let partial = self.pending.entry(key).or_insert_with(|| PartialResponse {
+            chunks: vec![None; count],
+            received: 0,
+            started,
+        });
+        let slot = &mut partial.chunks[response.index as usize];
+        if slot.is_none() {
+            *slot = Some(response.data);
+            partial.received += 1;
+        }
+        if partial.received < count {
+            return None;
+        }
+
+        let partial = self.pending.remove(&key)?;
+        let encoded: Vec<u8> = partial.chunks.into_iter().flatten().flatten().collect();
+        AtomEvents::decode(&encoded)
+    }
+
+    /// Number of responses partially received
+    pub fn pending_count(&self) -> usize {
+        self.pending.len()
+    }
+}
+
+fn version_size(version: &VersionVector) -> usize {
+    2 + version.to_compact().len() * 16
+}
+
+pub(crate) fn encode_version(buf: &mut Vec<u8>, version: &VersionVector) {
+    let mut entries = version.to_compact();
+    entries.sort();
+    buf.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+    for (node, clock) in entries {
+        buf.extend_from_slice(&node.to_bytes());
+        buf.extend_from_slice(&clock.to_le_bytes());
+    }
+}
+
+pub(crate) fn encode_bytes(buf: &mut Vec<u8>, data: &[u8]) {
+    buf.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    buf.extend_from_slice(data);
+}
+
+fn state_type_to_byte(state_type: StateType) -> u8 {
+    match state_type {
+        StateType::Core => 0,
+        StateType::Perceptual => 1,
+        StateType::Enhancement => 2,
+        StateType::Cosmetic => 3,
+    }
+}
+
+fn state_type_from_byte(b: u8) -> Option<StateType> {
+    match b {
+        0 => Some(StateType::Core),
+        1 => Some(StateType::Perceptual),
+        2 => Some(StateType::Enhancement),
+        3 => Some(StateType::Cosmetic),
+        _ => None,
+    }
+}
+
+fn encode_law(buf: &mut Vec<u8>, law: &DeltaLaw) {
+    match law {
+        DeltaLaw::LastWriterWins => buf.push(0),
+        DeltaLaw::AppendOnly { max_size } => {
+            buf.push(1);
+            buf.extend_from_slice(&(*max_size as u32).to_le_bytes());
+        }
+        DeltaLaw::Counter { merge } => {
+            buf.push(2);
+            buf.push(match merge {
+                CounterMerge::Max => 0,
+                CounterMerge::Sum => 1,
+                CounterMerge::Average => 2,
+            });
+        }
+        DeltaLaw::MultiValueRegister => buf.push(3),
+        DeltaLaw::ContinuousBlend {
+            interpolation,
+            max_deviation,
+        } => {
+            buf.push(4);
+            buf.push(match interpolation {
+                InterpolationType::Linear => 0,
+                InterpolationType::Cubic => 1,
+                InterpolationType::Catmull => 2,
+            });
+            buf.extend_from_slice(&max_deviation.to_le_bytes());
+        }
+    }
+}
+
+fn encode_merge(buf: &mut Vec<u8>, merge: &MergeState) {
+    match merge {
+        MergeState::Plain => buf.push(0),
+        MergeState::Log(entries) => {
+            buf.push(1);
+            buf.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+            for ((seq, source), data) in entries {
+                buf.extend_from_slice(&seq.to_le_bytes());
+                buf.extend_from_slice(&source.to_bytes());
+                encode_bytes(buf, data);
+            }
+        }
+        MergeState::Counter(slots) => {
+            buf.push(2);
+            buf.extend_from_slice(&(slots.len() as u32).to_le_bytes());
+            for (source, slot) in slots {
+                buf.extend_from_slice(&source.to_bytes());
+                buf.extend_from_slice(&slot.total.to_le_bytes());
+                buf.extend_from_slice(&slot.seq.to_le_bytes());
+            }
+        }
+        MergeState::Siblings(siblings) => {
+            buf.push(3);
+            buf.extend_from_slice(&(siblings.len() as u32).to_le_bytes());
+            for sibling in siblings {
+                buf.extend_from_slice(&sibling.source.to_bytes());
+                encode_version(buf, &sibling.version);
+                encode_bytes(buf, &sibling.value);
+            }
+        }
+        MergeState::Blend(samples) => {
+            buf.push(4);
+            buf.extend_from_slice(&(samples.len() as u32).to_le_bytes());
+            for (source, sample) in samples {
+                buf.extend_from_slice(&source.to_bytes());
+                buf.extend_from_slice(&sample.seq.to_le_bytes());
+                buf.extend_from_slice(&sample.weight.to_le_bytes());
+                buf.extend_from_slice(&(sample.components.len() as u32).to_le_bytes());
+                for component in &sample.components {
+                    buf.extend_from_slice(&component.to_le_bytes());
+                }
+            }
+        }
+    }
+}
+
+/// Bounds-checked cursor over an encoded repair or history payload
+pub(crate) struct Reader<'a> {
+    buf: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(buf: &'a [u8]) -> Self {
+        Reader { buf, offset: 0 }
+    }
+
+    pub(crate) fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let end = self.offset.checked_add(len)?;
+        let bytes = self.buf.get(self.offset..end)?;
+        self.offset = end;
+        Some(bytes)
+    }
+
+    pub(crate) fn array<const N: usize>(&mut self) -> Option<[u8; N]> {
+        self.take(N)?.try_into().ok()
+    }
+
+    pub(crate) fn u8(&mut self) -> Option<u8> {
+        Some(self.take(1)?[0])
+    }
+
+    pub(crate) fn u16(&mut self) -> Option<u16> {
+        self.array().map(u16::from_le_bytes)
+    }
+
+    pub(crate) fn u32(&mut self) -> Option<u32> {
+        self.array().map(u32::from_le_bytes)
+    }
+
+    pub(crate) fn u64(&mut self) -> Option<u64> {
+        self.array().map(u64::from_le_bytes)
+    }
+
+    /// Run a decoder that reports how many bytes it consumed
+    pub(crate) fn decoded<T>(
+        &mut self,
+        decode: impl FnOnce(&'a [u8]) -> Option<(T, usize)>,
+    ) -> Option<T> {
+        let (value, used) = decode(self.buf.get(self.offset..)?)?;
+        self.take(used)?;
+        Some(value)
+    }
+
+    pub(crate) fn node(&mut self) -> Option<NodeId> {
+        self.array().map(NodeId::from_bytes)
+    }
+
+    pub(crate) fn bytes(&mut self) -> Option<Vec<u8>> {
+        let len = self.u32()? as usize;
+        self.take(len).map(<[u8]>::to_vec)
+    }
+
+    pub(crate) fn version(&mut self) -> Option<VersionVector> {
+        let entries = (0..self.u16()?)
+            .map(|_| Some((self.node()?, self.u64()?)))
+            .collect::<Option<Vec<_>>>()?;
+        Some(VersionVector::from_compact(entries))
+    }
+
+    pub(crate) fn law(&mut self) -> Option<DeltaLaw> {
+        Some(match self.u8()? {
+            0 => DeltaLaw::LastWriterWins,
+            1 => DeltaLaw::AppendOnly {
+                max_size: self.u32()? as usize,
+            },
+            2 => DeltaLaw::Counter {
+                merge: match self.u8()? {
+                    0 => CounterMerge::Max,
+                    1 => CounterMerge::Sum,
+                    2 => CounterMerge::Average,
+                    _ => return None,
+                },
+            },
+            3 => DeltaLaw::MultiValueRegister,
+            4 => DeltaLaw::ContinuousBlend {
+                interpolation: match self.u8()? {
+                    0 => InterpolationType::Linear,
+                    1 => InterpolationType::Cubic,
+                    2 => InterpolationType::Catmull,
+                    _ => return None,
+                },
+                max_deviation: f64::from_le_bytes(self.array()?),
+            },
+            _ => return None,
+        })
+    }
+
+    pub(crate) fn merge(&mut self) -> Option<MergeState> {
+        let tag = self.u8()?;
+        if tag == 0 {
+            return Some(MergeState::Plain);
+        }
+        let count = self.u32()?;
+        Some(match tag {
+            1 => {
+                let mut entries = BTreeMap::new();
+                for _ in 0..count {
+                    let key = (self.u64()?, self.node()?);
+                    entries.insert(key, self.bytes()?);
+                }
+                MergeState::Log(entries)
+            }
+            2 => {
+                let mut slots = BTreeMap::new();
+                for _ in 0..count {
+                    let source = self.node()?;
+                    let total = i64::from_le_bytes(self.array()?);
+                    slots.insert(
+                        source,
+                        CounterSlot {
+                            total,
+                            seq: self.u64()?,
+                        },
+                    );
+                }
+                MergeState::Counter(slots)
+            }
+            3 => MergeState::Siblings(
+                (0..count)
+                    .map(|_| {
+                        Some(Sibling {
+                            source: self.node()?,
+                            version: self.version()?,
+                            value: self.bytes()?,
+                        })
+                    })
+                    .collect::<Option<Vec<_>>>()?,
+            ),
+            4 => {
+                let mut samples = BTreeMap::new();
+                for _ in 0..count {
+                    let source = self.node()?;
+                    let seq = self.u64()?;
+                    let weight = f32::from_le_bytes(self.array()?);
+                    let components = (0..self.u32()?)
+                        .map(|_| self.array().map(f32::from_le_bytes))
+                        .collect::<Option<Vec<_>>>()?;
+                    samples.insert(
+                        source,
+                        BlendSample {
+                            seq,
+                            components,
+                            weight,
+                        },
+                    );
+                }
+                MergeState::Blend(samples)
+            }
+            _ => return None,
+        })
+    }
+
+    /// Succeed only if every byte was consumed
+    pub(crate) fn finish(&self) -> Option<()> {
+        (self.offset == self.buf.len()).then_some(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use elara_core::SessionId;
+
+    #[test]
+    fn test_state_request_roundtrip() {
+        let request = StateRequest {
+            state: StateId::new(7),
+            missing: vec![
+                MissingRange {
+                    node: NodeId::new(1),
+                    from: 3,
+                    to: 5,
+                },
+                MissingRange {
+                    node: NodeId::new(2),
+                    from: 1,
+                    to: 1,
+                },
+            ],
+        };
+        let event = request.to_event(NodeId::new(9), 1);
+        assert_eq!(StateRequest::from_event(&event), Some(request.clone()));
+
+        let mut truncated = request.encode();
+        truncated.pop();
+        assert!(StateRequest::decode(request.state, &truncated).is_none());
+    }
+
+    #[test]
+    fn test_digest_split_and_roundtrip() {
+        let atoms: Vec<AtomDigest> = (1..=10)
+            .map(|i| {
+                let mut version = VersionVector::new();
+                version.set(NodeId::new(i), i);
+                AtomDigest {
+                    state: StateId::new(i),
+                    version,
+                }
+            })
+            .collect();
+        // Each atom takes 8 + 2 + 16 bytes
+        let digests = StateDigest::split(atoms.clone(), 2 + 3 * 26);
+        assert_eq!(digests.len(), 4);
+        assert!(digests.iter().all(|d| d.encode().len() <= 2 + 3 * 26));
+
+        let event = digests[0].to_event(NodeId::new(9), 1);
+        assert_eq!(StateDigest::from_event(&event).as_ref(), Some(&digests[0]));
+        let rejoined: Vec<AtomDigest> = digests.into_iter().flat_map(|d| d.atoms).collect();
+        assert_eq!(rejoined, atoms);
+    }
+
+    #[test]
+    fn test_response_chunks_reassemble() {
+        let state = StateId::new(3);
+        let events: Vec<Event> = (1..=20u64)
+            .map(|seq| {
+                let mut seen = VersionVector::new();
+                seen.set(NodeId::new(1), seq - 1);
+                Event::new(
+                    NodeId::new(1),
+                    seq,
+                    EventType::TextAppend,
+                    state,
+                    MutationOp::Append(vec![seq as u8; 5]),
+                )
+                .with_version(seen)
+            })
+            .collect();
+        let history = AtomEvents { state, events };
+
+        let chunks = StateResponse::split(&history, 64);
+        assert!(chunks.len() > 1);
+        let mut assembler = ResponseAssembler::new();
+        let source = NodeId::new(7);
+        let mut rebuilt = None;
+        // Out of order and duplicated chunks are fine
+        for chunk in chunks.iter().rev().chain(chunks.iter()) {
+            let event = chunk.to_event(source, 1);
+            if let Some(events) =
+                assembler.insert(source, StateResponse::from_event(&event).unwrap())
+            {
+                rebuilt = Some(events);
+                break;
+            }
+        }
+        let rebuilt = rebuilt.unwrap();
+        assert_eq!(rebuilt.encode(), history.encode());
+        assert_eq!(assembler.pending_count(), 0);
+        // Relayed events keep their author
+        assert_eq!(rebuilt.events[4].source, NodeId::new(1));
+        assert_eq!(
+            rebuilt.events[4].signing_bytes(SessionId::ZERO),
+            history.events[4].signing_bytes(SessionId::ZERO)
+        );
+
+        let mut truncated = history.encode();
+        truncated.pop();
+        assert!(AtomEvents::decode(&truncated).is_none());
+        // Every event must target the atom the response is for
+        let mut stray = history.clone();
+        stray.events[0].target_state = StateId::new(4);
+        assert!(AtomEvents::decode(&stray.encode()).is_none());
+    }
+}
@@ -1,6 +1,6 @@
 //! Network simulator for ELARA protocol testing
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::time::Duration;
 
 use elara_core::NodeId;
@@ -27,6 +27,8 @@ pub struct NetworkSimulator {
     current_time: Duration,
     /// RNG seed counter
     seed_counter: u64,
+    /// Links cut by a partition (keyed by (from, to))
+    partitioned: HashSet<(NodeId, NodeId)>,
 }
 
 impl NetworkSimulator {
@@ -37,6 +39,7 @@ impl NetworkSimulator {
             default_config,
             current_time: Duration::ZERO,
             seed_counter: 0,
+            partitioned: HashSet::new(),
         }
     }
 
@@ -66,7 +69,12 @@ impl NetworkSimulator {
     }
 
     /// Send a packet from one node to another
+    ///
+    /// Packets across a partition are lost.
     pub fn send(&mut self, from: NodeId, to: NodeId, data: Vec<u8>) {
+        if self.is_partitioned(from, to) {
+            return;
+        }
         let link = self.get_or_create_link(from, to);
         link.send(data);
     }
@@ -87,6 +95,28 @@ impl NetworkSimulator {
         delivered
     }
 
+    /// Cut every link between the two groups, in both directions
+    ///
+    /// Packets already in flight are still delivered.
+    pub fn partition(&mut self, a: &[NodeId], b: &[NodeId]) {
+        for &x in a {
+            for &y in b {
+                self.partitioned.insert((x, y));
+                self.partitioned.insert((y, x));
+            }
+        }
+    }
+
+    /// Restore every link cut by a partition
+    pub fn heal(&mut self) {
+        self.partitioned.clear();
+    }
+
+    /// Check if packets from one node to another are being cut
+    pub fn is_partitioned(&self, from: NodeId, to: NodeId) -> bool {
+        self.partitioned.contains(&(from, to))
+    }
+
     /// Get current simulation time
     pub fn current_time(&self) -> Duration {
         self.current_time
@@ -178,6 +208,9 @@ impl Default for ScenarioBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use elara_core::{Event, EventType, MutationOp, SessionId, StateId, VersionVector};
+    use elara_runtime::{Node, NodeConfig};
+    use elara_wire::Frame;
 
     #[test]
     fn test_network_simulator_basic() {
@@ -231,4 +264,137 @@ mod tests {
         let stats = sim.all_stats();
         assert!(!stats.is_empty());
     }
+
+    /// Tick every node, then carry their frames across the network
+    fn step(sim: &mut NetworkSimulator, nodes: &mut [Node]) {
+        for node in nodes.iter_mut() {
+            node.tick();
+        }
+        let ids: Vec<NodeId> = nodes.iter().map(|n| n.node_id()).collect();
+        for node in nodes.iter_mut() {
+            while let Some(frame) = node.pop_outgoing() {
+                let bytes = frame.serialize().unwrap();
+                for &to in ids.iter().filter(|&&id| id != node.node_id()) {
+                    sim.send(node.node_id(), to, bytes.clone());
+                }
+            }
+        }
+        for (_, to, data) in sim.tick(Duration::from_millis(10)) {
+            let node = nodes.iter_mut().find(|n| n.node_id() == to).unwrap();
+            node.queue_incoming(Frame::parse(&data).unwrap());
+        }
+    }
+
+    /// Append to a text atom the writer has written `written` times before
+    fn append(node: &mut Node, state: StateId, written: u64, text: &[u8]) {
+        let mut version = VersionVector::new();
+        version.set(node.node_id(), written);
+        let seq = node.next_event_seq();
+        let event = Event::new(
+            node.node_id(),
+            seq,
+            EventType::TextAppend,
+            state,
+            MutationOp::Append(text.to_vec()),
+        )
+        .with_version(version);
+        node.queue_local_event(event);
+    }
+
+    fn value(node: &Node, state: StateId) -> Option<Vec<u8>> {
+        let atom = node.state_engine().field().get(state)?;
+        Some(atom.value.clone())
+    }
+
+    #[test]
+    fn test_partitioned_nodes_catch_up_after_heal() {
+        let config = ChaosConfig {
+            loss_rate: 0.0,
+            burst_loss_prob: 0.0,
+            ..ChaosConfig::good()
+        };
+        let mut sim = NetworkSimulator::new(config);
+        let node_config = NodeConfig {
+            sync_interval: Some(Duration::ZERO),
+            ..Default::default()
+        };
+        let mut nodes: Vec<Node> = (0..4)
+            .map(|_| Node::with_config(node_config.clone()))
+            .collect();
+        let identities: Vec<_> = nodes.iter().map(|n| n.public_identity()).collect();
+        for node in &mut nodes {
+            node.join_session_unsecured(SessionId::new(7));
+            for identity in &identities {
+                node.add_peer_identity(identity.clone());
+            }
+        }
+        let ids: Vec<NodeId> = nodes.iter().map(|n| n.node_id()).collect();
+
+        // Each side of the partition writes history the other never hears
+        sim.partition(&ids[..2], &ids[2..]);
+        let (left, right) = (StateId::new(100), StateId::new(200));
+        for i in 0..3 {
+            append(&mut nodes[0], left, i, format!("L{i}").as_bytes());
+            append(&mut nodes[2], right, i, format!("R{i}").as_bytes());
+            for _ in 0..5 {
+                step(&mut sim, &mut nodes);
+            }
+        }
+        assert_eq!(value(&nodes[1], left).unwrap(), b"L0L1L2");
+        assert_eq!(value(&nodes[3], right).unwrap(), b"R0R1R2");
+        assert!(value(&nodes[2], left).is_none());
+        assert!(value(&nodes[1], right).is_none());
+
+        sim.heal();
+        for _ in 0..30 {
+            step(&mut sim, &mut nodes);
+        }
+        for node in &nodes {
+            assert_eq!(value(node, left).unwrap(), b"L0L1L2");
+            assert_eq!(value(node, right).unwrap(), b"R0R1R2");
+        }
+        assert!(nodes[2].stats().repairs_applied > 0);
+        assert!(nodes[1].stats().repairs_applied > 0);
+    }
+
+    #[test]
+    fn test_repair_traffic_respects_bandwidth() {
+        let mut sim = NetworkSimulator::new(ChaosConfig {
+            loss_rate: 0.0,
+            burst_loss_prob: 0.0,
+            ..ChaosConfig::good()
+        });
+        let node_config = NodeConfig {
+            sync_interval: Some(Duration::ZERO),
+            repair_bandwidth: 0,
+            ..Default::default()
+        };
+        let mut nodes: Vec<Node> = (0..3)
+            .map(|_| Node::with_config(node_config.clone()))
+            .collect();
+        let identities: Vec<_> = nodes.iter().map(|n| n.public_identity()).collect();
+        for node in &mut nodes {
+            node.join_session_unsecured(SessionId::new(8));
+            for identity in &identities {
+                node.add_peer_identity(identity.clone());
+            }
+        }
+        let ids: Vec<NodeId> = nodes.iter().map(|n| n.node_id()).collect();
+
+        sim.partition(&ids[..2], &ids[2..]);
+        let state = StateId::new(300);
+        append(&mut nodes[0], state, 0, b"x");
+        for _ in 0..10 {
+            step(&mut sim, &mut nodes);
+        }
+        sim.heal();
+        for _ in 0..20 {
+            step(&mut sim, &mut nodes);
+        }
+
+        // Without repair bandwidth the partitioned node never catches up
+        assert!(value(&nodes[2], state).is_none());
+        assert_eq!(nodes[1].stats().state_digests_sent, 0);
+        assert!(nodes[1].stats().repair_events_throttled > 0);
+    }
 }
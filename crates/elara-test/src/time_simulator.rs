@@ -4,17 +4,18 @@
 //! - Multiple nodes with independent clocks
 //! - Clock drift and skew
 //! - Network-induced timing variations
+//! - TimeSync probing over delayed, lossy links
 //! - Reality window behavior under stress
 
 use std::collections::HashMap;
 use std::time::Duration;
 
 use elara_core::{NodeId, PerceptualTime, RealityWindow, StateTime, TimePosition};
-use elara_time::{TimeEngine, TimeEngineConfig};
+use elara_time::{ManualClock, TimeEngine, TimeEngineConfig, TimeSyncProbe, TimeSyncReply};
 use rand::rngs::StdRng;
 use rand::{Rng, SeedableRng};
 
-use crate::chaos::{ChaosConfig, ChaosNetwork};
+use crate::chaos::{ChaosConfig, ChaosNetwork, JitterDistribution};
 
 /// Clock drift model for a simulated node
 #[derive(Clone, Debug)]
@@ -84,6 +85,8 @@ pub struct SimulatedNode {
     pub time_engine: TimeEngine,
     /// Clock drift model
     pub drift_model: ClockDriftModel,
+    /// Virtual clock behind τp, advanced by drifted local time
+    clock: ManualClock,
     /// Local RNG
     rng: StdRng,
     /// Tick count
@@ -97,10 +100,12 @@ impl SimulatedNode {
         drift: ClockDriftModel,
         seed: u64,
     ) -> Self {
+        let clock = ManualClock::new();
         SimulatedNode {
             node_id,
-            time_engine: TimeEngine::with_config(config),
+            time_engine: TimeEngine::with_clock_source(config, clock.clone()),
             drift_model: drift,
+            clock,
             rng: StdRng::seed_from_u64(seed),
             tick_count: 0,
         }
@@ -109,9 +114,9 @@ impl SimulatedNode {
     /// Advance the node's time by one tick
     pub fn tick(&mut self, real_dt: Duration) {
         // Apply drift to get local perception of time
-        let _local_dt = self.drift_model.apply(real_dt, &mut self.rng);
+        let local_dt = self.drift_model.apply(real_dt, &mut self.rng);
+        self.clock.advance(local_dt);
 
-        // Advance time engine (it uses its own internal tick)
         self.time_engine.tick();
         self.tick_count += 1;
     }
@@ -154,6 +159,8 @@ pub struct TimeSimulator {
     tick_interval: Duration,
     /// RNG seed counter
     seed_counter: u64,
+    /// Global time each linked node sends its next probe
+    next_probe: HashMap<NodeId, Duration>,
 }
 
 impl TimeSimulator {
@@ -165,6 +172,7 @@ impl TimeSimulator {
             global_time: Duration::ZERO,
             tick_interval,
             seed_counter: 0,
+            next_probe: HashMap::new(),
         }
     }
 
@@ -216,15 +224,19 @@ impl TimeSimulator {
 
         // Simulate time sync messages between nodes
         self.simulate_time_sync();
+        self.exchange_probes();
     }
 
     /// Simulate time synchronization between nodes
+    ///
+    /// Nodes joined by a network link run the `TimeSync` exchange over it;
+    /// other pairs read each other's state time directly.
     fn simulate_time_sync(&mut self) {
         let node_ids: Vec<NodeId> = self.nodes.keys().copied().collect();
 
         for from in &node_ids {
             for to in &node_ids {
-                if from == to {
+                if from == to || self.networks.contains_key(&(*from, *to)) {
                     continue;
                 }
 
@@ -245,6 +257,66 @@ impl TimeSimulator {
         }
     }
 
+    /// Deliver probes and replies due on each link, then send due probes
+    ///
+    /// Messages take the link's latency, so asymmetric links skew the
+    /// offset each prober measures by half their difference.
+    fn exchange_probes(&mut self) {
+        let mut links: Vec<(NodeId, NodeId)> = self.networks.keys().copied().collect();
+        links.sort();
+
+        for &(from, to) in &links {
+            let delivered = match self.networks.get_mut(&(from, to)) {
+                Some(link) => link.tick(self.tick_interval),
+                None => continue,
+            };
+            for message in delivered {
+                match message.split_first() {
+                    Some((&PROBE, payload)) => {
+                        let Some(probe) = TimeSyncProbe::decode(payload) else {
+                            continue;
+                        };
+                        let Some(node) = self.nodes.get(&to) else {
+                            continue;
+                        };
+                        let reply = node.time_engine.answer_probe(from, &probe);
+                        if let Some(link) = self.networks.get_mut(&(to, from)) {
+                            link.send([&[REPLY][..], &reply.encode()].concat());
+                        }
+                    }
+                    Some((&REPLY, payload)) => {
+                        let Some(reply) = TimeSyncReply::decode(payload) else {
+                            continue;
+                        };
+                        if let Some(node) = self.nodes.get_mut(&to) {
+                            node.time_engine.update_from_time_sync(from, &reply);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let mut probers: Vec<NodeId> = links.iter().map(|&(from, _)| from).collect();
+        probers.dedup();
+        for from in probers {
+            let due = self.next_probe.get(&from).copied().unwrap_or_default();
+            let Some(node) = self.nodes.get(&from).filter(|_| due <= self.global_time) else {
+                continue;
+            };
+            let probe = TimeSyncProbe {
+                origin: node.time_engine.probe_origin(),
+            };
+            let next = self.global_time + node.time_engine.time_sync_interval();
+            self.next_probe.insert(from, next);
+            for &(_, to) in links.iter().filter(|&&(source, _)| source == from) {
+                if let Some(link) = self.networks.get_mut(&(from, to)) {
+                    link.send([&[PROBE][..], &probe.encode()].concat());
+                }
+            }
+        }
+    }
+
     /// Get a node
     pub fn node(&self, id: NodeId) -> Option<&SimulatedNode> {
         self.nodes.get(&id)
@@ -266,6 +338,10 @@ impl TimeSimulator {
     }
 }
 
+/// Link message tags for the `TimeSync` exchange
+const PROBE: u8 = 0;
+const REPLY: u8 = 1;
+
 /// Simulation result and statistics
 #[derive(Debug, Default)]
 pub struct SimulationResult {
@@ -363,6 +439,36 @@ pub mod scenarios {
         sim
     }
 
+    /// Two perfect clocks `offset` apart, over links of fixed but unequal delay
+    pub fn asymmetric_pair(offset: Duration, out: Duration, back: Duration) -> TimeSimulator {
+        let mut sim = TimeSimulator::new(Duration::from_millis(10));
+        let node1 = NodeId::new(1);
+        let node2 = NodeId::new(2);
+        sim.add_node(node1);
+        sim.add_node(node2);
+        if let Some(node) = sim.node_mut(node2) {
+            let ahead = StateTime::ZERO.saturating_add(offset);
+            node.time_engine.state_clock_mut().sync_to(ahead);
+        }
+
+        let fixed = |latency| ChaosConfig {
+            base_latency: latency,
+            jitter: JitterDistribution::Uniform {
+                min_ms: 0,
+                max_ms: 1,
+            },
+            loss_rate: 0.0,
+            burst_loss_prob: 0.0,
+            burst_length: (0, 0),
+            reorder_prob: 0.0,
+            reorder_depth: 0,
+            duplicate_prob: 0.0,
+        };
+        sim.set_network(node1, node2, fixed(out));
+        sim.set_network(node2, node1, fixed(back));
+        sim
+    }
+
     /// Hostile network scenario
     pub fn hostile_network() -> TimeSimulator {
         let mut sim = TimeSimulator::new(Duration::from_millis(10));
@@ -424,6 +530,54 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_time_sync_converges_under_asymmetric_delay() {
+        let out = Duration::from_millis(20);
+        let back = Duration::from_millis(80);
+        let mut sim = scenarios::asymmetric_pair(Duration::from_millis(500), out, back);
+        let mut result = sim.run(Duration::from_secs(30));
+        result.finalize();
+
+        let (node1, node2) = (
+            sim.node(NodeId::new(1)).unwrap(),
+            sim.node(NodeId::new(2)).unwrap(),
+        );
+        let divergence = (node1.tau_s().as_micros() - node2.tau_s().as_micros()).abs();
+        println!(
+            "Asymmetric delay - Final divergence: {:.3}ms, Avg: {:.3}ms",
+            divergence as f64 / 1000.0,
+            result.avg_divergence_ms()
+        );
+        // Probes cannot see asymmetry, so half of it (30ms) remains, give or
+        // take delivery rounding to the tick
+        assert!(result.max_divergence_ms() >= 500.0);
+        assert!(divergence <= 40_000);
+
+        // Round trips are measured despite the offset
+        let peer = node1
+            .time_engine
+            .network()
+            .get_peer(NodeId::new(2))
+            .unwrap();
+        assert!(peer.is_probed());
+        assert!((peer.rtt - (out + back).as_secs_f64()).abs() < 0.03);
+        let latency = node2.time_engine.network().latency_mean;
+        assert!((latency - 0.05).abs() < 0.015);
+    }
+
+    #[test]
+    fn test_perceptual_time_follows_drifted_clock() {
+        let mut sim = TimeSimulator::new(Duration::from_millis(10));
+        sim.add_node(NodeId::new(1));
+        sim.add_node_with_drift(NodeId::new(2), ClockDriftModel::new(1.5, 0));
+        sim.run(Duration::from_secs(10));
+
+        // τp runs on simulated time, not on how long the run took
+        let tau_p = |id| sim.node(NodeId::new(id)).unwrap().tau_p();
+        assert_eq!(tau_p(1), PerceptualTime::from_millis(10_000));
+        assert_eq!(tau_p(2), PerceptualTime::from_millis(15_000));
+    }
+
     #[test]
     fn test_clock_drift_model() {
         let mut rng = StdRng::seed_from_u64(42);
@@ -1,16 +1,20 @@
 //! Clock implementations for ELARA Time Engine
 
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
 use elara_core::{PerceptualTime, StateTime};
 
+use crate::{ClockSource, SystemClock};
+
 /// Perceptual clock (τp) - monotonic, smooth, local-driven
 /// INVARIANT: τp MUST be monotonically increasing, NEVER jumps
 pub struct PerceptualClock {
     /// Current perceptual time
     value: PerceptualTime,
-    /// Last update instant
-    last_update: Instant,
+    /// Where elapsed time is read from
+    source: Box<dyn ClockSource>,
+    /// Source time at the last update
+    last_update: Duration,
 }
 
 const MAX_PERCEPTUAL_TICK: Duration = Duration::from_millis(100);
@@ -18,18 +22,24 @@ const MAX_PERCEPTUAL_TICK: Duration = Duration::from_millis(100);
 impl PerceptualClock {
     /// Create a new perceptual clock starting at zero
     pub fn new() -> Self {
-        let now = Instant::now();
+        Self::with_source(SystemClock::new())
+    }
+
+    /// Create a perceptual clock that follows `source`
+    pub fn with_source(source: impl ClockSource + 'static) -> Self {
+        let now = source.elapsed();
         PerceptualClock {
             value: PerceptualTime::ZERO,
+            source: Box::new(source),
             last_update: now,
         }
     }
 
-    /// Advance the clock based on elapsed real time
+    /// Advance the clock based on time elapsed at the source
     /// Returns the new perceptual time
     pub fn tick(&mut self) -> PerceptualTime {
-        let now = Instant::now();
-        let elapsed = now.duration_since(self.last_update);
+        let now = self.source.elapsed().max(self.last_update);
+        let elapsed = now - self.last_update;
 
         let clamped = if elapsed > MAX_PERCEPTUAL_TICK {
             MAX_PERCEPTUAL_TICK
@@ -4,7 +4,22 @@ use std::time::Duration;
 
 use elara_core::{NodeId, PerceptualTime, RealityWindow, StateTime, TimePosition};
 
-use crate::{NetworkModel, PerceptualClock, StateClock};
+use crate::{
+    ClockSource, NetworkModel, PerceptualClock, StateClock, SystemClock, TimeSyncProbe,
+    TimeSyncReply,
+};
+
+/// Largest rate adjustment consensus may apply to τs
+pub const MAX_RATE_CORRECTION: f64 = 0.01;
+
+/// Shortest wait between `TimeSync` probes
+pub const MIN_TIME_SYNC_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Longest wait between `TimeSync` probes
+pub const MAX_TIME_SYNC_INTERVAL: Duration = Duration::from_secs(8);
+
+/// Consensus offset beyond which τs is not settled (seconds)
+const SETTLED_OFFSET: f64 = 0.05;
 
 /// Time Engine configuration
 #[derive(Clone, Debug)]
@@ -75,6 +90,8 @@ pub struct TimeEngine {
     Hp: Duration,
     /// Current correction horizon
     Hc: Duration,
+    /// Total correction applied to τs (microseconds)
+    bend: i64,
     /// Configuration
     config: TimeEngineConfig,
 }
@@ -87,12 +104,18 @@ impl TimeEngine {
 
     /// Create a new Time Engine with custom configuration
     pub fn with_config(config: TimeEngineConfig) -> Self {
+        Self::with_clock_source(config, SystemClock::new())
+    }
+
+    /// Create a Time Engine whose τp follows `source`
+    pub fn with_clock_source(config: TimeEngineConfig, source: impl ClockSource + 'static) -> Self {
         TimeEngine {
-            perceptual: PerceptualClock::new(),
+            perceptual: PerceptualClock::with_source(source),
             state: StateClock::new(),
             network: NetworkModel::new(),
             Hp: config.Hp_min,
             Hc: config.Hc_min,
+            bend: 0,
             config,
         }
     }
@@ -104,8 +127,18 @@ impl TimeEngine {
         self.perceptual.tick();
 
         // τs advances with potential convergence correction
+        self.steer_to_consensus();
+        let before = self.state.now();
         self.state.advance(self.config.tick_interval);
 
+        // Keep peer samples in terms of the bent clock
+        let advanced = self.state.now().as_micros() - before.as_micros();
+        let bent = advanced - self.config.tick_interval.as_micros() as i64;
+        if bent != 0 {
+            self.bend += bent;
+            self.network.shift_local(bent as f64 / 1_000_000.0);
+        }
+
         // Adjust horizons based on network quality
         self.adjust_horizons();
     }
@@ -148,6 +181,31 @@ impl TimeEngine {
         self.reality_window().classify(t)
     }
 
+    /// Prediction and correction horizons for events from `peer`
+    ///
+    /// Derived from the peer's own jitter, reorder and loss once its model
+    /// has an estimate, so one poor link does not widen the window for
+    /// everybody. Until then, the aggregate horizons.
+    pub fn peer_horizons(&self, peer: NodeId) -> (Duration, Duration) {
+        match self.network.get_peer(peer) {
+            Some(model) if model.has_estimate() => {
+                self.horizons(model.jitter_envelope, model.reorder_depth, model.loss_rate)
+            }
+            _ => (self.Hp, self.Hc),
+        }
+    }
+
+    /// Reality window for events from `peer`
+    pub fn reality_window_for(&self, peer: NodeId) -> RealityWindow {
+        let (Hp, Hc) = self.peer_horizons(peer);
+        RealityWindow::new(self.state.now(), Hc, Hp)
+    }
+
+    /// Classify a time from `peer` relative to its reality window
+    pub fn classify_time_for(&self, peer: NodeId, t: StateTime) -> TimePosition {
+        self.reality_window_for(peer).classify(t)
+    }
+
     /// Update network model from a received packet
     pub fn update_from_packet(&mut self, peer: NodeId, remote_time: StateTime, seq: u16) {
         let local_time = self.state.now().as_secs_f64();
@@ -156,7 +214,81 @@ impl TimeEngine {
             .update_from_packet(peer, local_time, remote_time_f, seq);
     }
 
-    /// Record packet reorder
+    /// Origin timestamp for a `TimeSync` probe
+    ///
+    /// τs without the corrections applied to it, so a round trip is timed
+    /// on a clock that does not bend while the probe is in flight.
+    pub fn probe_origin(&self) -> StateTime {
+        StateTime::from_micros(self.state.now().as_micros() - self.bend)
+    }
+
+    /// Answer `requester`'s probe, which arrived this tick
+    pub fn answer_probe(&self, requester: NodeId, probe: &TimeSyncProbe) -> TimeSyncReply {
+        let now = self.state.now();
+        TimeSyncReply {
+            requester,
+            origin: probe.origin,
+            received: now,
+            transmitted: now,
+            correction: self.bend,
+        }
+    }
+
+    /// Update network model from `peer`'s reply to a probe from `probe_origin`
+    ///
+    /// Returns the round-trip time, or `None` if the reply is inconsistent.
+    pub fn update_from_time_sync(
+        &mut self,
+        peer: NodeId,
+        reply: &TimeSyncReply,
+    ) -> Option<Duration> {
+        let origin = (reply.origin.as_micros() + self.bend) as f64 / 1_000_000.0;
+        let rtt = self.network.update_from_probe(
+            peer,
+            origin,
+            reply.received.as_secs_f64(),
+            reply.transmitted.as_secs_f64(),
+            self.state.now().as_secs_f64(),
+            reply.correction as f64 / 1_000_000.0,
+        )?;
+        Some(Duration::from_secs_f64(rtt))
+    }
+
+    /// How long to wait before the next `TimeSync` probe
+    ///
+    /// Probes go out at `MIN_TIME_SYNC_INTERVAL` until every peer has a
+    /// probed offset, then back off toward `MAX_TIME_SYNC_INTERVAL` as τs
+    /// settles into consensus on a stable network.
+    pub fn time_sync_interval(&self) -> Duration {
+        let unsynced = self
+            .network
+            .peers
+            .values()
+            .any(|peer| !peer.is_probed() || !peer.has_estimate());
+        if unsynced {
+            return MIN_TIME_SYNC_INTERVAL;
+        }
+        let offset = self
+            .network
+            .consensus_offset(self.state.now().as_secs_f64())
+            .unwrap_or_default();
+        let settled = (1.0 - offset.abs() / SETTLED_OFFSET).clamp(0.0, 1.0);
+        let backoff = settled * self.network.stability_score;
+        MIN_TIME_SYNC_INTERVAL + (MAX_TIME_SYNC_INTERVAL - MIN_TIME_SYNC_INTERVAL).mul_f64(backoff)
+    }
+
+    /// Note traffic from `peer` that carries no timing sample
+    pub fn heard_from(&mut self, peer: NodeId) {
+        let local_time = self.state.now().as_secs_f64();
+        self.network.touch(peer, local_time);
+    }
+
+    /// Stop modelling a peer that left the session
+    pub fn forget_peer(&mut self, peer: NodeId) {
+        self.network.remove_peer(peer);
+    }
+
+    /// Record the deepest packet reorder over a recent window
     pub fn record_reorder(&mut self, depth: u32) {
         self.network.record_reorder(depth);
     }
@@ -166,38 +298,87 @@ impl TimeEngine {
         self.network.record_loss(lost, total);
     }
 
+    /// Record the deepest recent reorder of `peer`'s packets
+    pub fn record_peer_reorder(&mut self, peer: NodeId, depth: u32) {
+        self.network.record_peer_reorder(peer, depth);
+    }
+
+    /// Record loss of `peer`'s packets
+    pub fn record_peer_loss(&mut self, peer: NodeId, lost: u32, total: u32) {
+        self.network.record_peer_loss(peer, lost, total);
+    }
+
     /// Get network stability score
     pub fn stability_score(&self) -> f64 {
         self.network.stability_score
     }
 
+    /// Slew τs toward network consensus time, matching the consensus rate
+    ///
+    /// The state clock corrects a bounded amount per tick, so τs bends
+    /// toward consensus without jumping.
+    fn steer_to_consensus(&mut self) {
+        let now = self.state.now();
+        match self.network.consensus_offset(now.as_secs_f64()) {
+            Some(offset) => {
+                let consensus = now.as_micros() - (offset * 1_000_000.0) as i64;
+                self.state
+                    .set_convergence_target(StateTime::from_micros(consensus));
+                let rate = self.network.consensus_rate(self.state.rate());
+                self.state
+                    .set_rate(rate.clamp(1.0 - MAX_RATE_CORRECTION, 1.0 + MAX_RATE_CORRECTION));
+            }
+            None => {
+                self.state.clear_convergence_target();
+                self.state.set_rate(1.0);
+            }
+        }
+    }
+
     /// Adjust horizons based on network quality
     fn adjust_horizons(&mut self) {
         let net = &self.network;
+        (self.Hp, self.Hc) = self.horizons(net.jitter, net.reorder_depth, net.loss_rate);
+    }
+
+    /// Prediction and correction horizons for the given network quality
+    fn horizons(&self, jitter: f64, reorder_depth: u32, loss_rate: f64) -> (Duration, Duration) {
         let cfg = &self.config;
 
         // Prediction horizon: expands with network degradation
         let Hp_raw = cfg.Hp_min.as_secs_f64()
-            + cfg.k1_jitter * net.jitter
-            + cfg.k2_reorder * net.reorder_depth as f64 * 0.001
-            + cfg.k3_loss * net.loss_rate;
+            + cfg.k1_jitter * jitter
+            + cfg.k2_reorder * reorder_depth as f64 * 0.001
+            + cfg.k3_loss * loss_rate;
 
-        self.Hp = Duration::from_secs_f64(
+        let Hp = Duration::from_secs_f64(
             Hp_raw.clamp(cfg.Hp_min.as_secs_f64(), cfg.Hp_max.as_secs_f64()),
         );
 
         // Correction horizon: expands with jitter
-        let Hc_raw = cfg.Hc_min.as_secs_f64() + cfg.k4_jitter_correct * net.jitter;
+        let Hc_raw = cfg.Hc_min.as_secs_f64() + cfg.k4_jitter_correct * jitter;
 
-        self.Hc = Duration::from_secs_f64(
+        let Hc = Duration::from_secs_f64(
             Hc_raw.clamp(cfg.Hc_min.as_secs_f64(), cfg.Hc_max.as_secs_f64()),
         );
+        (Hp, Hc)
     }
 
     /// Calculate correction weight for a late event
     /// Weight decreases as event gets older
     pub fn correction_weight(&self, delay: Duration) -> f64 {
-        let Hc = self.Hc.as_secs_f64();
+        Self::weight_within(delay, self.Hc)
+    }
+
+    /// Correction weight for a late event from `peer`, within its own
+    /// correction horizon
+    pub fn correction_weight_for(&self, peer: NodeId, delay: Duration) -> f64 {
+        let (_, Hc) = self.peer_horizons(peer);
+        Self::weight_within(delay, Hc)
+    }
+
+    fn weight_within(delay: Duration, Hc: Duration) -> f64 {
+        let Hc = Hc.as_secs_f64();
         let delay_secs = delay.as_secs_f64();
         (1.0 - delay_secs / Hc).clamp(0.0, 1.0)
     }
@@ -216,15 +397,12 @@ impl TimeEngine {
     ///
     /// This returns the drift between the local state clock and the
     /// network consensus time. A positive value means the local clock
-    /// is ahead, negative means it's behind.
-    ///
-    /// For now, this returns 0 as a placeholder. In a full implementation,
-    /// this would track the difference between local τs and the network
-    /// consensus time based on peer synchronization.
+    /// is ahead, negative means it's behind. Zero until some peer has
+    /// enough timing samples for a consensus.
     pub fn drift_ms(&self) -> i64 {
-        // TODO: Implement actual drift calculation based on peer sync
-        // This would compare self.state.now() with network consensus time
-        0
+        self.network
+            .consensus_offset(self.state.now().as_secs_f64())
+            .map_or(0, |offset| (offset * 1000.0).round() as i64)
     }
 }
 
@@ -280,6 +458,109 @@ mod tests {
         assert!(engine.Hp() > initial_Hp);
     }
 
+    #[test]
+    fn test_peer_windows_follow_peer_network() {
+        let mut engine = TimeEngine::new();
+        let (steady, mobile, stranger) = (NodeId::new(1), NodeId::new(2), NodeId::new(3));
+        for i in 0..10 {
+            let local = i as f64 * 0.1;
+            let spike = if i % 2 == 0 { 0.08 } else { 0.0 };
+            engine
+                .network
+                .update_from_packet(steady, local, local - 0.01, 0);
+            engine
+                .network
+                .update_from_packet(mobile, local, local - 0.01 - spike, 0);
+        }
+        engine.record_peer_loss(mobile, 1, 5);
+        engine.tick();
+
+        // The steady peer keeps the tightest window despite the mobile one
+        let cfg = TimeEngineConfig::default();
+        assert_eq!(engine.peer_horizons(steady), (cfg.Hp_min, cfg.Hc_min));
+        let (Hp, Hc) = engine.peer_horizons(mobile);
+        assert!(Hp > engine.Hp() && engine.Hp() > cfg.Hp_min);
+        assert!(Hc > engine.Hc() && engine.Hc() > cfg.Hc_min);
+        assert_eq!(engine.peer_horizons(stranger), (engine.Hp(), engine.Hc()));
+
+        // The same lateness is past one peer's window but not the other's
+        let late = Duration::from_millis(150);
+        let t = engine.tau_s() - late;
+        assert_eq!(engine.classify_time_for(steady, t), TimePosition::TooLate);
+        assert_eq!(
+            engine.classify_time_for(mobile, t),
+            TimePosition::Correctable
+        );
+        assert_eq!(engine.correction_weight_for(steady, late), 0.0);
+        assert!(engine.correction_weight_for(mobile, late) > 0.0);
+    }
+
+    #[test]
+    fn test_slews_to_consensus() {
+        let mut engine = TimeEngine::new();
+        let peers = [NodeId::new(1), NodeId::new(2), NodeId::new(3)];
+        let tick = engine.config.tick_interval;
+
+        // Peers agree on a clock 200ms ahead of ours that runs 0.5% fast
+        let mut true_time = StateTime::ZERO;
+        let mut drifts = Vec::new();
+        for i in 0..1000u16 {
+            let peer_time =
+                StateTime::from_micros((true_time.as_micros() as f64 * 1.005) as i64 + 200_000);
+            if i % 5 == 0 {
+                for peer in peers {
+                    engine.update_from_packet(peer, peer_time, i);
+                }
+            }
+            drifts.push(engine.drift_ms());
+
+            let before = engine.tau_s();
+            engine.tick();
+            // τs never jumps
+            let step = engine.tau_s().as_micros() - before.as_micros();
+            assert!(step > 0 && step <= 2 * tick.as_micros() as i64);
+            true_time = true_time.saturating_add(tick);
+        }
+
+        let first = drifts.iter().find(|&&drift| drift != 0);
+        assert!(first.is_some_and(|&drift| drift <= -190));
+        assert!(engine.drift_ms().abs() <= 2);
+        assert!((engine.state.rate() - 1.005).abs() < 0.001);
+        let peer_time = (true_time.as_micros() as f64 * 1.005) as i64 + 200_000;
+        assert!((engine.tau_s().as_micros() - peer_time).abs() < 5_000);
+    }
+
+    #[test]
+    fn test_time_sync_backs_off_once_settled() {
+        let mut engine = TimeEngine::new();
+        let peer = NodeId::new(1);
+        assert_eq!(engine.time_sync_interval(), MIN_TIME_SYNC_INTERVAL);
+
+        // Peer shares our clock, 10ms away each way
+        for _ in 0..10 {
+            let origin = engine.probe_origin();
+            for _ in 0..2 {
+                engine.tick();
+            }
+            let at_peer = engine.tau_s().saturating_sub(Duration::from_millis(10));
+            let reply = TimeSyncReply {
+                requester: NodeId::new(2),
+                origin,
+                received: at_peer,
+                transmitted: at_peer,
+                correction: 0,
+            };
+            let rtt = engine.update_from_time_sync(peer, &reply).unwrap();
+            assert_eq!(rtt, Duration::from_millis(20));
+            for _ in 0..10 {
+                engine.tick();
+            }
+        }
+
+        assert!((engine.network().latency_mean - 0.01).abs() < 1e-6);
+        assert!(engine.time_sync_interval() > 4 * MIN_TIME_SYNC_INTERVAL);
+    }
+
     #[test]
     fn test_correction_weight() {
         let engine = TimeEngine::new();
@@ -4,14 +4,20 @@
 //! This crate implements the Time Engine:
 //! - τp (Perceptual Time): monotonic, smooth, local-driven
 //! - τs (State Time): elastic, drift-correctable, convergence-oriented
-//! - Reality Window management
+//! - Injectable clock sources: system, manual and audio sample clocks
+//! - Reality Window management, per peer and in aggregate
 //! - Network model and horizon adaptation
+//! - TimeSync probing for round-trip and offset estimates
 //! - Prediction and correction loops
 
 pub mod clock;
 pub mod engine;
 pub mod network;
+pub mod source;
+pub mod sync;
 
 pub use clock::*;
 pub use engine::*;
 pub use network::*;
+pub use source::*;
+pub use sync::*;
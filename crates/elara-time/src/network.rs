@@ -1,9 +1,24 @@
-//! Network model for passive jitter/latency estimation
+//! Network model for jitter, latency and clock offset estimation
+//!
+//! Peers are modelled passively from the timestamps on their traffic until
+//! a `TimeSync` probe answers; from then on only probes, which separate
+//! latency from clock offset, estimate the peer's offset.
 
 use std::collections::HashMap;
 
 use elara_core::NodeId;
 
+/// Samples a peer needs before its offset counts toward consensus
+pub const MIN_OFFSET_SAMPLES: usize = 5;
+
+/// Probe samples kept per peer
+///
+/// Fewer than passive samples, since each is exact up to path asymmetry.
+pub const MAX_PROBE_SAMPLES: usize = 8;
+
+/// Shortest span of samples a skew is fitted over (seconds)
+const MIN_SKEW_SPAN: f64 = 1.0;
+
 /// Network statistics for a single peer
 #[derive(Clone, Debug)]
 pub struct PeerNetworkModel {
@@ -13,8 +28,20 @@ pub struct PeerNetworkModel {
     pub skew: f64,
     /// Jitter envelope (max deviation)
     pub jitter_envelope: f64,
-    /// Recent latency samples
-    samples: Vec<f64>,
+    /// Local time of the latest sample
+    pub last_heard: f64,
+    /// Smoothed round-trip time from probes (seconds), zero until probed
+    pub rtt: f64,
+    /// Loss rate of the peer's traffic (0.0 - 1.0)
+    pub loss_rate: f64,
+    /// Deepest recent reorder of the peer's traffic
+    pub reorder_depth: u32,
+    /// Recent (local time, offset) samples
+    samples: Vec<(f64, f64)>,
+    /// Whether the samples come from probes rather than passive timestamps
+    probed: bool,
+    /// Total correction the peer reported applying to its clock (seconds)
+    remote_correction: f64,
     /// Maximum samples to keep
     max_samples: usize,
 }
@@ -25,44 +52,182 @@ impl PeerNetworkModel {
             offset: 0.0,
             skew: 0.0,
             jitter_envelope: 0.0,
+            last_heard: 0.0,
+            rtt: 0.0,
+            loss_rate: 0.0,
+            reorder_depth: 0,
             samples: Vec::new(),
+            probed: false,
+            remote_correction: 0.0,
             max_samples: 100,
         }
     }
 
     /// Update with a new timing sample
+    ///
+    /// Passive samples include the one-way latency, so they are ignored
+    /// once the peer answered a probe.
     pub fn update(&mut self, local_time: f64, remote_time: f64) {
-        let sample = local_time - remote_time;
-        self.samples.push(sample);
+        self.last_heard = self.last_heard.max(local_time);
+        if !self.probed {
+            self.push_sample(local_time, local_time - remote_time);
+        }
+    }
+
+    /// Update with a four-timestamp probe exchange
+    ///
+    /// `origin` and `local_time` are the local times the probe left and
+    /// its reply arrived; `received` and `transmitted` the peer's times it
+    /// arrived there and the reply left, and `correction` how far the peer
+    /// had bent its clock by then. Earlier samples are moved by the peer's
+    /// correction since, so its convergence is not mistaken for skew.
+    /// Returns the round-trip time, or `None` if the timestamps are
+    /// inconsistent.
+    pub fn update_probe(
+        &mut self,
+        origin: f64,
+        received: f64,
+        transmitted: f64,
+        local_time: f64,
+        correction: f64,
+    ) -> Option<f64> {
+        let rtt = (local_time - origin) - (transmitted - received);
+        if rtt < 0.0 || transmitted < received {
+            return None;
+        }
+        let remote_ahead = ((received - origin) + (transmitted - local_time)) / 2.0;
+
+        self.last_heard = self.last_heard.max(local_time);
+        if !self.probed {
+            self.probed = true;
+            self.samples.clear();
+            self.max_samples = MAX_PROBE_SAMPLES;
+        } else {
+            let bent = correction - self.remote_correction;
+            for (_, offset) in &mut self.samples {
+                *offset -= bent;
+            }
+        }
+        self.remote_correction = correction;
+        self.rtt = if self.rtt > 0.0 {
+            self.rtt * 0.875 + rtt * 0.125
+        } else {
+            rtt
+        };
+        self.push_sample(local_time, -remote_ahead);
+        Some(rtt)
+    }
+
+    /// Check if the offset comes from probes
+    pub fn is_probed(&self) -> bool {
+        self.probed
+    }
+
+    fn push_sample(&mut self, local_time: f64, sample: f64) {
+        self.samples.push((local_time, sample));
 
         // Trim old samples
-        if self.samples.len() > self.max_samples {
+        while self.samples.len() > self.max_samples {
             self.samples.remove(0);
         }
 
         // Update estimates
-        if self.samples.len() >= 5 {
-            self.offset = Self::median(&self.samples);
-            self.jitter_envelope = self
-                .samples
+        if self.has_estimate() {
+            let offsets: Vec<f64> = self.samples.iter().map(|&(_, offset)| offset).collect();
+            self.offset = median(&offsets);
+            self.jitter_envelope = offsets
                 .iter()
                 .map(|s| (s - self.offset).abs())
                 .fold(0.0, f64::max);
+            self.skew = self.fit_skew();
         }
     }
 
-    fn median(values: &[f64]) -> f64 {
-        let mut sorted = values.to_vec();
-        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
-        let mid = sorted.len() / 2;
-        if sorted.len().is_multiple_of(2) {
-            (sorted[mid - 1] + sorted[mid]) / 2.0
+    /// Check if enough samples arrived to estimate the offset
+    pub fn has_estimate(&self) -> bool {
+        self.samples.len() >= MIN_OFFSET_SAMPLES
+    }
+
+    /// Estimated offset at `local_time`, extrapolated along the skew
+    pub fn offset_at(&self, local_time: f64) -> f64 {
+        if self.samples.is_empty() {
+            return self.offset;
+        }
+        let center = self.samples.iter().map(|&(t, _)| t).sum::<f64>() / self.samples.len() as f64;
+        self.offset + self.skew * (local_time - center)
+    }
+
+    /// Move the local time base by `delta` seconds, after the local clock was bent
+    pub fn shift(&mut self, delta: f64) {
+        for (local_time, offset) in &mut self.samples {
+            *local_time += delta;
+            *offset += delta;
+        }
+        self.offset += delta;
+        self.last_heard += delta;
+    }
+
+    /// Least-squares slope of offset over local time
+    ///
+    /// Zero until the samples span `MIN_SKEW_SPAN`, since a short span
+    /// mistakes jitter for drift.
+    fn fit_skew(&self) -> f64 {
+        let n = self.samples.len() as f64;
+        let (first, last) = match (self.samples.first(), self.samples.last()) {
+            (Some(first), Some(last)) => (first.0, last.0),
+            _ => return 0.0,
+        };
+        if last - first < MIN_SKEW_SPAN {
+            return 0.0;
+        }
+
+        let mean_t = self.samples.iter().map(|&(t, _)| t).sum::<f64>() / n;
+        let mean_o = self.samples.iter().map(|&(_, o)| o).sum::<f64>() / n;
+        let (mut covariance, mut variance) = (0.0, 0.0);
+        for &(t, o) in &self.samples {
+            covariance += (t - mean_t) * (o - mean_o);
+            variance += (t - mean_t) * (t - mean_t);
+        }
+        if variance > 0.0 {
+            covariance / variance
         } else {
-            sorted[mid]
+            0.0
         }
     }
 }
 
+fn median(values: &[f64]) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let mid = sorted.len() / 2;
+    if sorted.len() % 2 == 0 {
+        (sorted[mid - 1] + sorted[mid]) / 2.0
+    } else {
+        sorted[mid]
+    }
+}
+
+/// Marzullo's algorithm: the interval most of `intervals` agree on
+///
+/// Returns how many intervals overlap there, and the overlap's bounds.
+fn marzullo(intervals: &[(f64, f64)]) -> (usize, f64, f64) {
+    // Starts sort before ends at the same point, so touching intervals agree
+    let mut edges: Vec<(f64, i32)> = intervals
+        .iter()
+        .flat_map(|&(lo, hi)| [(lo, -1), (hi, 1)])
+        .collect();
+    edges.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+
+    let (mut count, mut best) = (0i32, (0usize, 0.0, 0.0));
+    for (i, &(point, kind)) in edges.iter().enumerate() {
+        count -= kind;
+        if count as usize > best.0 {
+            best = (count as usize, point, edges[i + 1].0);
+        }
+    }
+    best
+}
+
 impl Default for PeerNetworkModel {
     fn default() -> Self {
         Self::new()
@@ -106,9 +271,42 @@ impl NetworkModel {
         self.update_aggregates();
     }
 
-    /// Record a detected reorder
+    /// Update model from a `TimeSync` probe answered by `peer`
+    ///
+    /// See [`PeerNetworkModel::update_probe`]. Returns the round-trip time.
+    pub fn update_from_probe(
+        &mut self,
+        peer: NodeId,
+        origin: f64,
+        received: f64,
+        transmitted: f64,
+        local_time: f64,
+        correction: f64,
+    ) -> Option<f64> {
+        let rtt = self.peers.entry(peer).or_default().update_probe(
+            origin,
+            received,
+            transmitted,
+            local_time,
+            correction,
+        )?;
+        self.update_aggregates();
+        Some(rtt)
+    }
+
+    /// Note that `peer` was heard from at `local_time` without a timing sample
+    pub fn touch(&mut self, peer: NodeId, local_time: f64) {
+        let peer_model = self.peers.entry(peer).or_default();
+        peer_model.last_heard = peer_model.last_heard.max(local_time);
+    }
+
+    /// Record the deepest reorder seen over a recent window
+    ///
+    /// Replaces the previous depth, so the estimate recovers once packets
+    /// arrive in order again.
     pub fn record_reorder(&mut self, depth: u32) {
-        self.reorder_depth = self.reorder_depth.max(depth);
+        self.reorder_depth = depth;
+        self.update_aggregates();
     }
 
     /// Record packet loss
@@ -117,6 +315,21 @@ impl NetworkModel {
             let new_rate = lost_count as f64 / total_count as f64;
             // Exponential moving average
             self.loss_rate = self.loss_rate * 0.9 + new_rate * 0.1;
+            self.update_aggregates();
+        }
+    }
+
+    /// Record the deepest recent reorder of `peer`'s traffic
+    pub fn record_peer_reorder(&mut self, peer: NodeId, depth: u32) {
+        self.peers.entry(peer).or_default().reorder_depth = depth;
+    }
+
+    /// Record loss of `peer`'s traffic
+    pub fn record_peer_loss(&mut self, peer: NodeId, lost_count: u32, total_count: u32) {
+        if total_count > 0 {
+            let peer_model = self.peers.entry(peer).or_default();
+            let new_rate = lost_count as f64 / total_count as f64;
+            peer_model.loss_rate = peer_model.loss_rate * 0.9 + new_rate * 0.1;
         }
     }
 
@@ -129,6 +342,17 @@ impl NetworkModel {
         let total_jitter: f64 = self.peers.values().map(|p| p.jitter_envelope).sum();
         self.jitter = total_jitter / self.peers.len() as f64;
 
+        // One-way latency: half the round trip of probed peers
+        let rtts: Vec<f64> = self
+            .peers
+            .values()
+            .filter(|p| p.rtt > 0.0)
+            .map(|p| p.rtt)
+            .collect();
+        if !rtts.is_empty() {
+            self.latency_mean = rtts.iter().sum::<f64>() / rtts.len() as f64 / 2.0;
+        }
+
         // Compute stability score
         let jitter_factor = 1.0 / (1.0 + self.jitter * 10.0);
         let loss_factor = 1.0 - self.loss_rate;
@@ -141,6 +365,67 @@ impl NetworkModel {
     pub fn get_peer(&self, peer: NodeId) -> Option<&PeerNetworkModel> {
         self.peers.get(&peer)
     }
+
+    /// Peers not heard from for more than `timeout` seconds at local time `now`
+    pub fn silent_peers(&self, now: f64, timeout: f64) -> Vec<NodeId> {
+        self.peers
+            .iter()
+            .filter(|(_, peer)| now - peer.last_heard > timeout)
+            .map(|(&node, _)| node)
+            .collect()
+    }
+
+    /// Stop tracking a peer that left
+    pub fn remove_peer(&mut self, peer: NodeId) {
+        self.peers.remove(&peer);
+    }
+
+    /// Offset of the local clock from network consensus at local time `now`
+    ///
+    /// Each peer with an estimate votes with its offset, widened by its
+    /// jitter envelope, and the local clock votes for zero. The middle of
+    /// the interval a majority agrees on wins (Marzullo); without a
+    /// majority, the median vote does. `None` until some peer has an
+    /// estimate. Positive means the local clock is ahead.
+    pub fn consensus_offset(&self, now: f64) -> Option<f64> {
+        let mut votes = vec![0.0];
+        let mut intervals = vec![(0.0, 0.0)];
+        for peer in self.peers.values().filter(|peer| peer.has_estimate()) {
+            let offset = peer.offset_at(now);
+            votes.push(offset);
+            intervals.push((offset - peer.jitter_envelope, offset + peer.jitter_envelope));
+        }
+        if votes.len() == 1 {
+            return None;
+        }
+
+        let (agreeing, lo, hi) = marzullo(&intervals);
+        if agreeing * 2 > intervals.len() {
+            Some((lo + hi) / 2.0)
+        } else {
+            Some(median(&votes))
+        }
+    }
+
+    /// Median clock rate of peers with an estimate, relative to the local
+    /// clock's natural rate, with the local clock voting `local_rate`
+    pub fn consensus_rate(&self, local_rate: f64) -> f64 {
+        let mut rates = vec![local_rate];
+        rates.extend(
+            self.peers
+                .values()
+                .filter(|peer| peer.has_estimate())
+                .map(|peer| 1.0 - peer.skew),
+        );
+        median(&rates)
+    }
+
+    /// Move every peer's local time base by `delta` seconds
+    pub fn shift_local(&mut self, delta: f64) {
+        for peer in self.peers.values_mut() {
+            peer.shift(delta);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -179,4 +464,92 @@ mod tests {
         // With 50% loss recorded (EMA gives ~5% loss_rate), stability should drop
         assert!(model.stability_score < 1.0);
     }
+
+    #[test]
+    fn test_consensus_offset() {
+        let mut model = NetworkModel::new();
+        assert_eq!(model.consensus_offset(0.0), None);
+
+        // One peer 100ms ahead: split the difference
+        for i in 0..5 {
+            let t = i as f64 * 0.1;
+            model.update_from_packet(NodeId::new(1), t, t + 0.1, i);
+        }
+        assert!((model.consensus_offset(0.5).unwrap() + 0.05).abs() < 1e-9);
+
+        // Two more peers agree with it, so the majority interval wins
+        for peer in [2, 3] {
+            for i in 0..5 {
+                let t = i as f64 * 0.1;
+                model.update_from_packet(NodeId::new(peer), t, t + 0.1, i);
+            }
+        }
+        assert!((model.consensus_offset(0.5).unwrap() + 0.1).abs() < 1e-9);
+        assert!((model.consensus_rate(1.0) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_skew_estimated_by_regression() {
+        let mut model = PeerNetworkModel::new();
+        // Remote clock runs 1% slow
+        for i in 0..50 {
+            let t = i as f64 * 0.1;
+            model.update(t, t * 0.99);
+        }
+        assert!((model.skew - 0.01).abs() < 1e-6);
+        assert!((model.offset_at(10.0) - 0.1).abs() < 1e-6);
+
+        model.shift(0.5);
+        assert!((model.offset_at(10.5) - 0.6).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_probe_separates_latency_from_offset() {
+        let mut model = NetworkModel::new();
+        let peer = NodeId::new(1);
+        // Peer 300ms ahead, 40ms out and 60ms back, 5ms to answer
+        for i in 0..10 {
+            let origin = i as f64 * 0.5;
+            let received = origin + 0.04 + 0.3;
+            let transmitted = received + 0.005;
+            let arrived = transmitted - 0.3 + 0.06;
+            model.update_from_packet(peer, arrived, transmitted, i);
+            let rtt = model.update_from_probe(peer, origin, received, transmitted, arrived, 0.0);
+            assert!((rtt.unwrap() - 0.1).abs() < 1e-9);
+        }
+
+        let peer_model = model.get_peer(peer).unwrap();
+        assert!(peer_model.is_probed());
+        assert!((peer_model.rtt - 0.1).abs() < 1e-9);
+        // Off by half the asymmetry, where the passive estimate is off by
+        // the whole return leg
+        assert!((peer_model.offset + 0.3 - 0.01).abs() < 1e-9);
+        assert!((model.latency_mean - 0.05).abs() < 1e-9);
+
+        // The peer slews 100ms back and says so: older samples move with it
+        model.update_from_probe(peer, 5.0, 5.24, 5.245, 5.105, -0.1);
+        let peer_model = model.get_peer(peer).unwrap();
+        assert!((peer_model.offset + 0.2 - 0.01).abs() < 1e-9);
+        assert!(peer_model.jitter_envelope < 1e-9);
+
+        // Replies claiming to leave before the probe arrived are dropped
+        assert_eq!(
+            model.update_from_probe(peer, 10.0, 10.3, 10.2, 10.1, 0.0),
+            None
+        );
+    }
+
+    #[test]
+    fn test_silent_peers() {
+        let mut model = NetworkModel::new();
+        model.update_from_packet(NodeId::new(1), 1.0, 0.95, 0);
+        model.update_from_packet(NodeId::new(2), 4.0, 3.95, 0);
+
+        assert_eq!(model.silent_peers(5.0, 2.0), vec![NodeId::new(1)]);
+        model.touch(NodeId::new(3), 1.0);
+        model.touch(NodeId::new(3), 4.0);
+        assert_eq!(model.silent_peers(5.0, 2.0), vec![NodeId::new(1)]);
+        model.remove_peer(NodeId::new(1));
+        assert!(model.silent_peers(5.0, 2.0).is_empty());
+    }
 }
@@ -0,0 +1,154 @@
+//! Clock sources - where τp reads elapsed time from
+//!
+//! The perceptual clock never reads the system clock itself. It asks a
+//! `ClockSource`, so τp can follow:
+//! - `SystemClock`: the monotonic system clock (the default)
+//! - `ManualClock`: virtual time advanced by hand, for simulation and tests
+//! - `SampleClock`: samples played by an audio device, so playback and τp
+//!   never drift apart
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Source of monotonic elapsed time
+pub trait ClockSource: Send + Sync {
+    /// Time elapsed since the source started; never decreases
+    fn elapsed(&self) -> Duration;
+}
+
+/// Monotonic system clock
+#[derive(Clone, Copy, Debug)]
+pub struct SystemClock {
+    origin: Instant,
+}
+
+impl SystemClock {
+    /// Start counting from now
+    pub fn new() -> Self {
+        SystemClock {
+            origin: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClockSource for SystemClock {
+    fn elapsed(&self) -> Duration {
+        self.origin.elapsed()
+    }
+}
+
+/// Virtual clock that only moves when told to
+///
+/// Clones share the same time, so a harness can keep one handle and give
+/// another to the engine it drives.
+#[derive(Clone, Debug, Default)]
+pub struct ManualClock {
+    micros: Arc<AtomicU64>,
+}
+
+impl ManualClock {
+    /// Start at zero
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Move the clock forward
+    pub fn advance(&self, dt: Duration) {
+        self.micros
+            .fetch_add(dt.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    /// Move the clock to `elapsed`, unless it is already past it
+    pub fn set(&self, elapsed: Duration) {
+        self.micros
+            .fetch_max(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+}
+
+impl ClockSource for ManualClock {
+    fn elapsed(&self) -> Duration {
+        Duration::from_micros(self.micros.load(Ordering::Relaxed))
+    }
+}
+
+/// Clock driven by a count of samples, such as frames an audio device
+/// has consumed
+///
+/// The audio callback advances one handle by each buffer it plays while
+/// the engine reads another.
+#[derive(Clone, Debug)]
+pub struct SampleClock {
+    sample_rate: u32,
+    samples: Arc<AtomicU64>,
+}
+
+impl SampleClock {
+    /// Count samples at `sample_rate` per second
+    pub fn new(sample_rate: u32) -> Self {
+        assert!(sample_rate > 0, "sample rate must be positive");
+        SampleClock {
+            sample_rate,
+            samples: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Record `count` more samples played
+    pub fn advance(&self, count: u64) {
+        self.samples.fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// Samples played so far
+    pub fn samples(&self) -> u64 {
+        self.samples.load(Ordering::Relaxed)
+    }
+
+    /// Samples per second
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+}
+
+impl ClockSource for SampleClock {
+    fn elapsed(&self) -> Duration {
+        let micros = self.samples() as u128 * 1_000_000 / self.sample_rate as u128;
+        Duration::from_micros(micros as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PerceptualClock;
+    use elara_core::PerceptualTime;
+
+    #[test]
+    fn test_injected_sources_drive_perceptual_clock() {
+        let manual = ManualClock::new();
+        let mut clock = PerceptualClock::with_source(manual.clone());
+        manual.advance(Duration::from_millis(30));
+        assert_eq!(clock.tick(), PerceptualTime::from_millis(30));
+        // Setting time backwards is ignored
+        manual.set(Duration::from_millis(10));
+        assert_eq!(clock.tick(), PerceptualTime::from_millis(30));
+        // A stall is clamped like any other
+        manual.set(Duration::from_secs(5));
+        assert_eq!(clock.tick(), PerceptualTime::from_millis(130));
+
+        // 48 kHz audio in 480-sample buffers is 10ms per callback
+        let audio = SampleClock::new(48_000);
+        let mut clock = PerceptualClock::with_source(audio.clone());
+        for _ in 0..25 {
+            audio.advance(480);
+            clock.tick();
+        }
+        assert_eq!(audio.samples(), 12_000);
+        assert_eq!(clock.now(), PerceptualTime::from_millis(250));
+    }
+}
@@ -0,0 +1,154 @@
+//! TimeSync probes - four-timestamp clock exchange between peers
+//!
+//! A node broadcasts a `TimeSync` probe stamped with its origin time t1.
+//! Every peer answers with a `TimeCorrection` echoing t1 together with its
+//! own state time when the probe arrived (t2) and when the reply left (t3),
+//! and the prober reads t4 when the reply arrives. As in NTP, the round
+//! trip is `(t4 - t1) - (t3 - t2)` and the peer is ahead by
+//! `((t2 - t1) + (t3 - t4)) / 2`, which is off by half the difference
+//! between the two legs of the path.
+
+use elara_core::{Event, EventType, MutationOp, NodeId, StateId, StateTime};
+
+/// Target state of time sync events, which touch no state
+pub const TIME_SYNC_STATE: StateId = StateId::ZERO;
+
+/// Probe asking every peer for its clock
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimeSyncProbe {
+    /// Prober's time when the probe left (t1)
+    pub origin: StateTime,
+}
+
+impl TimeSyncProbe {
+    /// Encode for an event payload
+    pub fn encode(&self) -> Vec<u8> {
+        self.origin.as_micros().to_le_bytes().to_vec()
+    }
+
+    /// Decode a probe payload
+    pub fn decode(buf: &[u8]) -> Option<Self> {
+        Some(TimeSyncProbe {
+            origin: StateTime::from_micros(i64::from_le_bytes(buf.try_into().ok()?)),
+        })
+    }
+
+    /// Wrap the probe in an unsigned `TimeSync` event from `source`
+    pub fn to_event(&self, source: NodeId, seq: u64) -> Event {
+        Event::new(
+            source,
+            seq,
+            EventType::TimeSync,
+            TIME_SYNC_STATE,
+            MutationOp::Set(self.encode()),
+        )
+    }
+
+    /// Extract a probe from a `TimeSync` event
+    pub fn from_event(event: &Event) -> Option<Self> {
+        if event.event_type != EventType::TimeSync {
+            return None;
+        }
+        match &event.mutation {
+            MutationOp::Set(data) => Self::decode(data),
+            _ => None,
+        }
+    }
+}
+
+/// Answer to a probe, addressed to the node that sent it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimeSyncReply {
+    /// Node whose probe this answers
+    pub requester: NodeId,
+    /// Echoed probe origin (t1)
+    pub origin: StateTime,
+    /// Replier's time when the probe arrived (t2)
+    pub received: StateTime,
+    /// Replier's time when the reply left (t3)
+    pub transmitted: StateTime,
+    /// How far the replier had bent its clock by t3 (microseconds)
+    pub correction: i64,
+}
+
+impl TimeSyncReply {
+    /// Size of an encoded reply: requester, three timestamps and correction
+    const SIZE: usize = 8 * 5;
+
+    /// Encode for an event payload
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(Self::SIZE);
+        buf.extend_from_slice(&self.requester.to_bytes());
+        for time in [self.origin, self.received, self.transmitted] {
+            buf.extend_from_slice(&time.as_micros().to_le_bytes());
+        }
+        buf.extend_from_slice(&self.correction.to_le_bytes());
+        buf
+    }
+
+    /// Decode a reply payload
+    pub fn decode(buf: &[u8]) -> Option<Self> {
+        if buf.len() != Self::SIZE {
+            return None;
+        }
+        let word = |i: usize| -> [u8; 8] { buf[i * 8..(i + 1) * 8].try_into().unwrap() };
+        let time = |i: usize| StateTime::from_micros(i64::from_le_bytes(word(i)));
+        Some(TimeSyncReply {
+            requester: NodeId::from_bytes(word(0)),
+            origin: time(1),
+            received: time(2),
+            transmitted: time(3),
+            correction: i64::from_le_bytes(word(4)),
+        })
+    }
+
+    /// Wrap the reply in an unsigned `TimeCorrection` event from `source`
+    pub fn to_event(&self, source: NodeId, seq: u64) -> Event {
+        Event::new(
+            source,
+            seq,
+            EventType::TimeCorrection,
+            TIME_SYNC_STATE,
+            MutationOp::Set(self.encode()),
+        )
+    }
+
+    /// Extract a reply from a `TimeCorrection` event
+    pub fn from_event(event: &Event) -> Option<Self> {
+        if event.event_type != EventType::TimeCorrection {
+            return None;
+        }
+        match &event.mutation {
+            MutationOp::Set(data) => Self::decode(data),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_probe_and_reply_roundtrip() {
+        let (alice, bob) = (NodeId::new(1), NodeId::new(2));
+        let probe = TimeSyncProbe {
+            origin: StateTime::from_micros(-1_500),
+        };
+        let event = probe.to_event(alice, 7);
+        assert_eq!(TimeSyncProbe::from_event(&event), Some(probe));
+        assert_eq!(TimeSyncReply::from_event(&event), None);
+
+        let reply = TimeSyncReply {
+            requester: alice,
+            origin: probe.origin,
+            received: StateTime::from_micros(40_000),
+            transmitted: StateTime::from_micros(41_000),
+            correction: -2_500,
+        };
+        let event = reply.to_event(bob, 3);
+        assert_eq!(event.event_type, EventType::TimeCorrection);
+        assert_eq!(TimeSyncReply::from_event(&event), Some(reply));
+        assert_eq!(TimeSyncReply::decode(&reply.encode()[1..]), None);
+    }
+}
@@ -0,0 +1,173 @@
+//! Payload compression
+//!
+//! Payloads are compressed before encryption. A compressed frame sets the
+//! `COMPRESSED` flag and names its algorithm in the `CompressionHint`
+//! extension. The compressed body is `uncompressed_len(4, LE) | data`, and
+//! the declared length is checked against a limit before any allocation so
+//! a small frame cannot expand into a decompression bomb.
+
+use elara_core::{ElaraError, ElaraResult};
+
+use crate::{Extensions, FixedHeader};
+
+/// Payloads shorter than this are sent as-is
+pub const COMPRESSION_THRESHOLD: usize = 128;
+
+/// Largest payload a compressed frame may expand to
+pub const MAX_DECOMPRESSED_SIZE: usize = 64 * 1024;
+
+/// Compression algorithm identifier (carried in `CompressionHint`)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[repr(u8)]
+pub enum CompressionAlgorithm {
+    /// No compression
+    #[default]
+    None = 0x00,
+    /// LZ4 block format
+    Lz4 = 0x01,
+}
+
+impl CompressionAlgorithm {
+    pub fn from_byte(b: u8) -> Option<Self> {
+        match b {
+            0x00 => Some(CompressionAlgorithm::None),
+            0x01 => Some(CompressionAlgorithm::Lz4),
+            _ => None,
+        }
+    }
+
+    pub fn to_byte(self) -> u8 {
+        self as u8
+    }
+}
+
+/// Compress a payload if it is large enough and actually shrinks
+///
+/// Returns None when the payload should be sent uncompressed.
+pub fn compress_payload(algorithm: CompressionAlgorithm, payload: &[u8]) -> Option<Vec<u8>> {
+    if payload.len() < COMPRESSION_THRESHOLD || payload.len() > MAX_DECOMPRESSED_SIZE {
+        return None;
+    }
+    let body = match algorithm {
+        CompressionAlgorithm::None => return None,
+        CompressionAlgorithm::Lz4 => lz4_flex::block::compress(payload),
+    };
+
+    let mut compressed = Vec::with_capacity(4 + body.len());
+    compressed.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    compressed.extend_from_slice(&body);
+    (compressed.len() < payload.len()).then_some(compressed)
+}
+
+/// Decompress a payload produced by [`compress_payload`]
+///
+/// Fails if the declared size exceeds `max_size` or the data is corrupt.
+pub fn decompress_payload(
+    algorithm: CompressionAlgorithm,
+    data: &[u8],
+    max_size: usize,
+) -> ElaraResult<Vec<u8>> {
+    if data.len() < 4 {
+        return Err(ElaraError::DecompressionFailed(
+            "missing length prefix".into(),
+        ));
+    }
+    let declared = u32::from_le_bytes([data[0], data[1], data[2], data[3]]) as usize;
+    if declared > max_size {
+        return Err(ElaraError::DecompressionFailed(format!(
+            "declared size {} exceeds limit {}",
+            declared, max_size
+        )));
+    }
+
+    let mut output = vec![0u8; declared];
+    let written = match algorithm {
+        CompressionAlgorithm::None => {
+            return Err(ElaraError::DecompressionFailed(
+                "no algorithm for compressed payload".into(),
+            ))
+        }
+        CompressionAlgorithm::Lz4 => lz4_flex::block::decompress_into(&data[4..], &mut output)
+            .map_err(|e| ElaraError::DecompressionFailed(e.to_string()))?,
+    };
+    if written != declared {
+        return Err(ElaraError::DecompressionFailed(format!(
+            "expected {} bytes, got {}",
+            declared, written
+        )));
+    }
+    Ok(output)
+}
+
+/// Restore a received payload according to its frame's compression flag
+pub fn decompress_frame_payload(
+    header: &FixedHeader,
+    extensions: &Extensions,
+    payload: Vec<u8>,
+    max_size: usize,
+) -> ElaraResult<Vec<u8>> {
+    if !header.flags.is_compressed() {
+        return Ok(payload);
+    }
+    let hint = extensions.compression_hint.unwrap_or(0);
+    let algorithm = CompressionAlgorithm::from_byte(hint).ok_or_else(|| {
+        ElaraError::DecompressionFailed(format!("unknown algorithm {}", hint))
+    })?;
+    decompress_payload(algorithm, &payload, max_size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let text = b"the quick brown fox jumps over the lazy dog. ".repeat(10);
+        let compressed = compress_payload(CompressionAlgorithm::Lz4, &text).unwrap();
+        assert!(compressed.len() < text.len());
+
+        let restored = decompress_payload(
+            CompressionAlgorithm::Lz4,
+            &compressed,
+            MAX_DECOMPRESSED_SIZE,
+        )
+        .unwrap();
+        assert_eq!(restored, text);
+    }
+
+    #[test]
+    fn test_skips_small_and_incompressible() {
+        assert!(compress_payload(CompressionAlgorithm::Lz4, &[0u8; 64]).is_none());
+        assert!(compress_payload(CompressionAlgorithm::None, &[0u8; 1024]).is_none());
+
+        // Pseudo-random bytes do not shrink
+        let mut x = 0x9E37_79B9u32;
+        let noise: Vec<u8> = (0..512)
+            .map(|_| {
+                x ^= x << 13;
+                x ^= x >> 17;
+                x ^= x << 5;
+                x as u8
+            })
+            .collect();
+        assert!(compress_payload(CompressionAlgorithm::Lz4, &noise).is_none());
+    }
+
+    #[test]
+    fn test_bomb_rejected() {
+        let zeros = vec![0u8; 32 * 1024];
+        let compressed = compress_payload(CompressionAlgorithm::Lz4, &zeros).unwrap();
+        assert!(matches!(
+            decompress_payload(CompressionAlgorithm::Lz4, &compressed, 1024),
+            Err(ElaraError::DecompressionFailed(_))
+        ));
+
+        // Lying about the size
+        let mut forged = compressed.clone();
+        forged[0..4].copy_from_slice(&(16u32).to_le_bytes());
+        assert!(
+            decompress_payload(CompressionAlgorithm::Lz4, &forged, MAX_DECOMPRESSED_SIZE).is_err()
+        );
+        assert!(decompress_payload(CompressionAlgorithm::Lz4, &[1, 2], 1024).is_err());
+    }
+}
@@ -0,0 +1,864 @@
+//! Forward error correction over outgoing frames
+//!
+//! Consecutive frames of one packet class form a group. Each data frame is
+//! tagged with a `RedundancyGroup` extension before encryption; once the
+//! group is full, or has stayed open for [`FEC_FLUSH_DELAY`], the encoder
+//! emits parity frames carrying the `REPAIR` flag.
+//! Parity is computed over the serialized (already encrypted) frames, so a
+//! recovered frame still goes through normal decryption and replay checks.
+//!
+//! Two schemes are supported:
+//! - XOR parity: one parity frame, recovers a single loss per group
+//! - Reed-Solomon (GF(2^8), Cauchy matrix): recovers up to `parity` losses
+//!
+//! Parity payload: `scheme(1) | data(1) | parity(1) | index(1) | base_seq(2) | shard`
+//! where each data shard is `len(2) | frame` zero-padded to the group maximum.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use elara_core::{ElaraError, ElaraResult, NodeId, PacketClass};
+
+use crate::{
+    Extensions, FixedHeader, Frame, FrameBuilder, AUTH_TAG_SIZE, FIXED_HEADER_SIZE, MAX_FRAME_SIZE,
+};
+
+/// Size of the parity header inside a repair frame payload
+pub const PARITY_HEADER_SIZE: usize = 6;
+
+/// Largest serialized data frame that fits into a parity shard
+pub const MAX_FEC_FRAME_SIZE: usize =
+    MAX_FRAME_SIZE - FIXED_HEADER_SIZE - 5 - PARITY_HEADER_SIZE - 2 - AUTH_TAG_SIZE;
+
+/// Largest event payload protected by FEC (leaves room for header,
+/// extensions and tag within [`MAX_FEC_FRAME_SIZE`])
+pub const MAX_FEC_PAYLOAD: usize = 1200;
+
+/// Default number of groups tracked by the decoder
+pub const DEFAULT_MAX_FEC_GROUPS: usize = 64;
+
+/// Default time a group waits for its parity
+pub const FEC_GROUP_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Default time an outgoing group stays open before parity is sent for the
+/// frames it has (well within [`FEC_GROUP_TIMEOUT`])
+pub const FEC_FLUSH_DELAY: Duration = Duration::from_millis(200);
+
+/// FEC scheme used by a group
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum FecScheme {
+    /// Single XOR parity frame
+    XorParity = 0x00,
+    /// Reed-Solomon erasure code over GF(2^8)
+    ReedSolomon = 0x01,
+}
+
+impl FecScheme {
+    pub fn from_byte(b: u8) -> Option<Self> {
+        match b {
+            0x00 => Some(FecScheme::XorParity),
+            0x01 => Some(FecScheme::ReedSolomon),
+            _ => None,
+        }
+    }
+
+    pub fn to_byte(self) -> u8 {
+        self as u8
+    }
+}
+
+/// Group shape: scheme plus data and parity frame counts
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FecParams {
+    pub scheme: FecScheme,
+    pub data_shards: u8,
+    pub parity_shards: u8,
+}
+
+impl FecParams {
+    /// XOR parity over `data_shards` frames
+    pub fn xor(data_shards: u8) -> Self {
+        FecParams {
+            scheme: FecScheme::XorParity,
+            data_shards: data_shards.max(1),
+            parity_shards: 1,
+        }
+    }
+
+    /// Reed-Solomon with `parity_shards` repair frames per `data_shards`
+    pub fn reed_solomon(data_shards: u8, parity_shards: u8) -> Self {
+        FecParams {
+            scheme: FecScheme::ReedSolomon,
+            data_shards: data_shards.max(1),
+            parity_shards: parity_shards.max(1),
+        }
+    }
+
+    /// Pick a protection level for an observed loss rate (0.0 - 1.0)
+    ///
+    /// Returns None when the link is clean enough to skip FEC.
+    pub fn for_loss_rate(loss_rate: f64) -> Option<Self> {
+        if loss_rate < 0.01 {
+            None
+        } else if loss_rate < 0.05 {
+            Some(Self::xor(8))
+        } else if loss_rate < 0.10 {
+            Some(Self::xor(4))
+        } else if loss_rate < 0.20 {
+            Some(Self::reed_solomon(4, 2))
+        } else {
+            Some(Self::reed_solomon(4, 4))
+        }
+    }
+
+    /// Parity overhead as a fraction of data frames
+    pub fn overhead(&self) -> f64 {
+        self.parity_shards as f64 / self.data_shards as f64
+    }
+}
+
+/// Check if a frame is a parity (repair) frame
+pub fn is_parity_frame(frame: &Frame) -> bool {
+    frame.header.flags.is_repair() && frame.extensions.redundancy_group.is_some()
+}
+
+/// Check if FEC applies to a packet class
+pub fn fec_protects(class: PacketClass) -> bool {
+    matches!(class, PacketClass::Core | PacketClass::Perceptual)
+}
+
+/// Data frames collected for a group that is still open
+struct OpenGroup {
+    id: u16,
+    params: FecParams,
+    base_seq: u16,
+    frames: Vec<Vec<u8>>,
+    /// Header of the latest frame, template for the parity headers
+    header: Option<FixedHeader>,
+    /// When parity is sent even if the group is not full
+    deadline: Option<Instant>,
+}
+
+/// Builds parity frames over outgoing frames
+pub struct FecEncoder {
+    params: Option<FecParams>,
+    next_group: u16,
+    open: HashMap<PacketClass, OpenGroup>,
+    flush_delay: Duration,
+}
+
+impl FecEncoder {
+    /// Create an encoder with FEC disabled until a loss rate is reported
+    pub fn new() -> Self {
+        Self::with_flush_delay(FEC_FLUSH_DELAY)
+    }
+
+    /// Create an encoder that closes partial groups after `flush_delay`
+    pub fn with_flush_delay(flush_delay: Duration) -> Self {
+        FecEncoder {
+            params: None,
+            next_group: 0,
+            open: HashMap::new(),
+            flush_delay,
+        }
+    }
+
+    /// Adapt the protection level to the observed loss rate
+    ///
+    /// Groups already open keep the parameters they started with.
+    pub fn set_loss_rate(&mut self, loss_rate: f64) {
+        self.params = FecParams::for_loss_rate(loss_rate);
+    }
+
+    /// Use fixed parameters (None disables FEC)
+    pub fn set_params(&mut self, params: Option<FecParams>) {
+        self.params = params;
+    }
+
+    /// Current parameters for new groups
+    pub fn params(&self) -> Option<FecParams> {
+        self.params
+    }
+
+    /// Group to tag the next frame of `class` with
+    ///
+    /// Returns None if the class is unprotected or FEC is off.
+    pub fn group_for(&mut self, class: PacketClass) -> Option<u16> {
+        if !fec_protects(class) {
+            return None;
+        }
+        if let Some(group) = self.open.get(&class) {
+            return Some(group.id);
+        }
+        let params = self.params?;
+        let id = self.next_group;
+        self.next_group = self.next_group.wrapping_add(1);
+        self.open.insert(
+            class,
+            OpenGroup {
+                id,
+                params,
+                base_seq: 0,
+                frames: Vec::new(),
+                header: None,
+                deadline: None,
+            },
+        );
+        Some(id)
+    }
+
+    /// Add a tagged, encrypted frame to its group
+    ///
+    /// Returns the parity frames once the group is full. The group's flush
+    /// deadline starts with its first frame.
+    pub fn push(&mut self, frame: &Frame, now: Instant) -> ElaraResult<Vec<Frame>> {
+        let class = frame.header.class;
+        let Some(id) = frame.extensions.redundancy_group else {
+            return Ok(Vec::new());
+        };
+        let bytes = frame.serialize()?;
+        if bytes.len() > MAX_FEC_FRAME_SIZE {
+            return Err(ElaraError::InvalidWireFormat(format!(
+                "Frame too large for FEC: {} > {}",
+                bytes.len(),
+                MAX_FEC_FRAME_SIZE
+            )));
+        }
+
+        let Some(group) = self.open.get_mut(&class).filter(|g| g.id == id) else {
+            return Ok(Vec::new());
+        };
+        let seq = frame.header.seq();
+        if group.frames.is_empty() {
+            group.base_seq = seq;
+            group.deadline = Some(now + self.flush_delay);
+        } else if seq != group.base_seq.wrapping_add(group.frames.len() as u16) {
+            // Sequence gap: close the group without this frame
+            let group = self.open.remove(&class).expect("present");
+            return Ok(Self::parity_frames(group));
+        }
+        group.frames.push(bytes);
+        group.header = Some(frame.header.clone());
+
+        if group.frames.len() < group.params.data_shards as usize {
+            return Ok(Vec::new());
+        }
+        let group = self.open.remove(&class).expect("present");
+        Ok(Self::parity_frames(group))
+    }
+
+    /// Close groups whose flush deadline has passed, emitting parity for the
+    /// frames they collected
+    ///
+    /// Without this a sparse stream would leave a group open until the
+    /// receiver gives up on it.
+    pub fn flush_expired(&mut self, now: Instant) -> Vec<Frame> {
+        let expired: Vec<_> = self
+            .open
+            .iter()
+            .filter(|(_, g)| g.deadline.is_some_and(|d| d <= now))
+            .map(|(&class, _)| class)
+            .collect();
+        expired
+            .into_iter()
+            .filter_map(|class| self.open.remove(&class))
+            .flat_map(Self::parity_frames)
+            .collect()
+    }
+
+    /// Close every open group, emitting parity for the frames collected
+    pub fn flush(&mut self) -> Vec<Frame> {
+        let groups: Vec<_> = self.open.drain().map(|(_, g)| g).collect();
+        groups.into_iter().flat_map(Self::parity_frames).collect()
+    }
+
+    fn parity_frames(group: OpenGroup) -> Vec<Frame> {
+        let Some(template) = group.header else {
+            return Vec::new();
+        };
+        let data_shards = group.frames.len() as u8;
+        let params = FecParams {
+            data_shards,
+            ..group.params
+        };
+        let shards = data_shards_from(&group.frames);
+        let parity = encode(params, &shards);
+
+        parity
+            .into_iter()
+            .enumerate()
+            .map(|(index, shard)| {
+                let mut header = template.clone();
+                header.flags.set_fragment(false);
+                header.flags.set_repair(true);
+                header.set_seq(group.base_seq);
+
+                let mut extensions = Extensions::new();
+                extensions.redundancy_group = Some(group.id);
+
+                let mut payload = Vec::with_capacity(PARITY_HEADER_SIZE + shard.len());
+                payload.push(params.scheme.to_byte());
+                payload.push(params.data_shards);
+                payload.push(params.parity_shards);
+                payload.push(index as u8);
+                payload.extend_from_slice(&group.base_seq.to_le_bytes());
+                payload.extend_from_slice(&shard);
+
+                FrameBuilder::new(header)
+                    .extensions(extensions)
+                    .payload(payload)
+                    .build()
+            })
+            .collect()
+    }
+}
+
+impl Default for FecEncoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Identifies a group on the receive side
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct GroupKey {
+    node_id: NodeId,
+    class: PacketClass,
+    id: u16,
+}
+
+/// Shape of a group, learned from its first parity frame
+#[derive(Clone, Copy, Debug)]
+struct GroupLayout {
+    params: FecParams,
+    base_seq: u16,
+    shard_len: usize,
+}
+
+/// Frames seen for a group on the receive side
+struct ReceivedGroup {
+    data: HashMap<u16, Vec<u8>>,
+    layout: Option<GroupLayout>,
+    parity: HashMap<u8, Vec<u8>>,
+    done: bool,
+    deadline: Instant,
+}
+
+/// Recovers lost frames from parity
+pub struct FecDecoder {
+    groups: HashMap<GroupKey, ReceivedGroup>,
+    max_groups: usize,
+    timeout: Duration,
+    recovered: u64,
+}
+
+impl FecDecoder {
+    /// Create a decoder with the default limits
+    pub fn new() -> Self {
+        Self::with_limits(DEFAULT_MAX_FEC_GROUPS, FEC_GROUP_TIMEOUT)
+    }
+
+    /// Create a decoder with custom limits
+    pub fn with_limits(max_groups: usize, timeout: Duration) -> Self {
+        FecDecoder {
+            groups: HashMap::new(),
+            max_groups: max_groups.max(1),
+            timeout,
+            recovered: 0,
+        }
+    }
+
+    /// Record a received frame
+    ///
+    /// Data frames are kept for recovery; parity frames are consumed.
+    /// Returns any data frames rebuilt as a result.
+    pub fn receive(&mut self, frame: &Frame, now: Instant) -> ElaraResult<Vec<Frame>> {
+        let Some(id) = frame.extensions.redundancy_group else {
+            return Ok(Vec::new());
+        };
+        let key = GroupKey {
+            node_id: frame.header.node_id,
+            class: frame.header.class,
+            id,
+        };
+
+        let parity = if is_parity_frame(frame) {
+            Some(Self::parse_parity(&frame.payload)?)
+        } else {
+            None
+        };
+
+        if !self.groups.contains_key(&key) {
+            if self.groups.len() >= self.max_groups {
+                self.evict_oldest();
+            }
+            self.groups.insert(
+                key,
+                ReceivedGroup {
+                    data: HashMap::new(),
+                    layout: None,
+                    parity: HashMap::new(),
+                    done: false,
+                    deadline: now + self.timeout,
+                },
+            );
+        }
+        let group = self.groups.get_mut(&key).expect("inserted above");
+        if group.done {
+            return Ok(Vec::new());
+        }
+
+        match parity {
+            Some((layout, index, shard)) => {
+                if let Some(existing) = group.layout {
+                    if existing.params != layout.params
+                        || existing.base_seq != layout.base_seq
+                        || existing.shard_len != layout.shard_len
+                    {
+                        return Err(ElaraError::InvalidWireFormat(
+                            "Inconsistent FEC group parameters".into(),
+                        ));
+                    }
+                }
+                group.layout = Some(layout);
+                group.parity.insert(index, shard);
+            }
+            None => {
+                group.data.insert(frame.header.seq(), frame.serialize()?);
+            }
+        }
+
+        let recovered = Self::try_recover(group)?;
+        self.recovered += recovered.len() as u64;
+        Ok(recovered)
+    }
+
+    fn parse_parity(payload: &[u8]) -> ElaraResult<(GroupLayout, u8, Vec<u8>)> {
+        if payload.len() <= PARITY_HEADER_SIZE + 2 {
+            return Err(ElaraError::BufferTooShort {
+                expected: PARITY_HEADER_SIZE + 3,
+                actual: payload.len(),
+            });
+        }
+        let scheme = FecScheme::from_byte(payload[0]).ok_or_else(|| {
+            ElaraError::InvalidWireFormat(format!("Unknown FEC scheme: {}", payload[0]))
+        })?;
+        let (data_shards, parity_shards, index) = (payload[1], payload[2], payload[3]);
+        let valid = match scheme {
+            FecScheme::XorParity => parity_shards == 1,
+            FecScheme::ReedSolomon => data_shards as usize + parity_shards as usize <= 256,
+        };
+        if data_shards == 0 || parity_shards == 0 || index >= parity_shards || !valid {
+            return Err(ElaraError::InvalidWireFormat(format!(
+                "Invalid FEC group {}+{} (index {})",
+                data_shards, parity_shards, index
+            )));
+        }
+        let base_seq = u16::from_le_bytes([payload[4], payload[5]]);
+        let shard = payload[PARITY_HEADER_SIZE..].to_vec();
+        let layout = GroupLayout {
+            params: FecParams {
+                scheme,
+                data_shards,
+                parity_shards,
+            },
+            base_seq,
+            shard_len: shard.len(),
+        };
+        Ok((layout, index, shard))
+    }
+
+    fn try_recover(group: &mut ReceivedGroup) -> ElaraResult<Vec<Frame>> {
+        let Some(layout) = group.layout else {
+            return Ok(Vec::new());
+        };
+        let k = layout.params.data_shards as usize;
+        let mut shards: Vec<Option<Vec<u8>>> = (0..k)
+            .map(|i| {
+                let seq = layout.base_seq.wrapping_add(i as u16);
+                group
+                    .data
+                    .get(&seq)
+                    .map(|bytes| pad_shard(bytes, layout.shard_len))
+            })
+            .collect();
+        let missing: Vec<usize> = (0..k).filter(|&i| shards[i].is_none()).collect();
+        if missing.is_empty() {
+            group.done = true;
+            return Ok(Vec::new());
+        }
+        if missing.len() > group.parity.len() || shards.iter().flatten().any(|s| s.is_empty()) {
+            return Ok(Vec::new());
+        }
+
+        decode(layout.params, &mut shards, &group.parity)?;
+        group.done = true;
+
+        let mut frames = Vec::with_capacity(missing.len());
+        for i in missing {
+            let shard = shards[i].take().expect("decoded");
+            let len = u16::from_le_bytes([shard[0], shard[1]]) as usize;
+            if len + 2 > shard.len() {
+                continue;
+            }
+            if let Ok(frame) = Frame::parse(&shard[2..2 + len]) {
+                frames.push(frame);
+            }
+        }
+        Ok(frames)
+    }
+
+    /// Drop groups whose parity wait has expired
+    pub fn expire(&mut self, now: Instant) -> usize {
+        let before = self.groups.len();
+        self.groups.retain(|_, group| group.deadline > now);
+        before - self.groups.len()
+    }
+
+    fn evict_oldest(&mut self) {
+        let oldest = self
+            .groups
+            .iter()
+            .min_by_key(|(_, group)| group.deadline)
+            .map(|(key, _)| *key);
+        if let Some(key) = oldest {
+            self.groups.remove(&key);
+        }
+    }
+
+    /// Number of groups being tracked
+    pub fn group_count(&self) -> usize {
+        self.groups.len()
+    }
+
+    /// Total frames rebuilt from parity
+    pub fn recovered_count(&self) -> u64 {
+        self.recovered
+    }
+}
+
+impl Default for FecDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Length-prefix and zero-pad serialized frames to a common shard size
+fn data_shards_from(frames: &[Vec<u8>]) -> Vec<Vec<u8>> {
+    let shard_len = frames.iter().map(|f| f.len() + 2).max().unwrap_or(2);
+    frames.iter().map(|f| pad_shard(f, shard_len)).collect()
+}
+
+/// Build one data shard; empty if the frame does not fit `shard_len`
+fn pad_shard(frame: &[u8], shard_len: usize) -> Vec<u8> {
+    if frame.len() + 2 > shard_len {
+        return Vec::new();
+    }
+    let mut shard = Vec::with_capacity(shard_len);
+    shard.extend_from_slice(&(frame.len() as u16).to_le_bytes());
+    shard.extend_from_slice(frame);
+    shard.resize(shard_len, 0);
+    shard
+}
+
+/// Coefficient of data shard `i` in parity row `j`
+fn coefficient(params: FecParams, j: usize, i: usize) -> u8 {
+    match params.scheme {
+        FecScheme::XorParity => 1,
+        // Cauchy matrix: 1 / (x_j + y_i) with x_j = k + j, y_i = i
+        FecScheme::ReedSolomon => {
+            let x = (params.data_shards as usize + j) as u8;
+            gf::inv(x ^ i as u8)
+        }
+    }
+}
+
+/// Compute the parity shards for a full set of data shards
+fn encode(params: FecParams, data: &[Vec<u8>]) -> Vec<Vec<u8>> {
+    let shard_len = data.first().map_or(0, |s| s.len());
+    (0..params.parity_shards as usize)
+        .map(|j| {
+            let mut parity = vec![0u8; shard_len];
+            for (i, shard) in data.iter().enumerate() {
+                gf::mul_acc(&mut parity, shard, coefficient(params, j, i));
+            }
+            parity
+        })
+        .collect()
+}
+
+/// Fill in missing data shards from the available parity
+fn decode(
+    params: FecParams,
+    shards: &mut [Option<Vec<u8>>],
+    parity: &HashMap<u8, Vec<u8>>,
+) -> ElaraResult<()> {
+    let missing: Vec<usize> = (0..shards.len()).filter(|&i| shards[i].is_none()).collect();
+    let mut rows: Vec<u8> = parity.keys().copied().collect();
+    rows.sort_unstable();
+    rows.truncate(missing.len());
+
+    // Right-hand side: parity minus the contribution of known shards
+    let mut rhs: Vec<Vec<u8>> = rows
+        .iter()
+        .map(|&j| {
+            let mut acc = parity[&j].clone();
+            for (i, shard) in shards.iter().enumerate() {
+                if let Some(shard) = shard {
+                    gf::mul_acc(&mut acc, shard, coefficient(params, j as usize, i));
+                }
+            }
+            acc
+        })
+        .collect();
+    let mut matrix: Vec<Vec<u8>> = rows
+        .iter()
+        .map(|&j| {
+            missing
+                .iter()
+                .map(|&i| coefficient(params, j as usize, i))
+                .collect()
+        })
+        .collect();
+
+    // Gauss-Jordan elimination over GF(2^8)
+    let n = missing.len();
+    for col in 0..n {
+        let pivot = (col..n)
+            .find(|&r| matrix[r][col] != 0)
+            .ok_or_else(|| ElaraError::InvalidWireFormat("Singular FEC matrix".into()))?;
+        matrix.swap(col, pivot);
+        rhs.swap(col, pivot);
+
+        let scale = gf::inv(matrix[col][col]);
+        for v in matrix[col].iter_mut() {
+            *v = gf::mul(*v, scale);
+        }
+        let pivot_rhs = std::mem::take(&mut rhs[col]);
+        let mut scaled = vec![0u8; pivot_rhs.len()];
+        gf::mul_acc(&mut scaled, &pivot_rhs, scale);
+        rhs[col] = scaled;
+
+        for r in 0..n {
+            let factor = matrix[r][col];
+            if r == col || factor == 0 {
+                continue;
+            }
+            let pivot_row = matrix[col].clone();
+            for (v, p) in matrix[r].iter_mut().zip(pivot_row) {
+                *v ^= gf::mul(p, factor);
+            }
+            let pivot_rhs = rhs[col].clone();
+            gf::mul_acc(&mut rhs[r], &pivot_rhs, factor);
+        }
+    }
+
+    for (slot, value) in missing.into_iter().zip(rhs) {
+        shards[slot] = Some(value);
+    }
+    Ok(())
+}
+
+/// GF(2^8) arithmetic with the 0x11D polynomial
+mod gf {
+    const fn tables() -> ([u8; 512], [u8; 256]) {
+        let mut exp = [0u8; 512];
+        let mut log = [0u8; 256];
+        let mut x: u16 = 1;
+        let mut i = 0;
+        while i < 255 {
+            exp[i] = x as u8;
+            exp[i + 255] = x as u8;
+            log[x as usize] = i as u8;
+            x <<= 1;
+            if x & 0x100 != 0 {
+                x ^= 0x11D;
+            }
+            i += 1;
+        }
+        (exp, log)
+    }
+
+    const TABLES: ([u8; 512], [u8; 256]) = tables();
+    const EXP: [u8; 512] = TABLES.0;
+    const LOG: [u8; 256] = TABLES.1;
+
+    pub fn mul(a: u8, b: u8) -> u8 {
+        if a == 0 || b == 0 {
+            return 0;
+        }
+        EXP[LOG[a as usize] as usize + LOG[b as usize] as usize]
+    }
+
+    pub fn inv(a: u8) -> u8 {
+        debug_assert!(a != 0, "zero has no inverse");
+        EXP[255 - LOG[a as usize] as usize]
+    }
+
+    /// dst += src * coef
+    pub fn mul_acc(dst: &mut [u8], src: &[u8], coef: u8) {
+        match coef {
+            0 => {}
+            1 => {
+                for (d, s) in dst.iter_mut().zip(src) {
+                    *d ^= s;
+                }
+            }
+            _ => {
+                for (d, s) in dst.iter_mut().zip(src) {
+                    *d ^= mul(*s, coef);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FixedHeader;
+    use elara_core::SessionId;
+
+    /// Tag and push `count` frames, returning data and parity frames
+    fn build_group(encoder: &mut FecEncoder, count: usize) -> (Vec<Frame>, Vec<Frame>) {
+        let mut data = Vec::new();
+        let mut parity = Vec::new();
+        for i in 0..count {
+            let mut header = FixedHeader::new(SessionId::new(1), NodeId::new(5));
+            header.class = PacketClass::Perceptual;
+            header.set_seq(100 + i as u16);
+            let mut ext = Extensions::new();
+            ext.redundancy_group = encoder.group_for(PacketClass::Perceptual);
+            let frame = FrameBuilder::new(header)
+                .extensions(ext)
+                .payload(vec![i as u8; 10 + i * 7])
+                .auth_tag([i as u8; AUTH_TAG_SIZE])
+                .build();
+            parity.extend(encoder.push(&frame, Instant::now()).unwrap());
+            data.push(frame);
+        }
+        (data, parity)
+    }
+
+    #[test]
+    fn test_gf_inverse() {
+        for a in 1..=255u8 {
+            assert_eq!(gf::mul(a, gf::inv(a)), 1);
+        }
+    }
+
+    #[test]
+    fn test_loss_adaptive_params() {
+        assert_eq!(FecParams::for_loss_rate(0.0), None);
+        assert_eq!(FecParams::for_loss_rate(0.02), Some(FecParams::xor(8)));
+        assert_eq!(
+            FecParams::for_loss_rate(0.15),
+            Some(FecParams::reed_solomon(4, 2))
+        );
+        assert!(
+            FecParams::for_loss_rate(0.3).unwrap().overhead()
+                > FecParams::for_loss_rate(0.07).unwrap().overhead()
+        );
+
+        let mut encoder = FecEncoder::new();
+        assert!(encoder.group_for(PacketClass::Perceptual).is_none());
+        encoder.set_loss_rate(0.07);
+        assert!(encoder.group_for(PacketClass::Enhancement).is_none());
+        assert!(encoder.group_for(PacketClass::Perceptual).is_some());
+    }
+
+    #[test]
+    fn test_xor_recovers_single_loss() {
+        let mut encoder = FecEncoder::new();
+        encoder.set_params(Some(FecParams::xor(4)));
+        let (data, parity) = build_group(&mut encoder, 4);
+        assert_eq!(parity.len(), 1);
+        assert!(is_parity_frame(&parity[0]));
+
+        let mut decoder = FecDecoder::new();
+        let now = Instant::now();
+        for (i, frame) in data.iter().enumerate() {
+            if i != 2 {
+                assert!(decoder.receive(frame, now).unwrap().is_empty());
+            }
+        }
+        let recovered = decoder.receive(&parity[0], now).unwrap();
+        assert_eq!(recovered.len(), 1);
+        assert_eq!(
+            recovered[0].serialize().unwrap(),
+            data[2].serialize().unwrap()
+        );
+        assert_eq!(decoder.recovered_count(), 1);
+    }
+
+    #[test]
+    fn test_reed_solomon_recovers_multiple_losses() {
+        let mut encoder = FecEncoder::new();
+        encoder.set_params(Some(FecParams::reed_solomon(4, 2)));
+        let (data, parity) = build_group(&mut encoder, 4);
+        assert_eq!(parity.len(), 2);
+
+        // Parity may arrive before the surviving data frames
+        let mut decoder = FecDecoder::new();
+        let now = Instant::now();
+        assert!(decoder.receive(&parity[1], now).unwrap().is_empty());
+        assert!(decoder.receive(&data[0], now).unwrap().is_empty());
+        assert!(decoder.receive(&parity[0], now).unwrap().is_empty());
+        let mut recovered = decoder.receive(&data[3], now).unwrap();
+        recovered.sort_by_key(|f| f.header.seq());
+        assert_eq!(recovered.len(), 2);
+        assert_eq!(
+            recovered[0].serialize().unwrap(),
+            data[1].serialize().unwrap()
+        );
+        assert_eq!(
+            recovered[1].serialize().unwrap(),
+            data[2].serialize().unwrap()
+        );
+
+        // Group is finished; late frames do not trigger another recovery
+        assert!(decoder.receive(&data[1], now).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_partial_group_flushed_after_delay() {
+        let mut encoder = FecEncoder::new();
+        encoder.set_params(Some(FecParams::xor(8)));
+        let (data, parity) = build_group(&mut encoder, 3);
+        assert!(parity.is_empty());
+
+        let now = Instant::now();
+        assert!(encoder.flush_expired(now).is_empty());
+        let parity = encoder.flush_expired(now + FEC_FLUSH_DELAY);
+        assert_eq!(parity.len(), 1);
+        assert_eq!(parity[0].header.class, PacketClass::Perceptual);
+        assert!(encoder.flush_expired(now + FEC_FLUSH_DELAY).is_empty());
+
+        // Three data frames plus parity still recover one loss
+        let mut decoder = FecDecoder::new();
+        decoder.receive(&data[0], now).unwrap();
+        decoder.receive(&data[2], now).unwrap();
+        let recovered = decoder.receive(&parity[0], now).unwrap();
+        assert_eq!(recovered.len(), 1);
+        assert_eq!(
+            recovered[0].serialize().unwrap(),
+            data[1].serialize().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_too_many_losses_and_expiry() {
+        let mut encoder = FecEncoder::new();
+        encoder.set_params(Some(FecParams::xor(4)));
+        let (data, parity) = build_group(&mut encoder, 4);
+
+        let mut decoder = FecDecoder::new();
+        let now = Instant::now();
+        decoder.receive(&data[0], now).unwrap();
+        decoder.receive(&data[1], now).unwrap();
+        assert!(decoder.receive(&parity[0], now).unwrap().is_empty());
+        assert_eq!(decoder.expire(now + FEC_GROUP_TIMEOUT), 1);
+        assert_eq!(decoder.group_count(), 0);
+    }
+}
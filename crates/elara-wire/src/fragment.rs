@@ -0,0 +1,386 @@
+//! Frame fragmentation and reassembly
+//!
+//! Payloads too large for one frame are split across frames carrying the
+//! `FRAGMENT` flag and a [`FragmentInfo`] extension. Fragments of one
+//! message use consecutive sequence numbers, so the receiver groups them by
+//! `(node, class, seq - index)`.
+//!
+//! Incomplete messages are held for a class-dependent time: `Perceptual`
+//! data is stale almost immediately, while `Core` events must survive
+//! retransmission delays.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use elara_core::{ElaraError, ElaraResult, NodeId, PacketClass};
+
+use crate::{FragmentInfo, Frame, AUTH_TAG_SIZE, FIXED_HEADER_SIZE, MAX_FRAME_SIZE};
+
+/// Frame space kept free for extensions on fragmented frames
+pub const FRAGMENT_EXTENSION_RESERVE: usize = 32;
+
+/// Largest payload carried by a single fragment
+pub const MAX_FRAGMENT_PAYLOAD: usize =
+    MAX_FRAME_SIZE - FIXED_HEADER_SIZE - AUTH_TAG_SIZE - FRAGMENT_EXTENSION_RESERVE;
+
+/// Maximum fragments per message
+pub const MAX_FRAGMENTS: u16 = 64;
+
+/// Default number of messages being reassembled at once
+pub const DEFAULT_MAX_PENDING: usize = 32;
+
+/// Default time to wait for the rest of a `Perceptual` message
+pub const PERCEPTUAL_REASSEMBLY_TIMEOUT: Duration = Duration::from_millis(150);
+
+/// Default time to wait for the rest of a `Core` message
+pub const CORE_REASSEMBLY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Splits payloads into fragment-sized chunks
+#[derive(Clone, Copy, Debug)]
+pub struct Fragmenter {
+    max_payload: usize,
+}
+
+impl Fragmenter {
+    /// Create a fragmenter using [`MAX_FRAGMENT_PAYLOAD`]
+    pub fn new() -> Self {
+        Self::with_max_payload(MAX_FRAGMENT_PAYLOAD)
+    }
+
+    /// Create a fragmenter with a custom chunk size
+    pub fn with_max_payload(max_payload: usize) -> Self {
+        Fragmenter {
+            max_payload: max_payload.max(1),
+        }
+    }
+
+    /// Largest chunk produced
+    pub fn max_payload(&self) -> usize {
+        self.max_payload
+    }
+
+    /// Check if a payload must be split
+    pub fn needs_split(&self, payload_len: usize) -> bool {
+        payload_len > self.max_payload
+    }
+
+    /// Split a payload into numbered chunks
+    pub fn split<'a>(&self, payload: &'a [u8]) -> ElaraResult<Vec<(FragmentInfo, &'a [u8])>> {
+        let total = payload.len().div_ceil(self.max_payload).max(1);
+        if total > MAX_FRAGMENTS as usize {
+            return Err(ElaraError::InvalidWireFormat(format!(
+                "Payload needs {} fragments, max {}",
+                total, MAX_FRAGMENTS
+            )));
+        }
+        if payload.is_empty() {
+            return Ok(vec![(FragmentInfo::new(0, 1), payload)]);
+        }
+
+        Ok(payload
+            .chunks(self.max_payload)
+            .enumerate()
+            .map(|(i, chunk)| (FragmentInfo::new(i as u16, total as u16), chunk))
+            .collect())
+    }
+}
+
+impl Default for Fragmenter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Identifies the message a fragment belongs to
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct FragmentKey {
+    node_id: NodeId,
+    class: PacketClass,
+    base_seq: u16,
+}
+
+/// Message waiting for missing fragments
+struct PendingMessage {
+    /// First fragment (header and extensions of the rebuilt frame)
+    first: Option<Frame>,
+    chunks: Vec<Option<Vec<u8>>>,
+    received: u16,
+    deadline: Instant,
+}
+
+/// Bounded, timeout-driven reassembly buffer
+pub struct FragmentReassembler {
+    pending: HashMap<FragmentKey, PendingMessage>,
+    max_pending: usize,
+    perceptual_timeout: Duration,
+    core_timeout: Duration,
+    dropped: u64,
+}
+
+impl FragmentReassembler {
+    /// Create a reassembler with the default limits
+    pub fn new() -> Self {
+        Self::with_limits(
+            DEFAULT_MAX_PENDING,
+            PERCEPTUAL_REASSEMBLY_TIMEOUT,
+            CORE_REASSEMBLY_TIMEOUT,
+        )
+    }
+
+    /// Create a reassembler with custom limits
+    pub fn with_limits(
+        max_pending: usize,
+        perceptual_timeout: Duration,
+        core_timeout: Duration,
+    ) -> Self {
+        FragmentReassembler {
+            pending: HashMap::new(),
+            max_pending: max_pending.max(1),
+            perceptual_timeout,
+            core_timeout,
+            dropped: 0,
+        }
+    }
+
+    /// How long an incomplete message of this class is kept
+    pub fn timeout_for(&self, class: PacketClass) -> Duration {
+        match class {
+            PacketClass::Core | PacketClass::Repair => self.core_timeout,
+            PacketClass::Perceptual | PacketClass::Enhancement | PacketClass::Cosmetic => {
+                self.perceptual_timeout
+            }
+        }
+    }
+
+    /// Add a received frame
+    ///
+    /// Unfragmented frames pass straight through. Returns the rebuilt frame
+    /// once the last missing fragment of a message arrives.
+    pub fn insert(&mut self, frame: Frame, now: Instant) -> ElaraResult<Option<Frame>> {
+        let Some(info) = frame.extensions.fragment_info else {
+            return Ok(Some(frame));
+        };
+        if info.total == 0 || info.index >= info.total || info.total > MAX_FRAGMENTS {
+            return Err(ElaraError::InvalidWireFormat(format!(
+                "Invalid fragment {}/{}",
+                info.index, info.total
+            )));
+        }
+
+        let class = frame.header.class;
+        let key = FragmentKey {
+            node_id: frame.header.node_id,
+            class,
+            base_seq: frame.header.seq().wrapping_sub(info.index),
+        };
+
+        if let Some(entry) = self.pending.get(&key) {
+            if entry.chunks.len() != info.total as usize {
+                self.pending.remove(&key);
+                self.dropped += 1;
+                return Err(ElaraError::InvalidWireFormat(
+                    "Fragment count changed mid-message".into(),
+                ));
+            }
+        } else {
+            if self.pending.len() >= self.max_pending {
+                self.evict_oldest();
+            }
+            let deadline = now + self.timeout_for(class);
+            self.pending.insert(
+                key,
+                PendingMessage {
+                    first: None,
+                    chunks: vec![None; info.total as usize],
+                    received: 0,
+                    deadline,
+                },
+            );
+        }
+
+        let entry = self.pending.get_mut(&key).expect("inserted above");
+        let slot = &mut entry.chunks[info.index as usize];
+        if slot.is_some() {
+            // Duplicate
+            return Ok(None);
+        }
+        entry.received += 1;
+        if info.is_first() {
+            *slot = Some(Vec::new());
+            entry.first = Some(frame);
+        } else {
+            *slot = Some(frame.payload);
+        }
+
+        if entry.received < info.total {
+            return Ok(None);
+        }
+
+        let entry = self.pending.remove(&key).expect("present");
+        let mut rebuilt = entry.first.expect("all fragments received");
+        for chunk in entry.chunks.into_iter().skip(1).flatten() {
+            rebuilt.payload.extend_from_slice(&chunk);
+        }
+        rebuilt.extensions.fragment_info = None;
+        rebuilt.header.flags.set_fragment(false);
+        Ok(Some(rebuilt))
+    }
+
+    /// Drop messages whose deadline has passed
+    ///
+    /// Returns the number of messages dropped.
+    pub fn expire(&mut self, now: Instant) -> usize {
+        let before = self.pending.len();
+        self.pending.retain(|_, entry| entry.deadline > now);
+        let expired = before - self.pending.len();
+        self.dropped += expired as u64;
+        expired
+    }
+
+    /// Evict the message closest to its deadline
+    fn evict_oldest(&mut self) {
+        let oldest = self
+            .pending
+            .iter()
+            .min_by_key(|(_, entry)| entry.deadline)
+            .map(|(key, _)| *key);
+        if let Some(key) = oldest {
+            self.pending.remove(&key);
+            self.dropped += 1;
+        }
+    }
+
+    /// Number of incomplete messages
+    pub fn pending_count(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Incomplete messages dropped by timeout, eviction or inconsistency
+    pub fn dropped_count(&self) -> u64 {
+        self.dropped
+    }
+}
+
+impl Default for FragmentReassembler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Extensions, FixedHeader, FrameBuilder};
+    use elara_core::SessionId;
+
+    fn fragment_frames(node: u64, class: PacketClass, base_seq: u16, payload: &[u8]) -> Vec<Frame> {
+        Fragmenter::with_max_payload(4)
+            .split(payload)
+            .unwrap()
+            .into_iter()
+            .map(|(info, chunk)| {
+                let mut header = FixedHeader::new(SessionId::new(1), NodeId::new(node));
+                header.class = class;
+                header.set_seq(base_seq.wrapping_add(info.index));
+                header.flags.set_fragment(true);
+                let mut ext = Extensions::new();
+                ext.fragment_info = Some(info);
+                FrameBuilder::new(header)
+                    .extensions(ext)
+                    .payload(chunk.to_vec())
+                    .build()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_split_sizes() {
+        let fragmenter = Fragmenter::new();
+        let payload = vec![7u8; MAX_FRAGMENT_PAYLOAD * 2 + 1];
+        let parts = fragmenter.split(&payload).unwrap();
+        assert_eq!(parts.len(), 3);
+        assert!(parts.iter().all(|(info, _)| info.total == 3));
+        assert_eq!(parts[2].1.len(), 1);
+        assert!(fragmenter.needs_split(payload.len()));
+        assert!(!fragmenter.needs_split(MAX_FRAGMENT_PAYLOAD));
+
+        let too_big = vec![0u8; MAX_FRAGMENT_PAYLOAD * MAX_FRAGMENTS as usize + 1];
+        assert!(fragmenter.split(&too_big).is_err());
+    }
+
+    #[test]
+    fn test_reassemble_out_of_order() {
+        let mut reassembler = FragmentReassembler::new();
+        let now = Instant::now();
+        let payload: Vec<u8> = (0..15).collect();
+        let mut frames = fragment_frames(9, PacketClass::Core, u16::MAX - 1, &payload);
+        assert_eq!(frames.len(), 4);
+        frames.reverse();
+
+        let last = frames.pop().unwrap();
+        for frame in frames {
+            assert!(reassembler.insert(frame, now).unwrap().is_none());
+        }
+        let rebuilt = reassembler.insert(last, now).unwrap().unwrap();
+        assert_eq!(rebuilt.payload, payload);
+        assert_eq!(rebuilt.header.seq(), u16::MAX - 1);
+        assert!(!rebuilt.header.flags.is_fragment());
+        assert!(rebuilt.extensions.fragment_info.is_none());
+        assert_eq!(reassembler.pending_count(), 0);
+    }
+
+    #[test]
+    fn test_perceptual_expires_before_core() {
+        let mut reassembler = FragmentReassembler::new();
+        let now = Instant::now();
+        let payload = [1u8; 10];
+
+        let core = fragment_frames(1, PacketClass::Core, 0, &payload);
+        let perceptual = fragment_frames(1, PacketClass::Perceptual, 0, &payload);
+        reassembler.insert(core[0].clone(), now).unwrap();
+        reassembler.insert(perceptual[0].clone(), now).unwrap();
+        assert_eq!(reassembler.pending_count(), 2);
+
+        assert_eq!(reassembler.expire(now + Duration::from_secs(1)), 1);
+        assert!(reassembler
+            .insert(perceptual[1].clone(), now + Duration::from_secs(1))
+            .unwrap()
+            .is_none());
+        assert_eq!(reassembler.expire(now + Duration::from_secs(10)), 2);
+        assert_eq!(reassembler.dropped_count(), 3);
+    }
+
+    #[test]
+    fn test_bounded_pending() {
+        let mut reassembler = FragmentReassembler::with_limits(
+            2,
+            PERCEPTUAL_REASSEMBLY_TIMEOUT,
+            CORE_REASSEMBLY_TIMEOUT,
+        );
+        let now = Instant::now();
+        let payload = [0u8; 8];
+
+        let core = fragment_frames(1, PacketClass::Core, 0, &payload);
+        let perceptual = fragment_frames(2, PacketClass::Perceptual, 0, &payload);
+        let newer = fragment_frames(3, PacketClass::Core, 0, &payload);
+        reassembler.insert(core[0].clone(), now).unwrap();
+        reassembler.insert(perceptual[0].clone(), now).unwrap();
+        reassembler.insert(newer[0].clone(), now).unwrap();
+
+        // The perceptual message had the nearest deadline
+        assert_eq!(reassembler.pending_count(), 2);
+        assert!(reassembler.insert(core[1].clone(), now).unwrap().is_some());
+        assert!(reassembler
+            .insert(perceptual[1].clone(), now)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_invalid_fragment_rejected() {
+        let mut reassembler = FragmentReassembler::new();
+        let mut frame = fragment_frames(1, PacketClass::Core, 0, &[0u8; 8]).remove(0);
+        frame.extensions.fragment_info = Some(FragmentInfo::new(2, 2));
+        assert!(reassembler.insert(frame, Instant::now()).is_err());
+    }
+}
@@ -56,6 +56,11 @@ impl Frame {
                 "Header length exceeds frame".into(),
             ));
         }
+        if (header.header_len as usize) < FIXED_HEADER_SIZE {
+            return Err(ElaraError::InvalidWireFormat(
+                "Header length shorter than fixed header".into(),
+            ));
+        }
 
         // Parse extensions if present
         let extensions =
@@ -30,7 +30,7 @@ pub enum CryptoSuite {
     Suite0 = 0,
     /// X25519 + AES-256-GCM + Ed25519
     Suite1 = 1,
-    /// Reserved for post-quantum
+    /// X25519 + ML-KEM-768 hybrid + ChaCha20-Poly1305 + Ed25519
     Suite2 = 2,
 }
 
@@ -3,15 +3,26 @@
 //! This crate implements the wire format for ELARA packets:
 //! - Fixed header (30 bytes)
 //! - Variable header extensions (TLV)
-//! - Encrypted payload
+//! - Encrypted (optionally compressed) payload
 //! - Auth tag (AEAD)
+//! - Fragmentation of payloads larger than one frame
+//! - Forward error correction over groups of frames
+//! - Loss and reorder accounting from sequence numbers
 
+pub mod compression;
 pub mod extensions;
+pub mod fec;
 pub mod flags;
+pub mod fragment;
 pub mod frame;
 pub mod header;
+pub mod sequence;
 
+pub use compression::*;
 pub use extensions::*;
+pub use fec::*;
 pub use flags::*;
+pub use fragment::*;
 pub use frame::*;
 pub use header::*;
+pub use sequence::*;
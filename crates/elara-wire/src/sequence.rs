@@ -0,0 +1,282 @@
+//! Sequence tracking - loss and reorder from received sequence numbers
+//!
+//! Senders number their frames per packet class with the 16-bit
+//! `FixedHeader::seq()`. The receiver keeps, for each (sender, class), a
+//! window over the last `SEQUENCE_WINDOW` numbers up to the highest seen:
+//! - A number still missing when it slides out of the window is lost
+//! - A number arriving below the highest is reordered by the distance
+//! - Loss rate and reorder depth are read over the window, so they recover
+//!   once the network does
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use elara_core::{NodeId, PacketClass};
+
+/// Sequence numbers a window covers, up to and including the highest seen
+pub const SEQUENCE_WINDOW: u16 = 64;
+
+/// What a received sequence number says about its stream
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Arrival {
+    /// Beyond everything seen before
+    InOrder,
+    /// Behind the highest number seen by this much, filling a gap
+    Reordered(u16),
+    /// Seen before
+    Duplicate,
+    /// Behind the window, already counted as lost
+    Late,
+}
+
+/// Received sequence numbers of one (sender, class) stream
+#[derive(Clone, Debug)]
+pub struct SequenceWindow {
+    /// Highest number seen, with wraparound
+    highest: u16,
+    /// Bit `i` is set if `highest - i` arrived
+    received: u64,
+    /// Positions of the window the stream has reached so far
+    span: u16,
+    /// Reorder depth of recent arrivals, zero for in order
+    depths: VecDeque<u16>,
+    /// Numbers that left the window since last taken
+    settled: u64,
+    /// How many of those never arrived
+    lost: u64,
+}
+
+impl SequenceWindow {
+    /// Start a window at the first number received
+    pub fn new(seq: u16) -> Self {
+        SequenceWindow {
+            highest: seq,
+            received: 1,
+            span: 1,
+            depths: VecDeque::from([0]),
+            settled: 0,
+            lost: 0,
+        }
+    }
+
+    /// Record a received number
+    pub fn observe(&mut self, seq: u16) -> Arrival {
+        let ahead = seq.wrapping_sub(self.highest) as i16;
+        if ahead == 0 {
+            return Arrival::Duplicate;
+        }
+        if ahead > 0 {
+            self.advance(ahead as u16);
+            self.highest = seq;
+            self.push_depth(0);
+            return Arrival::InOrder;
+        }
+
+        let depth = ahead.unsigned_abs();
+        if depth >= SEQUENCE_WINDOW {
+            return Arrival::Late;
+        }
+        let bit = 1u64 << depth;
+        if self.received & bit != 0 {
+            return Arrival::Duplicate;
+        }
+        self.received |= bit;
+        self.span = self.span.max(depth + 1);
+        self.push_depth(depth);
+        Arrival::Reordered(depth)
+    }
+
+    /// Slide the window `by` numbers, settling those that leave it
+    fn advance(&mut self, by: u16) {
+        let leaving = by.min(SEQUENCE_WINDOW);
+        for position in SEQUENCE_WINDOW - leaving..SEQUENCE_WINDOW {
+            if position < self.span {
+                self.settled += 1;
+                if self.received & (1u64 << position) == 0 {
+                    self.lost += 1;
+                }
+            }
+        }
+        // Numbers skipped past the window entirely never arrived
+        let skipped = (by - leaving) as u64;
+        self.settled += skipped;
+        self.lost += skipped;
+
+        self.received = self.received.checked_shl(by as u32).unwrap_or(0) | 1;
+        self.span = self.span.saturating_add(by).min(SEQUENCE_WINDOW);
+    }
+
+    fn push_depth(&mut self, depth: u16) {
+        if self.depths.len() == SEQUENCE_WINDOW as usize {
+            self.depths.pop_front();
+        }
+        self.depths.push_back(depth);
+    }
+
+    /// Fraction of the numbers in the window that have not arrived
+    pub fn loss_rate(&self) -> f64 {
+        self.missing() as f64 / self.span as f64
+    }
+
+    /// Numbers in the window that have not arrived
+    pub fn missing(&self) -> u16 {
+        self.span - (self.received & Self::span_mask(self.span)).count_ones() as u16
+    }
+
+    /// Deepest reorder among recent arrivals
+    pub fn reorder_depth(&self) -> u16 {
+        self.depths.iter().copied().max().unwrap_or(0)
+    }
+
+    /// Lost and total numbers that left the window since last taken
+    pub fn take_settled(&mut self) -> (u64, u64) {
+        (
+            std::mem::take(&mut self.lost),
+            std::mem::take(&mut self.settled),
+        )
+    }
+
+    fn span_mask(span: u16) -> u64 {
+        1u64.checked_shl(span as u32)
+            .map_or(u64::MAX, |bit| bit - 1)
+    }
+}
+
+/// Sequence windows for every (sender, class) stream received
+#[derive(Clone, Debug, Default)]
+pub struct SequenceTracker {
+    windows: HashMap<(NodeId, PacketClass), SequenceWindow>,
+}
+
+impl SequenceTracker {
+    pub fn new() -> Self {
+        SequenceTracker::default()
+    }
+
+    /// Record `seq` received from `node` on `class`
+    pub fn observe(&mut self, node: NodeId, class: PacketClass, seq: u16) -> Arrival {
+        match self.windows.get_mut(&(node, class)) {
+            Some(window) => window.observe(seq),
+            None => {
+                self.windows.insert((node, class), SequenceWindow::new(seq));
+                Arrival::InOrder
+            }
+        }
+    }
+
+    /// Window of one stream
+    pub fn window(&self, node: NodeId, class: PacketClass) -> Option<&SequenceWindow> {
+        self.windows.get(&(node, class))
+    }
+
+    /// Fraction of numbers missing across every window
+    pub fn loss_rate(&self) -> f64 {
+        let (missing, span) = self.windows.values().fold((0u64, 0u64), |acc, window| {
+            (acc.0 + window.missing() as u64, acc.1 + window.span as u64)
+        });
+        if span == 0 {
+            return 0.0;
+        }
+        missing as f64 / span as f64
+    }
+
+    /// Deepest recent reorder across every window
+    pub fn reorder_depth(&self) -> u16 {
+        self.windows
+            .values()
+            .map(SequenceWindow::reorder_depth)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Lost and total numbers that left any window since last taken
+    pub fn take_settled(&mut self) -> (u64, u64) {
+        self.windows
+            .values_mut()
+            .map(SequenceWindow::take_settled)
+            .fold((0, 0), |acc, (lost, settled)| {
+                (acc.0 + lost, acc.1 + settled)
+            })
+    }
+
+    /// Senders with at least one window
+    pub fn nodes(&self) -> HashSet<NodeId> {
+        self.windows.keys().map(|&(node, _)| node).collect()
+    }
+
+    /// Lost and total numbers from `node` that left its windows since last
+    /// taken
+    pub fn take_settled_from(&mut self, node: NodeId) -> (u64, u64) {
+        self.windows
+            .iter_mut()
+            .filter(|((sender, _), _)| *sender == node)
+            .map(|(_, window)| window.take_settled())
+            .fold((0, 0), |acc, (lost, settled)| {
+                (acc.0 + lost, acc.1 + settled)
+            })
+    }
+
+    /// Deepest recent reorder across `node`'s windows
+    pub fn reorder_depth_from(&self, node: NodeId) -> u16 {
+        self.windows
+            .iter()
+            .filter(|((sender, _), _)| *sender == node)
+            .map(|(_, window)| window.reorder_depth())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Stop tracking a sender
+    pub fn remove_node(&mut self, node: NodeId) {
+        self.windows.retain(|&(sender, _), _| sender != node);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_loss_and_reorder_across_wraparound() {
+        let mut window = SequenceWindow::new(u16::MAX - 2);
+        assert_eq!(window.observe(u16::MAX - 1), Arrival::InOrder);
+        // u16::MAX and 0 are late
+        assert_eq!(window.observe(1), Arrival::InOrder);
+        assert_eq!(window.missing(), 2);
+        assert_eq!(window.observe(u16::MAX), Arrival::Reordered(2));
+        assert_eq!(window.observe(u16::MAX), Arrival::Duplicate);
+        assert_eq!(window.reorder_depth(), 2);
+        assert_eq!(window.missing(), 1);
+        assert!((window.loss_rate() - 0.2).abs() < 1e-9);
+
+        // Slide the gap out of the window: it settles as lost
+        assert_eq!(window.take_settled(), (0, 0));
+        assert_eq!(window.observe(1 + SEQUENCE_WINDOW), Arrival::InOrder);
+        assert_eq!(window.take_settled(), (1, 5));
+        assert_eq!(window.observe(0), Arrival::Late);
+        assert_eq!(window.missing(), SEQUENCE_WINDOW - 1);
+    }
+
+    #[test]
+    fn test_tracker_per_sender_and_class() {
+        let mut tracker = SequenceTracker::new();
+        let (alice, bob) = (NodeId::new(1), NodeId::new(2));
+
+        for seq in 0..10 {
+            tracker.observe(alice, PacketClass::Perceptual, seq);
+            tracker.observe(bob, PacketClass::Perceptual, seq * 2);
+        }
+        tracker.observe(alice, PacketClass::Core, 500);
+        tracker.observe(alice, PacketClass::Core, 499);
+
+        let window = tracker.window(bob, PacketClass::Perceptual).unwrap();
+        assert_eq!(window.missing(), 9);
+        assert_eq!(tracker.nodes(), HashSet::from([alice, bob]));
+        assert_eq!(tracker.reorder_depth(), 1);
+        assert_eq!(tracker.reorder_depth_from(bob), 0);
+        assert!((tracker.loss_rate() - 9.0 / 31.0).abs() < 1e-9);
+
+        tracker.remove_node(bob);
+        assert_eq!(tracker.loss_rate(), 0.0);
+        assert!(tracker.window(alice, PacketClass::Core).is_some());
+    }
+}